@@ -0,0 +1,39 @@
+#ifndef LYNX_H
+#define LYNX_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define LYNX_OK 0
+
+#define LYNX_NO_SOLUTION 1
+
+#define LYNX_INVALID_ARGUMENT -1
+
+typedef struct LynxMatrix LynxMatrix;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+LynxMatrix *lynx_matrix_new(uint32_t primary_columns, uint32_t secondary_columns);
+
+int lynx_matrix_push_row(LynxMatrix *matrix,
+                         const uint32_t *columns,
+                         size_t length,
+                         size_t *row);
+
+int lynx_matrix_solve(const LynxMatrix *matrix, size_t *rows, size_t capacity, size_t *length);
+
+void lynx_matrix_free(LynxMatrix *matrix);
+
+int lynx_sudoku_solve(const char *puzzle, char *output);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* LYNX_H */
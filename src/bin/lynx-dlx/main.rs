@@ -0,0 +1,246 @@
+use lynx::cover::Problem;
+use lynx::writer::{CsvWriter, JsonLinesWriter, SolutionWriter};
+use lynx::DLXMatrix;
+
+use clap::{Parser, ValueEnum};
+use core::fmt;
+use std::fs;
+use std::io;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+// The instance was well-formed, but has no solution
+const EXIT_UNSOLVABLE: i32 = 2;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "lynx-dlx",
+    version,
+    about = "Solve generic exact cover problems",
+    long_about = "Solve generic exact cover problems.\n\n\
+                  By default the input is in Knuth's format: a line listing the items, with any \
+                  secondary items (which may be left uncovered) after a '|', then one line per \
+                  option listing its items. Lines starting with '|' are comments. As CSV, the \
+                  first row names the items and each further row is an option, with a 1 under \
+                  each of its items and 0 elsewhere. As JSON, the instance is an object giving \
+                  the number of primary \"columns\" and \"secondary\" columns, and the \"rows\", \
+                  each an object listing its \"cols\" by index with an optional \"label\".\n\n\
+                  Each solution is printed as its options, one per line, and solutions are \
+                  separated by blank lines. Rows of a JSON instance are printed as their labels, \
+                  or their columns if they have none. With --output csv or json, each solution is \
+                  instead written on one line as it's found, as a CSV record of its options or a \
+                  JSON object {\"rows\": [...], \"labels\": [...]}.\n\n\
+                  Exits with status 0 on success, 2 if there is no solution, and 1 if the input \
+                  couldn't be read or parsed."
+)]
+struct Cli {
+    #[arg(
+        value_name = "FILE",
+        help = "File containing the instance, or - for standard input [default: -]"
+    )]
+    file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Input format [default: csv or json for files ending in .csv or .json, otherwise \
+                knuth]"
+    )]
+    format: Option<Format>,
+
+    #[arg(
+        long,
+        conflicts_with = "count",
+        help = "Print every solution, rather than the first"
+    )]
+    all: bool,
+
+    #[arg(
+        long,
+        value_name = "LIMIT",
+        num_args = 0..=1,
+        require_equals = true,
+        help = "Print only the number of solutions, counting up to LIMIT if given"
+    )]
+    count: Option<Option<usize>>,
+
+    #[arg(
+        long,
+        help = "Print the index of each chosen option, counting from 0, rather than its items"
+    )]
+    indices: bool,
+
+    #[arg(
+        long,
+        value_enum,
+        default_value = "text",
+        conflicts_with = "count",
+        help = "Output format"
+    )]
+    output: Output,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Knuth,
+    Csv,
+    Json,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Output {
+    Text,
+    Csv,
+    Json,
+}
+
+fn main() {
+    // A malformed command line fails like an unreadable matrix, with status 1, since clap's usual
+    // status 2 would read as a matrix with no exact cover
+    let cli = Cli::try_parse().unwrap_or_else(|error| {
+        let _ = error.print();
+        process::exit(if error.use_stderr() { 1 } else { 0 });
+    });
+
+    let path = cli.file.as_deref().unwrap_or(Path::new("-"));
+    let text = read(path).unwrap_or_else(|error| fail(format_args!("{}: {}", name(path), error)));
+
+    let format = cli.format.unwrap_or_else(|| {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => Format::Csv,
+            Some("json") => Format::Json,
+            _ => Format::Knuth,
+        }
+    });
+
+    // Each option is printed from its description, worked out up front since solving consumes
+    // the matrix
+    let (matrix, descriptions) = match format {
+        Format::Knuth | Format::Csv => {
+            let problem = match format {
+                Format::Knuth => Problem::parse_knuth(&text),
+                _ => Problem::parse_csv(&text),
+            }
+            .unwrap_or_else(|error| fail(format_args!("{}: {}", name(path), error)));
+
+            let descriptions = (0..problem.options().len())
+                .map(|option| problem.option_names(option).collect::<Vec<_>>().join(" "))
+                .collect();
+            (problem.matrix(), descriptions)
+        }
+        Format::Json => {
+            let (matrix, labels) = DLXMatrix::<u32>::from_json(&text)
+                .unwrap_or_else(|error| fail(format_args!("{}: {}", name(path), error)));
+
+            let descriptions: Vec<String> = labels
+                .into_iter()
+                .enumerate()
+                .map(|(row, label)| {
+                    label.unwrap_or_else(|| {
+                        let columns: Vec<String> = matrix
+                            .row(row)
+                            .iter()
+                            .map(|(column, _)| column.to_string())
+                            .collect();
+                        columns.join(" ")
+                    })
+                })
+                .collect();
+            (matrix, descriptions)
+        }
+    };
+
+    let mut out = io::BufWriter::new(io::stdout().lock());
+
+    let solved = if let Some(limit) = cli.count {
+        let count = matrix.count_solutions(limit.unwrap_or(usize::MAX));
+        write_or_fail(writeln!(out, "{}", count));
+        count > 0
+    } else {
+        let solutions = matrix
+            .solutions()
+            .row_indices()
+            .take(if cli.all { usize::MAX } else { 1 });
+
+        // Options are written as indices in every format if asked, and otherwise as their
+        // descriptions, which serve as labels for CSV and JSON
+        let labels = match cli.indices {
+            true => vec![],
+            false => descriptions.into_iter().map(Some).collect(),
+        };
+
+        let count = match cli.output {
+            Output::Text => write_text(&mut out, solutions, &labels),
+            Output::Csv => CsvWriter::with_labels(&mut out, labels).write_solutions(solutions),
+            Output::Json => {
+                JsonLinesWriter::with_labels(&mut out, labels).write_solutions(solutions)
+            }
+        }
+        .unwrap_or_else(|error| fail(format_args!("<stdout>: {}", error)));
+
+        count > 0
+    };
+
+    write_or_fail(out.flush());
+
+    if !solved {
+        eprintln!("lynx-dlx: no solution");
+        process::exit(EXIT_UNSOLVABLE);
+    }
+}
+
+// Each option on its own line, with blank lines between solutions
+fn write_text<W: Write>(
+    out: &mut W,
+    solutions: impl Iterator<Item = Vec<usize>>,
+    labels: &[Option<String>],
+) -> io::Result<usize> {
+    let mut count = 0;
+
+    for mut options in solutions {
+        options.sort_unstable();
+        if count > 0 {
+            writeln!(out)?;
+        }
+        count += 1;
+
+        for option in options {
+            match labels.get(option) {
+                Some(Some(label)) => writeln!(out, "{}", label)?,
+                _ => writeln!(out, "{}", option)?,
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+fn read(path: &Path) -> io::Result<String> {
+    if path == Path::new("-") {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+fn name(path: &Path) -> String {
+    if path == Path::new("-") {
+        String::from("<stdin>")
+    } else {
+        path.display().to_string()
+    }
+}
+
+fn write_or_fail(result: io::Result<()>) {
+    if let Err(error) = result {
+        fail(format_args!("<stdout>: {}", error));
+    }
+}
+
+fn fail(message: fmt::Arguments) -> ! {
+    eprintln!("lynx-dlx: {}", message);
+    process::exit(1);
+}
@@ -0,0 +1,95 @@
+use lynx::langford::Langford;
+
+use clap::Parser;
+use std::io;
+use std::io::Write;
+use std::process;
+
+// There is no sequence of the order given
+const EXIT_UNSOLVABLE: i32 = 2;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "lynx-langford",
+    version,
+    about = "Find Langford pairings and Skolem sequences",
+    long_about = "Find Langford pairings and Skolem sequences.\n\n\
+                  A Langford pairing of order N arranges two copies of each of the numbers 1 to N \
+                  in a row so that the copies of each number k have k numbers between them. In a \
+                  Skolem sequence, they are k places apart instead. Each sequence is printed on \
+                  one line, and is counted separately from its reversal.\n\n\
+                  Exits with status 0 on success, and 2 if there is no sequence."
+)]
+struct Cli {
+    #[arg(value_name = "N", value_parser = clap::value_parser!(u32).range(1..=1000))]
+    n: u32,
+
+    #[arg(long, help = "Find Skolem sequences rather than Langford pairings")]
+    skolem: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "count",
+        help = "Print every sequence, rather than the first"
+    )]
+    all: bool,
+
+    #[arg(
+        long,
+        value_name = "LIMIT",
+        num_args = 0..=1,
+        require_equals = true,
+        help = "Print only the number of sequences, counting up to LIMIT if given"
+    )]
+    count: Option<Option<usize>>,
+}
+
+fn main() {
+    // Status 2 says that no sequence of order N exists, so a bad order or flag can't use clap's
+    // default of 2 and exits with 1 instead
+    let cli = Cli::try_parse().unwrap_or_else(|error| {
+        let _ = error.print();
+        process::exit(if error.use_stderr() { 1 } else { 0 });
+    });
+
+    let problem = if cli.skolem {
+        Langford::skolem(cli.n as usize)
+    } else {
+        Langford::new(cli.n as usize)
+    };
+    let mut out = io::BufWriter::new(io::stdout().lock());
+
+    let solved = if let Some(limit) = cli.count {
+        let count = problem.count(limit.unwrap_or(usize::MAX));
+        write_or_fail(writeln!(out, "{}", count));
+        count > 0
+    } else {
+        let mut found = false;
+
+        for sequence in problem
+            .enumerate()
+            .take(if cli.all { usize::MAX } else { 1 })
+        {
+            found = true;
+
+            let numbers: Vec<String> = sequence.iter().map(|k| k.to_string()).collect();
+            write_or_fail(writeln!(out, "{}", numbers.join(" ")));
+        }
+
+        found
+    };
+
+    write_or_fail(out.flush());
+
+    if !solved {
+        eprintln!("lynx-langford: no sequence");
+        process::exit(EXIT_UNSOLVABLE);
+    }
+}
+
+fn write_or_fail(result: io::Result<()>) {
+    if let Err(error) = result {
+        eprintln!("lynx-langford: <stdout>: {}", error);
+        process::exit(1);
+    }
+}
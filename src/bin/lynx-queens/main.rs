@@ -0,0 +1,97 @@
+use lynx::queens::Queens;
+
+use clap::Parser;
+use std::io;
+use std::io::Write;
+use std::process;
+
+// The board is too small for any solution
+const EXIT_UNSOLVABLE: i32 = 2;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "lynx-queens",
+    version,
+    about = "Place N queens on an NxN board so that none attacks another",
+    long_about = "Place N queens on an NxN board so that none attacks another.\n\n\
+                  Each solution is printed as a board, with Q for a queen and '.' for an empty \
+                  square, and solutions are separated by blank lines.\n\n\
+                  Exits with status 0 on success, and 2 if there is no solution."
+)]
+struct Cli {
+    #[arg(value_name = "N", value_parser = clap::value_parser!(u32).range(1..=1000))]
+    n: u32,
+
+    #[arg(
+        long,
+        conflicts_with = "count",
+        help = "Print every solution, rather than the first"
+    )]
+    all: bool,
+
+    #[arg(long, help = "Print only the number of solutions")]
+    count: bool,
+
+    #[arg(
+        long,
+        help = "Print each solution on one line, as the column of the queen in each row, counting from 1"
+    )]
+    lines: bool,
+}
+
+fn main() {
+    // N out of range or an unknown flag exits with status 1, not clap's 2, which callers of this
+    // tool take to mean the board has no placement
+    let cli = Cli::try_parse().unwrap_or_else(|error| {
+        let _ = error.print();
+        process::exit(if error.use_stderr() { 1 } else { 0 });
+    });
+
+    let queens = Queens::new(cli.n as usize);
+    let mut out = io::BufWriter::new(io::stdout().lock());
+
+    let solved = if cli.count {
+        let count = queens.count();
+        write_or_fail(writeln!(out, "{}", count));
+        count > 0
+    } else {
+        let mut found = false;
+
+        for board in queens
+            .iter_solutions()
+            .take(if cli.all { usize::MAX } else { 1 })
+        {
+            if found && !cli.lines {
+                write_or_fail(writeln!(out));
+            }
+            found = true;
+
+            if cli.lines {
+                let columns: Vec<String> = board
+                    .columns()
+                    .iter()
+                    .map(|column| (column + 1).to_string())
+                    .collect();
+                write_or_fail(writeln!(out, "{}", columns.join(" ")));
+            } else {
+                write_or_fail(writeln!(out, "{}", board));
+            }
+        }
+
+        found
+    };
+
+    write_or_fail(out.flush());
+
+    if !solved {
+        eprintln!("lynx-queens: no solution");
+        process::exit(EXIT_UNSOLVABLE);
+    }
+}
+
+fn write_or_fail(result: io::Result<()>) {
+    if let Err(error) = result {
+        eprintln!("lynx-queens: <stdout>: {}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::fail;
+use lynx::sudoku::{ParseSudokuError, Sudoku};
+
+use clap::Args;
+use core::str::FromStr;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::vec;
+
+#[derive(Args, Clone, Debug)]
+pub struct Input {
+    #[arg(
+        long,
+        help = "Parse each line as a separate puzzle, rather than each whole file as one"
+    )]
+    pub lines: bool,
+
+    #[arg(
+        value_name = "FILE",
+        help = "Files to read puzzles from, in order, with - for standard input [default: standard \
+                input]. A puzzle may also be given in place of a file, as a single argument"
+    )]
+    pub files: Vec<PathBuf>,
+}
+
+impl Input {
+    // Errors reading or parsing the input end the process, naming the file and line
+    pub fn puzzles(&self) -> impl Iterator<Item = Sudoku> {
+        self.located_puzzles().map(|(_, sudoku)| sudoku)
+    }
+
+    // Like puzzles, but with the location of each puzzle
+    pub fn located_puzzles(&self) -> impl Iterator<Item = (String, Sudoku)> {
+        self.entries().map(Entry::into_puzzle)
+    }
+
+    // The number of lines across every file, or None if some input is standard input and can't be
+    // read ahead. Errors are left for reading the files to report
+    pub fn line_count(&self) -> Option<usize> {
+        if self.files.is_empty() {
+            return None;
+        }
+
+        let mut count = 0;
+        for file in &self.files {
+            if file == Path::new("-") {
+                return None;
+            }
+
+            if literal(file).is_some() {
+                count += 1;
+                continue;
+            }
+
+            let mut reader = io::BufReader::new(fs::File::open(file).ok()?);
+            loop {
+                let buffer = reader.fill_buf().ok()?;
+                if buffer.is_empty() {
+                    break;
+                }
+
+                count += buffer.iter().filter(|&&byte| byte == b'\n').count();
+                let length = buffer.len();
+                reader.consume(length);
+            }
+        }
+
+        Some(count)
+    }
+
+    // Like located_puzzles, but leaves parse errors to the caller. Errors reading the input still
+    // end the process
+    pub fn entries(&self) -> Entries {
+        let files = if self.files.is_empty() {
+            vec![PathBuf::from("-")]
+        } else {
+            self.files.clone()
+        };
+
+        Entries {
+            files: files.into_iter(),
+            name: String::new(),
+            reader: None,
+            lines: self.lines,
+            line: 0,
+        }
+    }
+}
+
+pub struct Entry {
+    // The file, and the line if known, for diagnostics
+    pub location: String,
+    // The text the puzzle was parsed from, for grids which aren't 9x9
+    pub text: String,
+    pub sudoku: Result<Sudoku, ParseSudokuError>,
+}
+
+impl Entry {
+    // Parse errors end the process, naming the file and line
+    pub fn into_puzzle(self) -> (String, Sudoku) {
+        match self.sudoku {
+            Ok(sudoku) => (self.location, sudoku),
+            Err(error) => fail(format_args!("{}: {}", self.location, error)),
+        }
+    }
+}
+
+// Reads the files one after another, opening each only once the previous one is exhausted
+pub struct Entries {
+    files: vec::IntoIter<PathBuf>,
+    // The name of the file being read, and the reader if it isn't yet exhausted
+    name: String,
+    reader: Option<Box<dyn BufRead + Send>>,
+    lines: bool,
+    line: usize,
+}
+
+impl Entries {
+    fn open(&mut self, file: &Path) {
+        if let Some(puzzle) = literal(file) {
+            self.name = String::from("<argument>");
+            self.reader = Some(Box::new(io::Cursor::new(puzzle.to_string())));
+        } else if file == Path::new("-") {
+            self.name = String::from("<stdin>");
+            self.reader = Some(Box::new(io::BufReader::new(io::stdin())));
+        } else {
+            self.name = file.to_string_lossy().to_string();
+            self.reader = match fs::File::open(file) {
+                Ok(file) => Some(Box::new(io::BufReader::new(file))),
+                Err(error) => fail(format_args!("{}: {}", self.name, error)),
+            };
+        }
+
+        self.line = 0;
+    }
+
+    // The next puzzle from the current file, or None once it is exhausted
+    fn read(&mut self) -> Option<Entry> {
+        if !self.lines {
+            let mut string = String::new();
+            if let Err(error) = self.reader.take()?.read_to_string(&mut string) {
+                fail(format_args!("{}: {}", self.name, error));
+            }
+
+            let sudoku = Sudoku::from_str(&string);
+            let location = match sudoku {
+                Err(ParseSudokuError::InvalidCharacter { offset, .. }) => {
+                    let line = string[..offset].matches('\n').count() + 1;
+                    format!("{}:{}", self.name, line)
+                }
+                _ => self.name.clone(),
+            };
+
+            return Some(Entry {
+                location,
+                text: string,
+                sudoku,
+            });
+        }
+
+        let mut line = String::new();
+        self.line += 1;
+
+        match self.reader.as_mut()?.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_) => Some(Entry {
+                location: format!("{}:{}", self.name, self.line),
+                sudoku: Sudoku::from_str(&line),
+                text: line,
+            }),
+            Err(error) => fail(format_args!("{}:{}: {}", self.name, self.line, error)),
+        }
+    }
+}
+
+impl Iterator for Entries {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Entry> {
+        loop {
+            if self.reader.is_none() {
+                let file = self.files.next()?;
+                self.open(&file);
+            }
+
+            match self.read() {
+                Some(entry) => return Some(entry),
+                None => self.reader = None,
+            }
+        }
+    }
+}
+
+// An argument which names no file, but has as many cells as some size of grid, is taken to be a
+// puzzle itself
+fn literal(file: &Path) -> Option<&str> {
+    const CELLS: [usize; 7] = [16, 36, 64, 81, 144, 256, 625];
+
+    let string = file.to_str()?;
+    let cells = string.chars().filter(|ch| !ch.is_whitespace()).count();
+    let plausible = string
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || ch == '.' || ch.is_whitespace());
+
+    (plausible && CELLS.contains(&cells) && !file.exists()).then_some(string)
+}
@@ -1,103 +1,1605 @@
+use input::{Entry, Input};
+use lynx::json::Value;
+use lynx::rng::{Rng, SplitMix64};
+use lynx::sudoku::{Difficulty, LogicalSolver, Reason, Regions, Rules, SolveStats, Sudoku};
+use lynx::TimedOut;
+use output::{Format, Output, StatsReport, Writer};
+use progress::Progress;
+use sized::{Shape, SizedGrid};
+
+use clap::error::ErrorKind;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
 use core::fmt;
-use core::mem;
 use core::str::FromStr;
-use std::env;
-use std::ffi;
+use core::time::Duration;
+use std::collections::HashSet;
 use std::fs;
-use std::io;
-use std::io::BufRead;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::atomic::{AtomicI8, Ordering};
+use std::thread;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// Like println!, but to the Output given first
+macro_rules! outln {
+    ($out:expr) => {
+        $out.println(format_args!(""))
+    };
+    ($out:expr, $($arg:tt)*) => {
+        $out.println(format_args!($($arg)*))
+    };
+}
+
+mod input;
+mod output;
+mod parallel;
+mod progress;
+mod sized;
+
+// Every puzzle was well-formed, but at least one of them has no solution (or, for check, rate,
+// and minimize, no unique solution)
+const EXIT_UNSOLVABLE: i32 = 2;
+
+// -1 with --quiet, otherwise the number of times --verbose was given, up to 2
+static VERBOSITY: AtomicI8 = AtomicI8::new(0);
+
+fn verbosity() -> i8 {
+    VERBOSITY.load(Ordering::Relaxed)
+}
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "lynx-sudoku",
+    version,
+    about = "Solve, generate, and analyze Sudoku puzzles",
+    long_about = "Solve, generate, and analyze Sudoku puzzles.\n\n\
+                  Puzzles are specified using the digits 1 through 9 to represent occupied cells, \
+                  and '.' or 0 to represent unoccupied cells. Whitespace is ignored, except for \
+                  newlines in --lines mode. Grids of other sizes, such as 16x16, write the digits \
+                  beyond 9 as letters. Without a subcommand, puzzles are solved.\n\n\
+                  Exits with status 0 on success, 2 if every puzzle was valid but some had no \
+                  solution (or no unique solution, where one is needed) or timed out, and 1 if \
+                  the input couldn't be read or parsed.",
+    args_conflicts_with_subcommands = true
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    solve: Solve,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Write results to PATH, which is only replaced once the run is over [default: standard output]"
+    )]
+    output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        requires = "output",
+        help = "Add results to the end of the --output file, rather than replacing it"
+    )]
+    append: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Seed for everything random, such as generating puzzles and the order minimize \
+                removes clues in, so that runs can be repeated [default: the current time]"
+    )]
+    seed: Option<u64>,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Leave out puzzles without a solution, summaries, and other messages on standard error"
+    )]
+    quiet: bool,
+
+    #[arg(
+        short,
+        long,
+        global = true,
+        action = clap::ArgAction::Count,
+        conflicts_with = "quiet",
+        help = "Warn about suspicious puzzles and say which seed was used; given twice, also show \
+                per-puzzle statistics"
+    )]
+    verbose: u8,
+}
+
+#[derive(Args, Debug)]
+struct Solve {
+    #[command(flatten)]
+    input: Input,
+
+    #[arg(
+        long,
+        conflicts_with = "count",
+        help = "Print every solution of each puzzle, rather than the first"
+    )]
+    all: bool,
+
+    #[arg(
+        long,
+        value_name = "LIMIT",
+        num_args = 0..=1,
+        require_equals = true,
+        help = "Print only the number of solutions of each puzzle, counting up to LIMIT if given"
+    )]
+    count: Option<Option<usize>>,
+
+    #[arg(
+        long,
+        value_enum,
+        conflicts_with = "count",
+        help = "Output format [default: line with --lines, otherwise grid]"
+    )]
+    format: Option<Format>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        help = "Solve puzzles on N threads, keeping the output in input order (0 for one per CPU)"
+    )]
+    jobs: usize,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["all", "count"],
+        help = "Print the time, nodes, and backtracks for each puzzle and in total to standard error"
+    )]
+    stats: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["all", "count", "stats"],
+        help = "Like --stats, but as one JSON object per line"
+    )]
+    stats_json: bool,
+
+    #[arg(
+        long,
+        value_name = "MS",
+        conflicts_with_all = ["all", "count"],
+        help = "Give up on any puzzle not solved within MS milliseconds, reporting it and moving on"
+    )]
+    timeout: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Never show the progress bar, which is otherwise shown in --lines mode when standard \
+                error is a terminal and the results aren't"
+    )]
+    no_progress: bool,
+
+    #[arg(
+        long,
+        value_name = "VARIANT",
+        value_delimiter = ',',
+        value_parser = parse_variant,
+        help = "Extra rules, combined if given more than once or separated by commas: x, windoku, \
+                jigsaw=FILE (a grid of nine distinct region symbols), antiknight, antiking, or \
+                nonconsecutive"
+    )]
+    variant: Vec<Variant>,
+
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u8).range(4..=25),
+        help = "Grid size, for grids other than 9x9 [default: detected from the number of cells]"
+    )]
+    size: Option<u8>,
+
+    #[arg(
+        long = "box",
+        value_name = "WxH",
+        value_parser = sized::parse_shape,
+        help = "Box width and height, such as 3x4 for a 12x12 grid [default: as square as the size allows]"
+    )]
+    boxes: Option<Shape>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["format", "stats", "stats_json", "size", "boxes"],
+        help = "Read one JSON object per line, such as {\"id\":1,\"puzzle\":\"...\"}, and write one \
+                per line with the same id and the solution, or the error. Ids may be strings or \
+                integers"
+    )]
+    jsonl: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["all", "count", "format", "stats", "stats_json", "timeout", "jsonl"],
+        help = "Only parse the puzzles, writing each back in normal form and reporting malformed \
+                ones with their line numbers"
+    )]
+    parse_only: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["all", "count", "format", "stats", "stats_json", "jsonl", "parse_only"],
+        help = "Solve the files again whenever they change, until interrupted"
+    )]
+    watch: bool,
+}
+
+#[derive(Clone, Debug)]
+enum Variant {
+    X,
+    Windoku,
+    Jigsaw(Regions),
+    AntiKnight,
+    AntiKing,
+    NonConsecutive,
+}
+
+fn parse_variant(string: &str) -> Result<Variant, String> {
+    if let Some(file) = string.strip_prefix("jigsaw=") {
+        let regions = fs::read_to_string(file).map_err(|error| format!("{}: {}", file, error))?;
+        return Regions::from_str(&regions)
+            .map(Variant::Jigsaw)
+            .map_err(|error| format!("{}: {}", file, error));
+    }
+
+    match string {
+        "x" => Ok(Variant::X),
+        "windoku" => Ok(Variant::Windoku),
+        "jigsaw" => Err(String::from("jigsaw needs a region file, as jigsaw=FILE")),
+        "antiknight" => Ok(Variant::AntiKnight),
+        "antiking" => Ok(Variant::AntiKing),
+        "nonconsecutive" => Ok(Variant::NonConsecutive),
+        _ => Err(format!("unknown variant '{}'", string)),
+    }
+}
+
+fn rules(variants: &[Variant]) -> Rules {
+    variants
+        .iter()
+        .fold(Rules::new(), |rules, variant| match variant {
+            Variant::X => rules.diagonals(true),
+            Variant::Windoku => rules.windoku(true),
+            Variant::Jigsaw(regions) => rules.regions(*regions),
+            Variant::AntiKnight => rules.anti_knight(true),
+            Variant::AntiKing => rules.anti_king(true),
+            Variant::NonConsecutive => rules.non_consecutive(true),
+        })
+}
+
+#[derive(Args, Debug)]
+struct Generate {
+    #[arg(long, default_value_t = 1, help = "Number of puzzles to generate")]
+    count: usize,
+
+    #[arg(
+        long,
+        value_parser = clap::value_parser!(u8).range(17..=81),
+        help = "Number of clues in each puzzle [default: as few as keep the solution unique]"
+    )]
+    clues: Option<u8>,
+
+    #[arg(
+        long,
+        value_parser = parse_difficulty,
+        help = "Only emit puzzles of this difficulty: easy, medium, hard, expert, or extreme"
+    )]
+    difficulty: Option<Difficulty>,
+
+    #[arg(
+        long,
+        help = "Follow each puzzle with its solution, separated by a space"
+    )]
+    solutions: bool,
+}
+
+fn parse_difficulty(string: &str) -> Result<Difficulty, String> {
+    [
+        Difficulty::Easy,
+        Difficulty::Medium,
+        Difficulty::Hard,
+        Difficulty::Expert,
+        Difficulty::Extreme,
+    ]
+    .into_iter()
+    .find(|difficulty| difficulty.to_string().eq_ignore_ascii_case(string))
+    .ok_or_else(|| format!("unknown difficulty '{}'", string))
+}
+
+enum Outcome {
+    Count(usize),
+    // Each solution with the time taken to find it
+    Solutions {
+        solutions: Vec<(Sudoku, Duration)>,
+        elapsed: Duration,
+        stats: Option<SolveStats>,
+    },
+    TimedOut(Duration),
+    // Every solution, left to be enumerated while they're written rather than collected, since
+    // there may be far too many to hold
+    Enumerate,
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+enum Backend {
+    // Exact cover, which also counts nodes and backtracks
+    Dlx,
+    // The bitboard backtracking solver
+    Fast,
+    // Each of the above in turn, for comparison
+    Both,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[command(about = "Solve each puzzle, echoing puzzles with no solution verbatim")]
+    Solve(Solve),
+
+    #[command(about = "Generate random puzzles with unique solutions, one per line")]
+    Generate(Generate),
+
+    #[command(
+        about = "Report whether each puzzle is malformed, invalid, unsolvable, ambiguous, or unique"
+    )]
+    Check {
+        #[command(flatten)]
+        input: Input,
+    },
+
+    #[command(
+        about = "Verify solutions against their puzzles, given as two columns or on alternating lines"
+    )]
+    Verify {
+        #[command(flatten)]
+        input: Input,
+    },
+
+    #[command(about = "Rate the difficulty of each puzzle with a unique solution")]
+    Rate {
+        #[command(flatten)]
+        input: Input,
+
+        #[arg(
+            long,
+            value_parser = parse_difficulty,
+            help = "Only print puzzles at least this difficult, leaving out unrated ones"
+        )]
+        min: Option<Difficulty>,
+
+        #[arg(
+            long,
+            value_parser = parse_difficulty,
+            help = "Only print puzzles at most this difficult, leaving out unrated ones"
+        )]
+        max: Option<Difficulty>,
+    },
+
+    #[command(
+        about = "Explain each puzzle as a sequence of logical techniques, marking where guessing is needed"
+    )]
+    Explain {
+        #[command(flatten)]
+        input: Input,
+    },
+
+    #[command(about = "Suggest a single placement in each puzzle, with the reason for it")]
+    Hint {
+        #[command(flatten)]
+        input: Input,
+    },
+
+    #[command(about = "Count the solutions of each puzzle")]
+    Count {
+        #[command(flatten)]
+        input: Input,
+
+        #[arg(
+            long,
+            default_value_t = 1000,
+            help = "Stop counting at this many solutions"
+        )]
+        limit: usize,
+    },
+
+    #[command(
+        about = "Remove clues from each puzzle while its solution stays unique, saying how many went"
+    )]
+    Minimize {
+        #[command(flatten)]
+        input: Input,
+    },
+
+    #[command(
+        about = "Time solving every puzzle over several passes, and report throughput and latency"
+    )]
+    Bench {
+        #[command(flatten)]
+        input: Input,
+
+        #[arg(
+            long,
+            default_value_t = 3,
+            help = "Number of timed passes over the puzzles"
+        )]
+        passes: usize,
+
+        #[arg(
+            long,
+            default_value_t = 1,
+            help = "Number of untimed passes to make first"
+        )]
+        warmup: usize,
+
+        #[arg(long, value_enum, default_value_t = Backend::Dlx, help = "Solver to time")]
+        backend: Backend,
+    },
+
+    #[command(about = "Show a grid of the cells that differ between two puzzles or solutions")]
+    Diff {
+        #[arg(help = "File holding the first grid, or - for standard input")]
+        first: PathBuf,
+
+        #[arg(help = "File holding the second grid, or - for standard input")]
+        second: PathBuf,
+    },
+
+    #[command(about = "Print the canonical form of each puzzle, shared by all its isomorphs")]
+    Canon {
+        #[command(flatten)]
+        input: Input,
+
+        #[arg(
+            long,
+            help = "Print each canonical form only the first time it appears, dropping isomorphs"
+        )]
+        dedupe: bool,
+    },
+}
+
+fn main() {
+    // Usage errors are reported like unreadable input, with status 1, leaving status 2 to mean
+    // that some puzzle had no solution
+    let cli = Cli::try_parse().unwrap_or_else(|error| {
+        let _ = error.print();
+        process::exit(if error.use_stderr() { 1 } else { 0 });
+    });
+
+    let mut out = Output::open(cli.output.as_deref(), cli.append).unwrap_or_else(|error| {
+        let path = cli.output.unwrap_or_default();
+        fail(format_args!("{}: {}", path.display(), error))
+    });
+
+    let level = if cli.quiet {
+        -1
+    } else {
+        cli.verbose.min(2) as i8
+    };
+    VERBOSITY.store(level, Ordering::Relaxed);
+
+    let seed = cli.seed.unwrap_or_else(time_seed);
+
+    let succeeded = match cli.command.unwrap_or(Command::Solve(cli.solve)) {
+        Command::Solve(args) => solve(&args, &mut out),
+        Command::Generate(args) => generate(&args, seed, &mut out),
+        Command::Check { input } => check(&input, &mut out),
+        Command::Verify { input } => verify(&input, &mut out),
+        Command::Rate { input, min, max } => rate(&input, min, max, &mut out),
+        Command::Explain { input } => explain(&input, &mut out),
+        Command::Hint { input } => hint(&input, &mut out),
+        Command::Count { input, limit } => count(&input, limit, &mut out),
+        Command::Minimize { input } => minimize(&input, seed, &mut out),
+        Command::Canon { input, dedupe } => canon(&input, dedupe, &mut out),
+        Command::Diff { first, second } => diff(&first, &second, &mut out),
+        Command::Bench {
+            input,
+            passes,
+            warmup,
+            backend,
+        } => bench(&input, passes, warmup, backend, &mut out),
+    };
+
+    out.commit();
+
+    if !succeeded {
+        process::exit(EXIT_UNSOLVABLE);
+    }
+}
+
+// Each of the subcommands returns false if some puzzle had no (unique) solution
+
+fn solve(args: &Solve, out: &mut Output) -> bool {
+    if args.jsonl {
+        return solve_jsonl(args, out);
+    }
+
+    if args.parse_only {
+        return parse_only(&args.input, out);
+    }
 
-struct Args {
-    file: Option<ffi::OsString>,
-    lines: bool,
-}
-
-fn parse_args() -> Args {
-    let mut args = env::args_os().collect::<Vec<_>>();
-
-    let usage = || {
-        let program_name = args
-            .get(0)
-            .map(|path| path.to_string_lossy().to_string())
-            .unwrap_or_else(|| String::from_str("lynx-sudoku").unwrap());
-        _ = eprintln!(
-            "Usage: {} [--lines] [FILE]\n\
-             \n\
-             Solve one or several Sudoku puzzles given in FILE. FILE defaults to the\n\
-             standard input. If --lines is specified, each line of FILE is parsed and\n\
-             solved as a separate puzzle, otherwise the whole file parsed as one single\n\
-             puzzle.\n\
-             \n\
-             Puzzles are specified using the digits 1 through 9 to represent occupied\n\
-             cells, and '.' or 0 to represent unoccupied cells. Whitespace is ignored,\n\
-             except for newlines in --lines mode. Puzzles with no solution are echoed\n\
-             verbatim.",
-            program_name
+    if args.watch {
+        watch(args, out);
+    }
+
+    let input = &args.input;
+    let mut solved_all = true;
+
+    let format = args.format.unwrap_or(if input.lines {
+        Format::Line
+    } else {
+        Format::Grid
+    });
+    let mut writer = Writer::new(format, verbosity() >= 0);
+
+    let jobs = match args.jobs {
+        0 => thread::available_parallelism().map_or(1, |jobs| jobs.get()),
+        jobs => jobs,
+    };
+
+    let stats =
+        args.stats || args.stats_json || (verbosity() >= 2 && !args.all && args.count.is_none());
+    let mut report = stats.then(|| StatsReport::new(args.stats_json));
+
+    // The bar would be torn up by results or statistics written to the same terminal
+    let show_progress = input.lines
+        && !args.no_progress
+        && verbosity() >= 0
+        && report.is_none()
+        && !out.is_terminal();
+    let mut progress = show_progress
+        .then(|| Progress::new(input.line_count()))
+        .flatten();
+
+    let mut entries = input.entries().peekable();
+
+    let shape = match (args.size, args.boxes) {
+        (Some(size), Some(shape)) if shape.size() != size as usize => fail(format_args!(
+            "--box {}x{} doesn't make a {}x{} grid",
+            shape.width, shape.height, size, size
+        )),
+        (_, Some(shape)) => Some(shape),
+        (Some(size), None) => Some(Shape::for_size(size as usize).unwrap_or_else(|| {
+            fail(format_args!(
+                "--size {} needs --box to give the box shape",
+                size
+            ))
+        })),
+        (None, None) => entries.peek().and_then(|entry| Shape::detect(&entry.text)),
+    };
+
+    if let Some(shape) = shape.filter(|shape| shape.size() != 9) {
+        return solve_sized(args, shape, entries, out);
+    }
+
+    let rules = rules(&args.variant);
+
+    let outcome = |entry: &Entry| {
+        let sudoku = entry.sudoku.as_ref().ok()?;
+        Some(outcome(args, &rules, sudoku))
+    };
+
+    parallel::for_each_ordered(entries, jobs, outcome, |entry, outcome| {
+        // Parse errors are reported in input order, like results, so that every earlier puzzle is
+        // still emitted when puzzles are read ahead on other threads
+        let Entry {
+            location, sudoku, ..
+        } = entry;
+        let sudoku = sudoku.unwrap_or_else(|error| fail(format_args!("{}: {}", location, error)));
+        let outcome = outcome.expect("puzzles which parse have an outcome");
+
+        if verbosity() >= 1 {
+            if let Some(progress) = progress.as_mut() {
+                progress.clear();
+            }
+            warn_suspicious(&location, &sudoku, &rules);
+        }
+
+        match outcome {
+            Outcome::Count(count) => {
+                solved_all &= count > 0;
+
+                if Some(count) == args.count.flatten() {
+                    outln!(out, "{}+", count);
+                } else {
+                    outln!(out, "{}", count);
+                }
+            }
+            Outcome::Solutions {
+                solutions,
+                elapsed,
+                stats,
+            } => {
+                if let (Some(report), Some(stats)) = (report.as_mut(), stats) {
+                    report.puzzle(&stats, !solutions.is_empty());
+                }
+
+                if solutions.is_empty() {
+                    solved_all = false;
+                    writer.record(out, &location, &sudoku, None, elapsed);
+                }
+
+                for (solved, elapsed) in solutions {
+                    writer.record(out, &location, &sudoku, Some(&solved), elapsed);
+                }
+            }
+            Outcome::TimedOut(elapsed) => {
+                solved_all = false;
+
+                if let Some(progress) = progress.as_mut() {
+                    progress.clear();
+                }
+
+                match report.as_mut() {
+                    Some(report) => report.timed_out(elapsed),
+                    None if verbosity() < 0 => (),
+                    None => eprintln!(
+                        "lynx-sudoku: {}: timed out after {:.3} ms",
+                        location,
+                        elapsed.as_secs_f64() * 1000.0
+                    ),
+                }
+                writer.timed_out(out, &location, &sudoku, elapsed);
+            }
+            Outcome::Enumerate => {
+                let start = Instant::now();
+                let mut found = false;
+
+                for solved in sudoku.solve_all_with(&rules) {
+                    found = true;
+                    writer.record(out, &location, &sudoku, Some(&solved), start.elapsed());
+                }
+
+                if !found {
+                    solved_all = false;
+                    writer.record(out, &location, &sudoku, None, start.elapsed());
+                }
+            }
+        }
+
+        if let Some(progress) = progress.as_mut() {
+            progress.tick();
+        }
+    });
+
+    if let Some(progress) = progress {
+        progress.finish();
+    }
+
+    if let Some(report) = report {
+        report.finish();
+    }
+
+    solved_all
+}
+
+// Puzzles which parse, but likely aren't what was meant: too few clues to have a unique solution,
+// or givens which break the rules outright, variants included
+fn warn_suspicious(location: &str, sudoku: &Sudoku, rules: &Rules) {
+    let clues = sudoku.clue_count();
+    if clues < 17 {
+        eprintln!(
+            "lynx-sudoku: warning: {}: only {} clue(s), too few for a unique solution",
+            location, clues
         );
+    }
+
+    if let Some(conflict) = sudoku.conflicts_with(rules).first() {
+        eprintln!("lynx-sudoku: warning: {}: {}", location, conflict);
+    }
+}
+
+// Solves, enumerates, or counts as the arguments ask
+fn outcome(args: &Solve, rules: &Rules, sudoku: &Sudoku) -> Outcome {
+    if let Some(limit) = args.count {
+        let limit = limit.unwrap_or(usize::MAX);
+        return Outcome::Count(sudoku.count_solutions_with(rules, limit));
+    }
+
+    if args.all {
+        return Outcome::Enumerate;
+    }
+
+    let start = Instant::now();
+    let deadline = args
+        .timeout
+        .map(|timeout| start + Duration::from_millis(timeout));
+    let (solved, stats) = match sudoku.solve_until(rules, deadline) {
+        Ok(result) => result,
+        Err(TimedOut) => return Outcome::TimedOut(start.elapsed()),
+    };
+
+    Outcome::Solutions {
+        solutions: solved
+            .map(|solved| (solved, stats.elapsed))
+            .into_iter()
+            .collect(),
+        elapsed: stats.elapsed,
+        stats: Some(stats),
+    }
+}
+
+// Polls the files rather than waiting on notifications from the system, which is plenty quick for
+// a person editing a puzzle. Malformed puzzles are reported in place of their solutions rather than
+// ending the process, since the file is likely only half edited
+fn watch(args: &Solve, out: &mut Output) -> ! {
+    const INTERVAL: Duration = Duration::from_millis(250);
+
+    let input = &args.input;
+    if input.files.is_empty() || input.files.iter().any(|file| file == Path::new("-")) {
+        fail(format_args!(
+            "--watch needs files to watch, not standard input"
+        ));
+    }
+    if out.is_file() {
+        fail(format_args!(
+            "--watch writes to standard output, so can't be used with --output"
+        ));
+    }
+
+    let rules = rules(&args.variant);
+    let mut seen = None;
+
+    loop {
+        // Editors often replace a file by renaming another over it, so it may briefly be missing
+        let modified = input
+            .files
+            .iter()
+            .map(|file| fs::metadata(file).and_then(|metadata| metadata.modified()))
+            .collect::<Result<Vec<_>, _>>();
+
+        if let Some(modified) = modified
+            .ok()
+            .filter(|modified| seen.as_ref() != Some(modified))
+        {
+            seen = Some(modified);
+
+            if out.is_terminal() {
+                // Clears the screen and moves to the top, so that only the latest results show
+                out.print(format_args!("\x1b[2J\x1b[H"));
+            }
+
+            for entry in input.entries() {
+                match entry.sudoku {
+                    Ok(sudoku) => match sudoku.solve_with(&rules) {
+                        Some(solved) => {
+                            outln!(out, "{}:", entry.location);
+                            print_sudoku(&solved, input, out);
+                        }
+                        None => outln!(out, "{}: no solution", entry.location),
+                    },
+                    Err(error) => outln!(out, "{}: {}", entry.location, error),
+                }
+            }
+
+            out.flush();
+        }
+
+        thread::sleep(INTERVAL);
+    }
+}
+
+// Carries on past malformed puzzles so that all of them are reported in one pass, but exits with
+// status 1 at the end if there were any
+fn parse_only(input: &Input, out: &mut Output) -> bool {
+    let mut puzzles = 0;
+    let mut malformed = 0;
+
+    for entry in input.entries() {
+        puzzles += 1;
+
+        match entry.sudoku {
+            Ok(sudoku) => print_sudoku(&sudoku, input, out),
+            Err(error) => {
+                malformed += 1;
+                eprintln!("lynx-sudoku: {}: {}", entry.location, error);
+            }
+        }
+    }
+
+    if verbosity() >= 0 {
+        eprintln!("{} puzzle(s), {} malformed", puzzles, malformed);
+    }
+
+    if malformed > 0 {
+        out.commit();
         process::exit(1);
+    }
+
+    true
+}
+
+// Each request is answered in order on a line of its own, so a process driving this one can match
+// them up. Malformed requests are answered with an error rather than ending the run, but still
+// make the process exit with status 1
+fn solve_jsonl(args: &Solve, out: &mut Output) -> bool {
+    let input = Input {
+        lines: true,
+        ..args.input.clone()
     };
 
-    match args.len() {
-        0 | 1 => Args {
-            file: None,
-            lines: false,
+    let jobs = match args.jobs {
+        0 => thread::available_parallelism().map_or(1, |jobs| jobs.get()),
+        jobs => jobs,
+    };
+
+    let rules = rules(&args.variant);
+    let mut solved_all = true;
+    let mut malformed = false;
+
+    let requests = input
+        .entries()
+        .filter(|entry| !entry.text.trim().is_empty())
+        .map(|entry| {
+            let request = Value::from_str(&entry.text).map_err(|error| error.to_string());
+            let id = request
+                .as_ref()
+                .ok()
+                .and_then(|request| request.get("id"))
+                .cloned()
+                .unwrap_or(Value::Null);
+
+            // Numbers are read as f64, so only ids that survive the round trip exactly are echoed
+            let (id, request) = match id {
+                Value::Null | Value::String(_) => (id, request),
+                Value::Number(number) if is_safe_integer(number) => (id, request),
+                _ => (
+                    Value::Null,
+                    Err(String::from(
+                        "\"id\" must be a string or an integer of magnitude less than 2^53",
+                    )),
+                ),
+            };
+
+            let sudoku = request.and_then(|request| {
+                let puzzle = request
+                    .get("puzzle")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| String::from("Missing \"puzzle\" string"))?;
+                Sudoku::from_str(puzzle).map_err(|error| error.to_string())
+            });
+
+            (entry.location, id, sudoku)
+        });
+
+    let outcome = |(_, _, sudoku): &(String, Value, Result<Sudoku, String>)| {
+        sudoku
+            .as_ref()
+            .ok()
+            .map(|sudoku| outcome(args, &rules, sudoku))
+    };
+
+    parallel::for_each_ordered(
+        requests,
+        jobs,
+        outcome,
+        |(location, id, sudoku), outcome| {
+            let time_ms =
+                |elapsed: Duration| Value::Number((elapsed.as_secs_f64() * 1e6).round() / 1e3);
+            let line = |sudoku: &Sudoku| Value::String(sudoku.to_line());
+
+            let fields = match (sudoku, outcome) {
+                (Ok(_), Some(Outcome::Count(count))) => {
+                    solved_all &= count > 0;
+                    vec![("count", Value::Number(count as f64))]
+                }
+                // Written a solution at a time, building up the response on the one line
+                (Ok(sudoku), Some(Outcome::Enumerate)) => {
+                    let start = Instant::now();
+                    out.print(format_args!("{{\"id\":{},\"solutions\":[", id));
+
+                    let mut found = false;
+                    for solved in sudoku.solve_all_with(&rules) {
+                        if found {
+                            out.print(format_args!(","));
+                        }
+                        found = true;
+                        out.print(format_args!("{}", line(&solved)));
+                    }
+
+                    solved_all &= found;
+                    outln!(out, "],\"time_ms\":{}}}", time_ms(start.elapsed()));
+                    return;
+                }
+                (
+                    Ok(_),
+                    Some(Outcome::Solutions {
+                        solutions, elapsed, ..
+                    }),
+                ) => {
+                    solved_all &= !solutions.is_empty();
+                    let solution = solutions
+                        .first()
+                        .map_or(Value::Null, |(solved, _)| line(solved));
+                    vec![
+                        ("solution", solution),
+                        ("solvable", Value::Bool(!solutions.is_empty())),
+                        ("time_ms", time_ms(elapsed)),
+                    ]
+                }
+                (Ok(_), Some(Outcome::TimedOut(elapsed))) => {
+                    solved_all = false;
+                    vec![
+                        ("solution", Value::Null),
+                        ("solvable", Value::Null),
+                        ("timed_out", Value::Bool(true)),
+                        ("time_ms", time_ms(elapsed)),
+                    ]
+                }
+                (Err(error), _) => {
+                    malformed = true;
+                    vec![("error", Value::String(format!("{}: {}", location, error)))]
+                }
+                (Ok(_), None) => unreachable!(),
+            };
+
+            let response = [(String::from("id"), id)]
+                .into_iter()
+                .chain(
+                    fields
+                        .into_iter()
+                        .map(|(key, value)| (String::from(key), value)),
+                )
+                .collect();
+            outln!(out, "{}", Value::Object(response));
         },
-        2 => match args[1].to_str() {
-            Some("--help" | "-h") => usage(),
-            Some("--lines") => Args {
-                file: None,
-                lines: true,
-            },
-            _ => Args {
-                file: Some(mem::take(&mut args[1])),
-                lines: false,
+    );
+
+    if malformed {
+        out.commit();
+        process::exit(1);
+    }
+
+    solved_all
+}
+
+fn is_safe_integer(number: f64) -> bool {
+    number.fract() == 0.0 && number.abs() < (1u64 << 53) as f64
+}
+
+// Grids other than 9x9 are only solved for a single solution, written as lines or grids
+fn solve_sized(
+    args: &Solve,
+    shape: Shape,
+    entries: impl Iterator<Item = Entry>,
+    out: &mut Output,
+) -> bool {
+    let unsupported = [
+        ("--all", args.all),
+        ("--count", args.count.is_some()),
+        ("--stats", args.stats || args.stats_json),
+        ("--timeout", args.timeout.is_some()),
+        ("--variant", !args.variant.is_empty()),
+        ("--jobs", args.jobs != 1),
+    ];
+    if let Some((flag, _)) = unsupported.iter().find(|&&(_, given)| given) {
+        fail(format_args!("{} isn't supported for {} grids", flag, shape));
+    }
+
+    let lines = match args.format {
+        None => args.input.lines,
+        Some(Format::Line) => true,
+        Some(Format::Grid) => false,
+        Some(format) => fail(format_args!(
+            "--format {:?} isn't supported for {} grids",
+            format, shape
+        )),
+    };
+
+    let mut solved_all = true;
+
+    for (i, entry) in entries.enumerate() {
+        let grid = SizedGrid::parse(&entry.text, shape)
+            .unwrap_or_else(|error| fail(format_args!("{}: {}", entry.location, error)));
+
+        let solved = grid.solve().unwrap_or_else(|| {
+            solved_all = false;
+            grid
+        });
+
+        if lines {
+            outln!(out, "{}", solved.to_line());
+        } else {
+            if i > 0 {
+                outln!(out);
+            }
+            outln!(out, "{}", solved);
+        }
+    }
+
+    solved_all
+}
+
+fn generate(args: &Generate, seed: u64, out: &mut Output) -> bool {
+    // Minimal puzzles rarely have more than 28 clues, and padding one out to --clues can make it
+    // easier than the requested difficulty, so puzzles are drawn again up to this many times
+    const ATTEMPTS: usize = 1000;
+
+    if verbosity() >= 1 {
+        eprintln!("lynx-sudoku: seed {}", seed);
+    }
+
+    let mut rng = SplitMix64::new(seed);
+
+    for _ in 0..args.count {
+        let generated = (0..ATTEMPTS).find_map(|_| {
+            let mut puzzle = match args.difficulty {
+                Some(difficulty) => Sudoku::generate_with_difficulty(difficulty, &mut rng)
+                    .unwrap_or_else(|| fail(format_args!("no {} puzzle found", difficulty))),
+                None => Sudoku::random_filled(&mut rng)
+                    .minimize(&mut rng)
+                    .expect("filled grids have a unique solution"),
+            };
+            let solution = puzzle.solve().expect("generated puzzles are solvable");
+
+            if let Some(clues) = args.clues {
+                let clues = clues as usize;
+                if puzzle.clue_count() > clues {
+                    return None;
+                }
+
+                // Clues taken from the solution keep it unique
+                let mut empty = puzzle.empty_cells().collect::<Vec<_>>();
+                rng.shuffle(&mut empty);
+                for &(x, y) in &empty[..clues - puzzle.clue_count()] {
+                    puzzle.set(x, y, solution.get(x, y));
+                }
+
+                if let Some(difficulty) = args.difficulty {
+                    if puzzle.rate().map(|rating| rating.difficulty) != Some(difficulty) {
+                        return None;
+                    }
+                }
+            }
+
+            Some((puzzle, solution))
+        });
+
+        let (puzzle, solution) = generated.unwrap_or_else(|| {
+            fail(format_args!(
+                "no puzzle matching the requested clues and difficulty after {} attempts",
+                ATTEMPTS
+            ))
+        });
+
+        if args.solutions {
+            outln!(out, "{} {}", puzzle.to_line(), solution.to_line());
+        } else {
+            outln!(out, "{}", puzzle.to_line());
+        }
+    }
+
+    true
+}
+
+// Diagnoses each puzzle on its own line, then summarizes on standard error. Malformed puzzles are
+// reported rather than ending the process, but still make it exit with status 1
+fn check(input: &Input, out: &mut Output) -> bool {
+    const NAMES: [&str; 5] = ["unique", "multiple", "unsolvable", "invalid", "malformed"];
+
+    let mut counts = [0; 5];
+
+    for entry in input.entries() {
+        let (status, details) = match entry.sudoku {
+            Err(error) => (4, error.to_string()),
+            Ok(sudoku) => {
+                let conflicts = sudoku.conflicts();
+                let clues = format!("{} clues", sudoku.clue_count());
+
+                if !conflicts.is_empty() {
+                    let conflicts = conflicts
+                        .iter()
+                        .map(|conflict| conflict.to_string())
+                        .collect::<Vec<_>>();
+                    (3, conflicts.join("; "))
+                } else {
+                    match sudoku.count_solutions(2) {
+                        0 => (2, clues),
+                        1 => (0, clues),
+                        _ => (1, clues),
+                    }
+                }
+            }
+        };
+
+        counts[status] += 1;
+        outln!(out, "{}: {}: {}", entry.location, NAMES[status], details);
+    }
+
+    let total = counts.iter().sum::<usize>();
+    let summary = NAMES
+        .iter()
+        .zip(counts)
+        .map(|(name, count)| format!("{} {}", count, name))
+        .collect::<Vec<_>>();
+    if verbosity() >= 0 {
+        eprintln!("{} puzzle(s): {}", total, summary.join(", "));
+    }
+
+    // The diagnoses are complete, so they are kept even so
+    if counts[4] > 0 {
+        out.commit();
+        process::exit(1);
+    }
+
+    counts[0] == total
+}
+
+// Reports each pair as correct, or why not, then summarizes on standard error like check. A pair is
+// either a line holding the puzzle and then the solution, or a line with only the puzzle followed by
+// a line with only the solution
+fn verify(input: &Input, out: &mut Output) -> bool {
+    let input = Input {
+        lines: true,
+        ..input.clone()
+    };
+
+    let mut entries = input
+        .entries()
+        .filter(|entry| !entry.text.trim().is_empty());
+    let mut pairs = 0;
+    let mut correct = 0;
+    let mut malformed = false;
+
+    while let Some(entry) = entries.next() {
+        pairs += 1;
+
+        let mut columns = entry.text.split_whitespace();
+        let puzzle = columns.next().unwrap_or_default();
+        let (solution, extra) = match columns.next() {
+            Some(solution) => (String::from(solution), columns.next()),
+            None => match entries.next() {
+                Some(next) => (String::from(next.text.trim()), None),
+                None => (String::new(), None),
             },
-        },
-        3 => {
-            if args[1].to_str() != Some("--lines") {
-                usage();
+        };
+
+        let parse = |string: &str, name| {
+            Sudoku::from_str(string).map_err(|error| format!("malformed {}: {}", name, error))
+        };
+
+        let verdict = extra
+            .map(|_| Err(String::from("malformed: more than two columns")))
+            .unwrap_or_else(|| Ok((parse(puzzle, "puzzle")?, parse(&solution, "solution")?)))
+            .map(|(puzzle, solution)| {
+                if !solution.is_complete() {
+                    let empty = solution.empty_cells().count();
+                    return format!("incorrect: {} empty cell(s)", empty);
+                }
+
+                if let Some(conflict) = solution.conflicts().first() {
+                    return format!("incorrect: {}", conflict);
+                }
+
+                match puzzle
+                    .difference(&solution)
+                    .iter()
+                    .find(|&(_, _, value)| value != 0)
+                {
+                    Some((x, y, value)) => format!(
+                        "incorrect: r{}c{} is {} in the solution but {} in the puzzle",
+                        y + 1,
+                        x + 1,
+                        solution.get(x, y),
+                        value
+                    ),
+                    None => String::from("correct"),
+                }
+            });
+
+        let verdict = verdict.unwrap_or_else(|error| {
+            malformed = true;
+            error
+        });
+
+        correct += (verdict == "correct") as usize;
+        outln!(out, "{}: {}", entry.location, verdict);
+    }
+
+    if verbosity() >= 0 {
+        eprintln!(
+            "{} pair(s): {} correct, {} not",
+            pairs,
+            correct,
+            pairs - correct
+        );
+    }
+
+    if malformed {
+        out.commit();
+        process::exit(1);
+    }
+
+    correct == pairs
+}
+
+// Prints each puzzle followed by its difficulty and score, so that a collection can be sorted on
+// either with sort -k
+fn rate(input: &Input, min: Option<Difficulty>, max: Option<Difficulty>, out: &mut Output) -> bool {
+    let filtered = min.is_some() || max.is_some();
+    let mut rated_all = true;
+
+    for sudoku in input.puzzles() {
+        match sudoku.rate() {
+            Some(rating)
+                if min.is_some_and(|min| rating.difficulty < min)
+                    || max.is_some_and(|max| rating.difficulty > max) => {}
+            Some(rating) => outln!(
+                out,
+                "{} {} {}",
+                sudoku.to_line(),
+                rating.difficulty,
+                rating.score
+            ),
+            None => {
+                rated_all = false;
+                if !filtered {
+                    outln!(out, "{} unrated", sudoku.to_line());
+                }
+            }
+        }
+    }
+
+    rated_all
+}
+
+// Numbers each step, with a guess wherever the techniques run dry. Guesses are taken from the
+// solution, so the puzzle must have exactly one
+fn explain(input: &Input, out: &mut Output) -> bool {
+    let mut explained_all = true;
+
+    for (i, (location, sudoku)) in input.located_puzzles().enumerate() {
+        if i > 0 {
+            outln!(out);
+        }
+        outln!(out, "{}: {}", location, sudoku.to_line());
+
+        let mut solutions = sudoku.solve_all();
+        let solution = match (solutions.next(), solutions.next()) {
+            (Some(solution), None) => solution,
+            _ => {
+                explained_all = false;
+                outln!(out, "no unique solution");
+                continue;
             }
-            Args {
-                file: Some(mem::take(&mut args[2])),
-                lines: true,
+        };
+
+        let mut sudoku = sudoku;
+        let mut steps = 0;
+        let mut guesses = 0;
+
+        loop {
+            let mut solver =
+                LogicalSolver::new(&sudoku).expect("solvable puzzles have no conflicts");
+
+            for step in solver.by_ref() {
+                steps += 1;
+                outln!(out, "{:>4}. {}", steps, step);
+            }
+
+            if solver.is_solved() {
+                break;
             }
+
+            // Guess in the cell with the fewest candidates, where a guess is most likely to be right
+            sudoku = solver.sudoku();
+            let (x, y, _) = sudoku
+                .iter()
+                .filter(|&(_, _, value)| value == 0)
+                .min_by_key(|&(x, y, _)| solver.candidates(x, y).count())
+                .expect("unsolved puzzles have an empty cell");
+            let candidates = solver.candidates(x, y).count();
+
+            steps += 1;
+            guesses += 1;
+            outln!(
+                out,
+                "{:>4}. requires guessing: r{}c{} is {}, one of {} candidates",
+                steps,
+                y + 1,
+                x + 1,
+                solution.get(x, y),
+                candidates
+            );
+            sudoku.set(x, y, solution.get(x, y));
         }
-        _ => usage(),
+
+        outln!(
+            out,
+            "solved in {} steps, {} of them guesses",
+            steps,
+            guesses
+        );
     }
+
+    explained_all
 }
 
-fn main() {
-    let args = parse_args();
+fn hint(input: &Input, out: &mut Output) -> bool {
+    let mut hinted_all = true;
 
-    let mut file: Box<dyn io::BufRead> = match args.file {
-        Some(file) => Box::new(io::BufReader::new(
-            fs::File::open(file).unwrap_or_else(handle_error),
-        )),
-        None => Box::new(io::BufReader::new(io::stdin())),
+    for (location, sudoku) in input.located_puzzles() {
+        match sudoku.hint() {
+            Some(hint) => {
+                let reason = match hint.reason {
+                    Reason::Technique(technique) => technique.to_string(),
+                    Reason::Search => String::from("search, since no technique applies"),
+                };
+                outln!(
+                    out,
+                    "{}: r{}c{} is {} by {}",
+                    location,
+                    hint.y + 1,
+                    hint.x + 1,
+                    hint.value,
+                    reason
+                );
+            }
+            None if sudoku.is_complete() => outln!(out, "{}: already solved", location),
+            None => {
+                hinted_all = false;
+                outln!(out, "{}: no unique solution", location);
+            }
+        }
+    }
+
+    hinted_all
+}
+
+fn count(input: &Input, limit: usize, out: &mut Output) -> bool {
+    let mut solvable_all = true;
+
+    for sudoku in input.puzzles() {
+        let count = sudoku.count_solutions(limit);
+        solvable_all &= count > 0;
+
+        if count == limit {
+            outln!(out, "{} {}+", sudoku.to_line(), count);
+        } else {
+            outln!(out, "{} {}", sudoku.to_line(), count);
+        }
+    }
+
+    solvable_all
+}
+
+fn minimize(input: &Input, seed: u64, out: &mut Output) -> bool {
+    if verbosity() >= 1 {
+        eprintln!("lynx-sudoku: seed {}", seed);
+    }
+
+    let mut rng = SplitMix64::new(seed);
+    let mut minimized_all = true;
+
+    // Each puzzle is followed by the number of clues removed, on the same line in --lines mode
+    for sudoku in input.puzzles() {
+        let (sudoku, note) = match sudoku.minimize(&mut rng) {
+            Some(minimized) => {
+                let removed = sudoku.clue_count() - minimized.clue_count();
+                (minimized, format!("{} clues removed", removed))
+            }
+            None => {
+                minimized_all = false;
+                (sudoku, String::from("no unique solution"))
+            }
+        };
+
+        if input.lines {
+            outln!(out, "{} {}", sudoku.to_line(), note);
+        } else {
+            outln!(out, "{}", sudoku);
+            outln!(out, "{}", note);
+        }
+    }
+
+    minimized_all
+}
+
+// Reports on one line per backend. Every puzzle is kept in memory, so that reading the input isn't
+// timed along with solving it
+fn bench(input: &Input, passes: usize, warmup: usize, backend: Backend, out: &mut Output) -> bool {
+    let puzzles = input.puzzles().collect::<Vec<_>>();
+    let mut solved_all = true;
+
+    let backends = match backend {
+        Backend::Both => &[Backend::Dlx, Backend::Fast][..],
+        Backend::Dlx => &[Backend::Dlx],
+        Backend::Fast => &[Backend::Fast],
     };
 
-    if args.lines {
-        for line in file.lines() {
-            let sudoku = lynx::sudoku::Sudoku::from_str(&line.unwrap_or_else(handle_error))
-                .unwrap_or_else(handle_error);
-            let solved = sudoku.solve();
-            println!("{}", solved.unwrap_or(sudoku).to_string_line());
+    for &backend in backends {
+        let mut latencies = Vec::with_capacity(puzzles.len() * passes);
+        let mut nodes = 0;
+        let mut backtracks = 0;
+
+        for pass in 0..warmup + passes {
+            for sudoku in &puzzles {
+                let start = Instant::now();
+                let solved = match backend {
+                    Backend::Fast => sudoku.solve_fast(),
+                    _ => {
+                        let (solved, stats) = sudoku.solve_with_stats();
+                        if pass >= warmup {
+                            nodes += stats.nodes;
+                            backtracks += stats.backtracks;
+                        }
+                        solved
+                    }
+                };
+                let elapsed = start.elapsed();
+
+                if pass >= warmup {
+                    solved_all &= solved.is_some();
+                    latencies.push(elapsed);
+                }
+            }
         }
-    } else {
-        let string = {
-            let mut string = String::new();
-            file.read_to_string(&mut string)
-                .unwrap_or_else(handle_error);
-            string
+
+        latencies.sort_unstable();
+        let total = latencies.iter().sum::<Duration>();
+        let percentile = |p: f64| {
+            let rank = ((p * latencies.len() as f64).ceil() as usize).max(1);
+            latencies
+                .get(rank - 1)
+                .map_or(0.0, |latency| latency.as_secs_f64() * 1000.0)
         };
-        let sudoku = lynx::sudoku::Sudoku::from_str(&string).unwrap_or_else(handle_error);
-        let solved = sudoku.solve();
-        println!("{}", solved.unwrap_or(sudoku));
+        let solves = latencies.len().max(1) as f64;
+
+        let mut line = format!(
+            "{}: {} puzzle(s) x {} pass(es) in {:.3} s, {:.0} puzzles/s, \
+             p50 {:.3} ms, p90 {:.3} ms, p99 {:.3} ms, max {:.3} ms",
+            backend
+                .to_possible_value()
+                .expect("backends have names")
+                .get_name(),
+            puzzles.len(),
+            passes,
+            total.as_secs_f64(),
+            latencies.len() as f64 / total.as_secs_f64().max(f64::EPSILON),
+            percentile(0.5),
+            percentile(0.9),
+            percentile(0.99),
+            percentile(1.0)
+        );
+
+        if backend == Backend::Dlx {
+            line += &format!(
+                ", mean {:.1} nodes, {:.1} backtracks",
+                nodes as f64 / solves,
+                backtracks as f64 / solves
+            );
+        }
+
+        outln!(out, "{}", line);
+    }
+
+    solved_all
+}
+
+// Cells which agree are shown once, and cells which don't as first>second, highlighted when
+// written to a terminal. The differences are then listed on a line of their own
+fn diff(first: &Path, second: &Path, out: &mut Output) -> bool {
+    // Standard input can only be read once, so the second grid would always come up empty
+    if first == Path::new("-") && second == Path::new("-") {
+        let mut command = Cli::command();
+        command.build();
+        let error = command
+            .find_subcommand_mut("diff")
+            .expect("diff is a subcommand")
+            .error(
+                ErrorKind::ArgumentConflict,
+                "only one of the grids can be read from standard input",
+            );
+        let _ = error.print();
+        process::exit(1);
+    }
+
+    let read = |file: &Path| {
+        let input = Input {
+            lines: false,
+            files: vec![file.to_path_buf()],
+        };
+        input
+            .puzzles()
+            .next()
+            .unwrap_or_else(|| fail(format_args!("{}: no grid to compare", file.display())))
+    };
+    let (first, second) = (read(first), read(second));
+
+    let symbol = |value: u8| match value {
+        0 => '.',
+        value => (b'0' + value) as char,
+    };
+    let highlight = out.is_terminal();
+    let mut differences = vec![];
+
+    for y in 0..9 {
+        if y > 0 && y % 3 == 0 {
+            outln!(out, "---------+---------+---------");
+        }
+
+        let mut row = String::new();
+        for x in 0..9 {
+            if x > 0 && x % 3 == 0 {
+                row.push('|');
+            }
+
+            let (a, b) = (first.get(x, y), second.get(x, y));
+            if a == b {
+                row += &format!(" {} ", symbol(a));
+                continue;
+            }
+
+            differences.push(format!("r{}c{} {}>{}", y + 1, x + 1, symbol(a), symbol(b)));
+            let cell = format!("{}>{}", symbol(a), symbol(b));
+            if highlight {
+                row += &format!("\x1b[1;31m{}\x1b[0m", cell);
+            } else {
+                row += &cell;
+            }
+        }
+
+        outln!(out, "{}", row);
+    }
+
+    if differences.is_empty() {
+        outln!(out, "identical");
+    } else {
+        outln!(
+            out,
+            "{} cell(s) differ: {}",
+            differences.len(),
+            differences.join(", ")
+        );
     }
+
+    true
+}
+
+fn canon(input: &Input, dedupe: bool, out: &mut Output) -> bool {
+    let mut seen = HashSet::new();
+    let mut duplicates = 0;
+
+    for sudoku in input.puzzles() {
+        let canonical = sudoku.canonical_form();
+
+        if dedupe && !seen.insert(canonical.to_line()) {
+            duplicates += 1;
+            continue;
+        }
+
+        print_sudoku(&canonical, input, out);
+    }
+
+    if dedupe && verbosity() >= 0 {
+        eprintln!("{} duplicate(s) dropped", duplicates);
+    }
+
+    true
+}
+
+// Puzzles read one per line are written one per line; a whole-file puzzle is written as a grid
+fn print_sudoku(sudoku: &Sudoku, input: &Input, out: &mut Output) {
+    if input.lines {
+        outln!(out, "{}", sudoku.to_line());
+    } else {
+        outln!(out, "{}", sudoku);
+    }
+}
+
+fn time_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or(0)
 }
 
-fn handle_error<E: fmt::Display, R>(error: E) -> R {
-    _ = eprintln!("{}", error);
+fn fail(message: fmt::Arguments) -> ! {
+    output::discard_pending();
+    eprintln!("lynx-sudoku: {}", message);
     process::exit(1);
 }
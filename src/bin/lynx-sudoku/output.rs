@@ -0,0 +1,339 @@
+use crate::fail;
+use lynx::json::Value;
+use lynx::sudoku::{SolveStats, Sudoku};
+use lynx::writer::csv_field;
+
+use clap::ValueEnum;
+use core::fmt;
+use core::time::Duration;
+use std::fs;
+use std::io;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Mutex;
+
+// The temporary file being written in place of the output file, so that it can be removed if the
+// process fails before the output is committed
+static PENDING: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+// Where results are written: standard output, or a file which is only replaced (or appended to)
+// once the run is over, by renaming a temporary file alongside it over it
+pub struct Output {
+    writer: Box<dyn Write>,
+    // The temporary file and the path it will be renamed to
+    target: Option<(PathBuf, PathBuf)>,
+}
+
+impl Output {
+    pub fn open(path: Option<&Path>, append: bool) -> io::Result<Self> {
+        let path = match path {
+            Some(path) => path,
+            None => {
+                return Ok(Self {
+                    writer: Box::new(io::stdout()),
+                    target: None,
+                })
+            }
+        };
+
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp = path.with_file_name(format!(".{}.{}.tmp", name, process::id()));
+        *PENDING.lock().unwrap() = Some(temp.clone());
+
+        if append && path.exists() {
+            fs::copy(path, &temp)?;
+        }
+
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(append)
+            .truncate(!append)
+            .open(&temp)?;
+
+        Ok(Self {
+            writer: Box::new(io::BufWriter::new(file)),
+            target: Some((temp, path.to_path_buf())),
+        })
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.target.is_some()
+    }
+
+    pub fn is_terminal(&self) -> bool {
+        self.target.is_none() && io::stdout().is_terminal()
+    }
+
+    // Errors writing the output end the process
+    pub fn println(&mut self, args: fmt::Arguments) {
+        self.print(format_args!("{}\n", args));
+    }
+
+    pub fn print(&mut self, args: fmt::Arguments) {
+        if let Err(error) = self.writer.write_fmt(args) {
+            self.fail(error);
+        }
+    }
+
+    pub fn flush(&mut self) {
+        if let Err(error) = self.writer.flush() {
+            self.fail(error);
+        }
+    }
+
+    // Flushes the output and moves the file into place. Nothing more should be written afterwards
+    pub fn commit(&mut self) {
+        if let Err(error) = self.writer.flush() {
+            self.fail(error);
+        }
+
+        if let Some((temp, path)) = &self.target {
+            if let Err(error) = fs::rename(temp, path) {
+                self.fail(error);
+            }
+            *PENDING.lock().unwrap() = None;
+        }
+    }
+
+    fn fail(&self, error: io::Error) -> ! {
+        match &self.target {
+            Some((_, path)) => fail(format_args!("{}: {}", path.display(), error)),
+            None => fail(format_args!("<stdout>: {}", error)),
+        }
+    }
+}
+
+// Removes the temporary file of an output which won't be committed
+pub fn discard_pending() {
+    if let Some(temp) = PENDING.lock().map_or(None, |mut pending| pending.take()) {
+        let _ = fs::remove_file(temp);
+    }
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    // 81 characters per puzzle
+    Line,
+    // Nine lines of nine characters
+    Grid,
+    // A grid with box-drawing borders
+    Pretty,
+    // One JSON object per line, with the source file and line, input, solution, solvable flag (null
+    // if the puzzle timed out), and timing
+    Json,
+    // A header row, then the same fields as the JSON output
+    Csv,
+}
+
+// Writes one record per solution found, or per puzzle without one. The plain text formats echo a
+// puzzle which has no solution, or which timed out, in place of one unless told not to
+pub struct Writer {
+    format: Format,
+    // Whether the plain text formats echo puzzles without a solution, or leave them out
+    echo: bool,
+    records: usize,
+}
+
+impl Writer {
+    pub fn new(format: Format, echo: bool) -> Self {
+        Self {
+            format,
+            echo,
+            records: 0,
+        }
+    }
+
+    // The location names the file and line the input came from
+    pub fn record(
+        &mut self,
+        out: &mut Output,
+        location: &str,
+        input: &Sudoku,
+        solution: Option<&Sudoku>,
+        elapsed: Duration,
+    ) {
+        self.write(
+            out,
+            location,
+            input,
+            solution,
+            Some(solution.is_some()),
+            elapsed,
+        );
+    }
+
+    pub fn timed_out(
+        &mut self,
+        out: &mut Output,
+        location: &str,
+        input: &Sudoku,
+        elapsed: Duration,
+    ) {
+        self.write(out, location, input, None, None, elapsed);
+    }
+
+    fn write(
+        &mut self,
+        out: &mut Output,
+        location: &str,
+        input: &Sudoku,
+        solution: Option<&Sudoku>,
+        solvable: Option<bool>,
+        elapsed: Duration,
+    ) {
+        let plain = matches!(self.format, Format::Line | Format::Grid | Format::Pretty);
+        if plain && solution.is_none() && !self.echo {
+            return;
+        }
+
+        let first = self.records == 0;
+        self.records += 1;
+
+        let time_ms = elapsed.as_secs_f64() * 1000.0;
+
+        match self.format {
+            Format::Line => outln!(out, "{}", solution.unwrap_or(input).to_line()),
+            Format::Grid | Format::Pretty => {
+                // Blank lines keep consecutive grids apart
+                if !first {
+                    outln!(out);
+                }
+
+                let sudoku = solution.unwrap_or(input);
+                if self.format == Format::Grid {
+                    outln!(out, "{}", sudoku);
+                } else {
+                    outln!(out, "{}", sudoku.to_pretty());
+                }
+            }
+            Format::Json => {
+                let solvable =
+                    solvable.map_or(String::from("null"), |solvable| solvable.to_string());
+                let solution = match solution {
+                    Some(solution) => format!("\"{}\"", solution.to_line()),
+                    None => String::from("null"),
+                };
+                outln!(
+                    out,
+                    "{{\"source\":{},\"input\":\"{}\",\"solution\":{},\"solvable\":{},\"time_ms\":{:.3}}}",
+                    Value::String(String::from(location)),
+                    input.to_line(),
+                    solution,
+                    solvable,
+                    time_ms
+                );
+            }
+            Format::Csv => {
+                if first {
+                    outln!(out, "source,input,solution,solvable,time_ms");
+                }
+                outln!(
+                    out,
+                    "{},{},{},{},{:.3}",
+                    csv_field(location),
+                    input.to_line(),
+                    solution.map(Sudoku::to_line).unwrap_or_default(),
+                    solvable
+                        .map(|solvable| solvable.to_string())
+                        .unwrap_or_default(),
+                    time_ms
+                );
+            }
+        }
+    }
+}
+
+// Per-puzzle solver statistics and their totals, written to standard error so that they don't mix
+// with the solutions
+pub struct StatsReport {
+    json: bool,
+    puzzles: usize,
+    solved: usize,
+    timed_out: usize,
+    nodes: u64,
+    backtracks: u64,
+    total: Duration,
+    max: Duration,
+}
+
+impl StatsReport {
+    pub fn new(json: bool) -> Self {
+        Self {
+            json,
+            puzzles: 0,
+            solved: 0,
+            timed_out: 0,
+            nodes: 0,
+            backtracks: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+
+    pub fn puzzle(&mut self, stats: &SolveStats, solved: bool) {
+        self.puzzles += 1;
+        self.solved += solved as usize;
+        self.nodes += stats.nodes;
+        self.backtracks += stats.backtracks;
+        self.total += stats.elapsed;
+        self.max = self.max.max(stats.elapsed);
+
+        let time_ms = stats.elapsed.as_secs_f64() * 1000.0;
+
+        if self.json {
+            eprintln!(
+                "{{\"puzzle\":{},\"solved\":{},\"time_ms\":{:.3},\"nodes\":{},\"backtracks\":{},\"propagated\":{}}}",
+                self.puzzles, solved, time_ms, stats.nodes, stats.backtracks, stats.propagated
+            );
+        } else {
+            eprintln!(
+                "puzzle {}: {} in {:.3} ms, {} nodes, {} backtracks, {} cells propagated",
+                self.puzzles,
+                if solved { "solved" } else { "unsolvable" },
+                time_ms,
+                stats.nodes,
+                stats.backtracks,
+                stats.propagated
+            );
+        }
+    }
+
+    // Timed out puzzles count towards the times, but have no node counts to contribute
+    pub fn timed_out(&mut self, elapsed: Duration) {
+        self.puzzles += 1;
+        self.timed_out += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+
+        let time_ms = elapsed.as_secs_f64() * 1000.0;
+
+        if self.json {
+            eprintln!(
+                "{{\"puzzle\":{},\"solved\":false,\"timed_out\":true,\"time_ms\":{:.3}}}",
+                self.puzzles, time_ms
+            );
+        } else {
+            eprintln!("puzzle {}: timed out after {:.3} ms", self.puzzles, time_ms);
+        }
+    }
+
+    pub fn finish(&self) {
+        let total_ms = self.total.as_secs_f64() * 1000.0;
+        let mean_ms = total_ms / self.puzzles.max(1) as f64;
+        let max_ms = self.max.as_secs_f64() * 1000.0;
+
+        if self.json {
+            eprintln!(
+                "{{\"puzzles\":{},\"solved\":{},\"timed_out\":{},\"total_ms\":{:.3},\"mean_ms\":{:.3},\"max_ms\":{:.3},\"nodes\":{},\"backtracks\":{}}}",
+                self.puzzles, self.solved, self.timed_out, total_ms, mean_ms, max_ms, self.nodes, self.backtracks
+            );
+        } else {
+            eprintln!(
+                "{} puzzle(s), {} solved, {} timed out in {:.3} ms (mean {:.3} ms, max {:.3} ms), {} nodes, {} backtracks",
+                self.puzzles, self.solved, self.timed_out, total_ms, mean_ms, max_ms, self.nodes, self.backtracks
+            );
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+// Items read ahead of the last one emitted, per worker; enough to keep every worker busy while a
+// slow puzzle holds up the output
+const QUEUE_PER_JOB: usize = 64;
+
+// Applies f to each item on up to jobs threads, passing the results to emit in input order as soon
+// as every earlier one has been emitted. Items are read on a thread of their own, so a result is
+// never held back waiting on more input, as it would be for a process answering requests over a
+// pipe, and no more than a bounded number are read ahead, so memory stays bounded however long the
+// input is
+pub fn for_each_ordered<T, R, I, F, E>(items: I, jobs: usize, f: F, mut emit: E)
+where
+    T: Send,
+    R: Send,
+    I: Iterator<Item = T> + Send,
+    F: Fn(&T) -> R + Sync,
+    E: FnMut(T, R),
+{
+    if jobs <= 1 {
+        for item in items {
+            let result = f(&item);
+            emit(item, result);
+        }
+        return;
+    }
+
+    let limit = jobs * QUEUE_PER_JOB;
+
+    // The reader takes a permit for each item and the emitter returns it, bounding how many are in
+    // flight at once
+    let (permits, permit) = mpsc::sync_channel::<()>(limit);
+    let (queue, jobs_rx) = mpsc::sync_channel::<(usize, T)>(limit);
+    let (done, results) = mpsc::channel::<(usize, T, R)>();
+    let jobs_rx = Mutex::new(jobs_rx);
+
+    thread::scope(|scope| {
+        scope.spawn(move || {
+            for job in items.enumerate() {
+                if permits.send(()).is_err() || queue.send(job).is_err() {
+                    break;
+                }
+            }
+        });
+
+        for _ in 0..jobs {
+            let (jobs_rx, f, done) = (&jobs_rx, &f, done.clone());
+            scope.spawn(move || loop {
+                let job = jobs_rx.lock().expect("worker thread panicked").recv();
+                let Ok((index, item)) = job else {
+                    break;
+                };
+
+                let result = f(&item);
+                if done.send((index, item, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(done);
+
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+
+        for (index, item, result) in results {
+            pending.insert(index, (item, result));
+
+            while let Some((item, result)) = pending.remove(&next) {
+                emit(item, result);
+                next += 1;
+                let _ = permit.recv();
+            }
+        }
+    });
+}
@@ -0,0 +1,112 @@
+use core::fmt;
+use core::time::Duration;
+use std::io;
+use std::io::{IsTerminal, Write};
+use std::time::Instant;
+
+// Redrawing more often than this costs more than it tells
+const REDRAW_INTERVAL: Duration = Duration::from_millis(100);
+
+// A one-line progress bar on standard error, redrawn in place. The total is only known when every
+// input is a file, in which case the bar shows how far along the run is and when it should finish
+pub struct Progress {
+    total: Option<usize>,
+    done: usize,
+    start: Instant,
+    drawn: Option<Instant>,
+}
+
+impl Progress {
+    // None unless standard error is a terminal, so that nothing is drawn into a pipe or log file
+    pub fn new(total: Option<usize>) -> Option<Self> {
+        if !io::stderr().is_terminal() {
+            return None;
+        }
+
+        Some(Self {
+            total,
+            done: 0,
+            start: Instant::now(),
+            drawn: None,
+        })
+    }
+
+    pub fn tick(&mut self) {
+        self.done += 1;
+
+        let now = Instant::now();
+        if self
+            .drawn
+            .is_some_and(|drawn| now.duration_since(drawn) < REDRAW_INTERVAL)
+        {
+            return;
+        }
+
+        self.drawn = Some(now);
+        self.draw();
+    }
+
+    // Erases the bar, so that other messages can be written to standard error. It reappears at
+    // the next redraw
+    pub fn clear(&mut self) {
+        if self.drawn.take().is_some() {
+            eprint!("\r\x1b[K");
+        }
+    }
+
+    pub fn finish(mut self) {
+        self.clear();
+    }
+
+    fn draw(&self) {
+        const WIDTH: usize = 30;
+
+        let elapsed = self.start.elapsed().as_secs_f64();
+        let rate = self.done as f64 / elapsed.max(f64::EPSILON);
+
+        let line = match self.total {
+            Some(total) if total > 0 => {
+                let fraction = (self.done as f64 / total as f64).min(1.0);
+                let filled = (fraction * WIDTH as f64) as usize;
+                let remaining = total.saturating_sub(self.done) as f64 / rate.max(f64::EPSILON);
+
+                format!(
+                    "[{}{}] {}/{} ({:.1}%), {:.0}/s, ETA {}",
+                    "#".repeat(filled),
+                    "-".repeat(WIDTH - filled),
+                    self.done,
+                    total,
+                    fraction * 100.0,
+                    rate,
+                    Clock(remaining)
+                )
+            }
+            _ => format!(
+                "{} puzzles, {:.0}/s, {} elapsed",
+                self.done,
+                rate,
+                Clock(elapsed)
+            ),
+        };
+
+        let mut stderr = io::stderr().lock();
+        let _ = write!(stderr, "\r\x1b[K{}", line);
+        let _ = stderr.flush();
+    }
+}
+
+// Seconds as h:mm:ss, or m:ss under an hour
+struct Clock(f64);
+
+impl fmt::Display for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let seconds = self.0.round() as u64;
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+        if hours > 0 {
+            write!(f, "{}:{:02}:{:02}", hours, minutes, seconds)
+        } else {
+            write!(f, "{}:{:02}", minutes, seconds)
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use lynx::sudoku::{Digit, Grid, Sudoku16};
+
+use core::fmt;
+
+// Digits beyond 9 are written as letters, as in most collections of large puzzles. Some 16x16
+// collections count from 0 instead, which is told apart by the presence of a 0
+const SYMBOLS: &str = "123456789ABCDEFGHIJKLMNOP";
+const HEX_SYMBOLS: &str = "0123456789ABCDEF";
+
+// The box dimensions used for each grid size when they aren't given, as (width, height)
+const DEFAULT_BOXES: [(usize, usize); 7] = [(2, 2), (3, 2), (4, 2), (3, 3), (4, 3), (4, 4), (5, 5)];
+
+// The dimensions of a box, which fix those of the grid: a grid is as wide as a box has cells
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Shape {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Shape {
+    pub fn size(self) -> usize {
+        self.width * self.height
+    }
+
+    pub fn for_size(size: usize) -> Option<Self> {
+        DEFAULT_BOXES
+            .into_iter()
+            .find(|&(width, height)| width.checked_mul(height) == Some(size))
+            .map(|(width, height)| Self { width, height })
+    }
+
+    // Guesses the shape from the number of cells, which is all there is to go on in a line
+    pub fn detect(text: &str) -> Option<Self> {
+        let cells = text.chars().filter(|ch| !ch.is_whitespace()).count();
+        DEFAULT_BOXES
+            .into_iter()
+            .map(|(width, height)| Self { width, height })
+            .find(|shape| shape.size() * shape.size() == cells)
+    }
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}x{}", self.size(), self.size())
+    }
+}
+
+pub fn parse_shape(string: &str) -> Result<Shape, String> {
+    let (width, height): (usize, usize) = string
+        .split_once('x')
+        .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+        .ok_or_else(|| format!("expected WIDTHxHEIGHT, such as 3x4, not '{}'", string))?;
+
+    match width.checked_mul(height) {
+        Some(2..=25) => Ok(Shape { width, height }),
+        _ => Err(String::from("boxes must have between 2 and 25 cells")),
+    }
+}
+
+// Calls solve_grid for the grid size given at runtime, which must be one of those listed
+macro_rules! solve_grid {
+    ($cells:expr, $shape:expr, $($n:literal)*) => {
+        match $shape.size() {
+            $($n => solve_grid::<$n>($cells, $shape),)*
+            size => unreachable!("unsupported grid size {}", size),
+        }
+    };
+}
+
+// Solves with the library: Sudoku16 for 16x16 grids with 4x4 boxes, and otherwise a Grid of the
+// right size, whose boxes can be any shape
+fn solve_grid<const N: usize>(cells: &[u8], shape: Shape) -> Option<Vec<u8>> {
+    let cell = |i: usize| (i % N, i / N);
+
+    if N == 16 && shape.width == 4 {
+        let mut sudoku = Sudoku16::new();
+        for (i, &value) in cells.iter().enumerate() {
+            let (x, y) = cell(i);
+            sudoku.set(x, y, value);
+        }
+
+        let solved = sudoku.solve()?;
+        return Some(
+            (0..N * N)
+                .map(|i| solved.get(cell(i).0, cell(i).1))
+                .collect(),
+        );
+    }
+
+    let mut grid = Grid::<N>::new();
+    for (i, &value) in cells.iter().enumerate() {
+        if let Some(digit) = Digit::new(value) {
+            let (x, y) = cell(i);
+            grid.set(x, y, digit);
+        }
+    }
+
+    let solved = grid.solve_with_boxes(shape.width, shape.height)?;
+    Some(
+        (0..N * N)
+            .map(|i| solved.get(cell(i).0, cell(i).1).map_or(0, Digit::get))
+            .collect(),
+    )
+}
+
+// A grid of any size up to 25x25 with rectangular boxes, for the sizes the Sudoku type doesn't
+// cover. Cells are held row by row, with 0 for an empty cell
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SizedGrid {
+    shape: Shape,
+    symbols: &'static str,
+    cells: Vec<u8>,
+}
+
+impl SizedGrid {
+    pub fn parse(text: &str, shape: Shape) -> Result<Self, String> {
+        let size = shape.size();
+        let symbols = if size == 16 && text.contains('0') {
+            HEX_SYMBOLS
+        } else {
+            &SYMBOLS[..size]
+        };
+
+        let mut cells = Vec::with_capacity(size * size);
+
+        for ch in text.chars().filter(|ch| !ch.is_whitespace()) {
+            let value = match symbols.find(ch.to_ascii_uppercase()) {
+                Some(index) => index as u8 + 1,
+                None if ch == '.' || (ch == '0' && symbols != HEX_SYMBOLS) => 0,
+                None => {
+                    return Err(format!(
+                        "Invalid character {:?} in {} grid (valid characters are {} and '.')",
+                        ch, shape, symbols
+                    ))
+                }
+            };
+            cells.push(value);
+        }
+
+        if cells.len() != size * size {
+            return Err(format!(
+                "{} grid has {} cell(s) (must be exactly {} cells)",
+                shape,
+                cells.len(),
+                size * size
+            ));
+        }
+
+        Ok(Self {
+            shape,
+            symbols,
+            cells,
+        })
+    }
+
+    pub fn solve(&self) -> Option<Self> {
+        let cells = solve_grid!(
+            &self.cells,
+            self.shape,
+            2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25
+        )?;
+
+        Some(Self {
+            cells,
+            ..self.clone()
+        })
+    }
+
+    pub fn to_line(&self) -> String {
+        let symbols = self.symbols.as_bytes();
+
+        self.cells
+            .iter()
+            .map(|&value| match value {
+                0 => '.',
+                value => symbols[value as usize - 1] as char,
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for SizedGrid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let size = self.shape.size();
+        let line = self.to_line();
+
+        for y in 0..size {
+            write!(f, "{}", &line[size * y..size * (y + 1)])?;
+
+            if y + 1 < size {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
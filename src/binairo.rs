@@ -0,0 +1,355 @@
+use crate::DLXMatrix;
+
+use core::fmt;
+use core::str::FromStr;
+
+// A binairo (or takuzu) grid of 0s and 1s, with each row and column holding as many of one as the
+// other, no three of the same in a row across or down, and no two rows or two columns the same.
+// Cells are None until they're filled in
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Binairo {
+    width: usize,
+    height: usize,
+    cells: Vec<Option<bool>>,
+}
+
+// A row or column of the grid, and the pattern a row of the matrix fills it with
+type Placement = (bool, usize, Vec<bool>);
+
+impl Binairo {
+    pub fn new(width: usize, height: usize) -> Self {
+        assert!(
+            width.is_multiple_of(2) && height.is_multiple_of(2),
+            "Width and height must both be even (got {}x{})",
+            width,
+            height
+        );
+
+        Self {
+            width,
+            height,
+            cells: vec![None; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        assert!(x < self.width && y < self.height);
+        self.cells[self.width * y + x] = Some(value);
+    }
+
+    pub fn clear(&mut self, x: usize, y: usize) {
+        assert!(x < self.width && y < self.height);
+        self.cells[self.width * y + x] = None;
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<bool> {
+        assert!(x < self.width && y < self.height);
+        self.cells[self.width * y + x]
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.cells.iter().all(Option::is_some)
+    }
+
+    pub fn solve(&self) -> Option<Binairo> {
+        self.solutions().next()
+    }
+
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.encode().0.count_solutions(limit)
+    }
+
+    pub fn solutions(&self) -> impl Iterator<Item = Binairo> + '_ {
+        let (matrix, placements) = self.encode();
+
+        matrix.solutions().row_indices().map(move |rows| {
+            let mut solved = self.clone();
+            for row in rows {
+                let (down, i, ref pattern) = placements[row];
+                for (j, &value) in pattern.iter().enumerate() {
+                    let (x, y) = if down { (i, j) } else { (j, i) };
+                    solved.set(x, y, value);
+                }
+            }
+            solved
+        })
+    }
+
+    // One primary column per row and per column of the grid, then a secondary column per cell
+    // colored by its value, then an uncolored secondary column for each pattern a row could take
+    // and each a column could take, so that no pattern is used twice. A row of the matrix fills a
+    // whole row or column of the grid with a pattern that agrees with the givens there
+    fn encode(&self) -> (DLXMatrix<u32>, Vec<Placement>) {
+        let (width, height) = (self.width, self.height);
+        let across = patterns(width);
+        let down = if height == width {
+            across.clone()
+        } else {
+            patterns(height)
+        };
+
+        let cell_base = width + height;
+        let across_base = cell_base + width * height;
+        let down_base = across_base + across.len();
+
+        let mut matrix = DLXMatrix::with_secondary_columns(
+            (width + height) as u32,
+            (width * height + across.len() + down.len()) as u32,
+        );
+        let mut placements = vec![];
+        let mut row = vec![];
+
+        for (is_down, lines, patterns, pattern_base) in [
+            (false, height, &across, across_base),
+            (true, width, &down, down_base),
+        ] {
+            for i in 0..lines {
+                let item = if is_down { height + i } else { i };
+                let cell = |j: usize| match is_down {
+                    true => width * j + i,
+                    false => width * i + j,
+                };
+
+                for (p, pattern) in patterns.iter().enumerate() {
+                    let fits = pattern
+                        .iter()
+                        .enumerate()
+                        .all(|(j, &value)| self.cells[cell(j)].is_none_or(|given| given == value));
+                    if !fits {
+                        continue;
+                    }
+
+                    row.clear();
+                    row.push((item as u32, None));
+                    row.extend(
+                        pattern
+                            .iter()
+                            .enumerate()
+                            .map(|(j, &value)| ((cell_base + cell(j)) as u32, Some(value as u32))),
+                    );
+                    row.push(((pattern_base + p) as u32, None));
+
+                    matrix.push_colored_row(&row);
+                    placements.push((is_down, i, pattern.clone()));
+                }
+            }
+        }
+
+        (matrix, placements)
+    }
+}
+
+// Every balanced sequence of the given length with no three of the same value in a row
+fn patterns(length: usize) -> Vec<Vec<bool>> {
+    fn extend(length: usize, pattern: &mut Vec<bool>, ones: usize, patterns: &mut Vec<Vec<bool>>) {
+        let i = pattern.len();
+        if i == length {
+            patterns.push(pattern.clone());
+            return;
+        }
+
+        for value in [false, true] {
+            let count = if value { ones + 1 } else { i + 1 - ones };
+            let triple = i >= 2 && pattern[i - 1] == value && pattern[i - 2] == value;
+
+            if count <= length / 2 && !triple {
+                pattern.push(value);
+                extend(length, pattern, ones + value as usize, patterns);
+                pattern.pop();
+            }
+        }
+    }
+
+    let mut patterns = vec![];
+    extend(length, &mut vec![], 0, &mut patterns);
+    patterns
+}
+
+// Rows of '0', '1', and '.' for an empty cell
+impl FromStr for Binairo {
+    type Err = ParseBinairoError;
+
+    fn from_str(string: &str) -> Result<Self, ParseBinairoError> {
+        let lines: Vec<&str> = string
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        let mut cells = vec![];
+
+        for (y, line) in lines.iter().enumerate() {
+            if line.chars().count() != width {
+                return Err(ParseBinairoError::Ragged { row: y });
+            }
+
+            for ch in line.chars() {
+                cells.push(match ch {
+                    '0' => Some(false),
+                    '1' => Some(true),
+                    '.' => None,
+                    ch => return Err(ParseBinairoError::InvalidCharacter { ch }),
+                });
+            }
+        }
+
+        let height = lines.len();
+        if !width.is_multiple_of(2) || !height.is_multiple_of(2) {
+            return Err(ParseBinairoError::OddDimensions { width, height });
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseBinairoError {
+    Ragged { row: usize },
+    InvalidCharacter { ch: char },
+    OddDimensions { width: usize, height: usize },
+}
+
+impl fmt::Display for ParseBinairoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            ParseBinairoError::Ragged { row } => {
+                write!(f, "Row {} is not as wide as the first row", row + 1)
+            }
+            ParseBinairoError::InvalidCharacter { ch } => {
+                write!(f, "Invalid character {:?} (expected '0', '1', or '.')", ch)
+            }
+            ParseBinairoError::OddDimensions { width, height } => {
+                write!(f, "Grid is {}x{}, but both must be even", width, height)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Binairo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (y, row) in self.cells.chunks(self.width.max(1)).enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for &cell in row {
+                let ch = match cell {
+                    Some(false) => '0',
+                    Some(true) => '1',
+                    None => '.',
+                };
+                write!(f, "{}", ch)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::binairo::{Binairo, ParseBinairoError};
+
+    const PUZZLE: &str = "
+        ......
+        1...1.
+        0.0...
+        ..0...
+        0..1..
+        1.0...
+    ";
+
+    const SOLUTION: &str = "
+        001011
+        101010
+        010101
+        110010
+        001101
+        110100
+    ";
+
+    fn is_valid(grid: &Binairo) -> bool {
+        let (width, height) = (grid.width(), grid.height());
+        let lines: Vec<Vec<bool>> = (0..height)
+            .map(|y| (0..width).map(|x| grid.get(x, y).unwrap()).collect())
+            .chain((0..width).map(|x| (0..height).map(|y| grid.get(x, y).unwrap()).collect()))
+            .collect();
+
+        let (rows, columns) = lines.split_at(height);
+        let distinct =
+            |lines: &[Vec<bool>]| (0..lines.len()).all(|i| (0..i).all(|j| lines[i] != lines[j]));
+
+        lines.iter().all(|line| {
+            2 * line.iter().filter(|&&value| value).count() == line.len()
+                && line
+                    .windows(3)
+                    .all(|run| run[0] != run[1] || run[1] != run[2])
+        }) && distinct(rows)
+            && distinct(columns)
+    }
+
+    #[test]
+    fn test_empty_grids() {
+        // Checking every way of filling a 4x4 grid
+        let mut valid = 0;
+        for bits in 0..1u32 << 16 {
+            let mut grid = Binairo::new(4, 4);
+            for i in 0..16 {
+                grid.set(i % 4, i / 4, bits & (1 << i) != 0);
+            }
+            valid += is_valid(&grid) as usize;
+        }
+
+        let grid = Binairo::new(4, 4);
+        assert_eq!(grid.count_solutions(usize::MAX), valid);
+        assert!(grid.solutions().all(|solved| is_valid(&solved)));
+
+        let grid = Binairo::new(6, 4);
+        for solved in grid.solutions().take(10) {
+            assert!(solved.is_complete());
+            assert!(is_valid(&solved));
+        }
+        assert_eq!(Binairo::new(0, 0).count_solutions(usize::MAX), 1);
+    }
+
+    #[test]
+    fn test_puzzle() {
+        let puzzle: Binairo = PUZZLE.parse().unwrap();
+        assert_eq!(puzzle.to_string().parse(), Ok(puzzle.clone()));
+        assert_eq!(puzzle.count_solutions(usize::MAX), 1);
+
+        let solved = puzzle.solve().unwrap();
+        assert!(is_valid(&solved));
+        assert_eq!(Ok(solved), SOLUTION.parse());
+
+        assert_eq!(
+            "01\n0".parse::<Binairo>(),
+            Err(ParseBinairoError::Ragged { row: 1 })
+        );
+        assert_eq!(
+            "0x\n10".parse::<Binairo>(),
+            Err(ParseBinairoError::InvalidCharacter { ch: 'x' })
+        );
+        assert_eq!(
+            "010\n101".parse::<Binairo>(),
+            Err(ParseBinairoError::OddDimensions {
+                width: 3,
+                height: 2
+            })
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use crate::DLXMatrix;
+
+use core::fmt;
+use core::str::FromStr;
+use std::collections::HashMap;
+
+// An undirected graph with named vertices, numbered in the order they were added
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Graph {
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The vertices 0 to n - 1, named by their numbers
+    pub fn with_vertices(n: usize) -> Self {
+        let mut graph = Self::new();
+        for i in 0..n {
+            graph.add_vertex(&i.to_string());
+        }
+        graph
+    }
+
+    // Returns the vertex's index, adding it first if there is no vertex by that name
+    pub fn add_vertex(&mut self, name: &str) -> usize {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
+        }
+
+        self.names.push(name.to_string());
+        self.indices.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    // Repeated edges are ignored
+    pub fn add_edge(&mut self, u: usize, v: usize) {
+        assert!(u < self.names.len() && v < self.names.len());
+        assert_ne!(u, v, "A vertex can't be adjacent to itself");
+
+        let edge = (u.min(v), u.max(v));
+        if !self.edges.contains(&edge) {
+            self.edges.push(edge);
+        }
+    }
+
+    pub fn vertices(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, vertex: usize) -> &str {
+        &self.names[vertex]
+    }
+
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    // Some coloring with at most k colors, as the color of each vertex, counting from 0
+    pub fn color(&self, k: usize) -> Option<Vec<usize>> {
+        self.colorings(k).next()
+    }
+
+    // Colorings which differ only in the names of the colors all count separately, so with no
+    // limit this is the chromatic polynomial evaluated at k
+    pub fn count_colorings(&self, k: usize, limit: usize) -> usize {
+        self.encode(k).count_solutions(limit)
+    }
+
+    pub fn colorings(&self, k: usize) -> impl Iterator<Item = Vec<usize>> {
+        let n = self.vertices();
+
+        self.encode(k).solutions().row_indices().map(move |rows| {
+            let mut colors = vec![0; n];
+            for row in rows {
+                colors[row / k] = row % k;
+            }
+            colors
+        })
+    }
+
+    pub fn chromatic_number(&self) -> usize {
+        let mut k = 0;
+        while self.count_colorings(k, 1) == 0 {
+            k += 1;
+        }
+        k
+    }
+
+    // One primary column per vertex, and a secondary column for each edge and color, so that the
+    // two ends of an edge can't both take the same color. Row k * v + c gives vertex v color c
+    fn encode(&self, k: usize) -> DLXMatrix<u32> {
+        let n = self.vertices();
+        let mut incident = vec![vec![]; n];
+        for (e, &(u, v)) in self.edges.iter().enumerate() {
+            incident[u].push(e);
+            incident[v].push(e);
+        }
+
+        let mut matrix = DLXMatrix::with_secondary_columns(n as u32, (self.edges.len() * k) as u32);
+        let mut row = vec![];
+
+        for (v, edges) in incident.iter().enumerate() {
+            for c in 0..k {
+                row.clear();
+                row.push(v as u32);
+                row.extend(edges.iter().map(|&e| (n + k * e + c) as u32));
+                matrix.push_row(&row);
+            }
+        }
+
+        matrix
+    }
+}
+
+// An adjacency list: each line names a vertex, then a colon, then its neighbours, separated by
+// whitespace. Edges need only be listed from one end, and a vertex with no neighbours can be
+// given on a line of its own. Blank lines and lines starting with '#' are skipped
+impl FromStr for Graph {
+    type Err = ParseGraphError;
+
+    fn from_str(string: &str) -> Result<Self, ParseGraphError> {
+        let mut graph = Graph::new();
+
+        for (i, line) in string.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (vertex, neighbours) = line.split_once(':').unwrap_or((line, ""));
+            let vertex = vertex.trim();
+            if vertex.is_empty() || vertex.contains(char::is_whitespace) {
+                return Err(ParseGraphError::Malformed { line: i + 1 });
+            }
+
+            let u = graph.add_vertex(vertex);
+            for neighbour in neighbours.split_whitespace() {
+                if neighbour == vertex {
+                    return Err(ParseGraphError::SelfLoop { line: i + 1 });
+                }
+
+                let v = graph.add_vertex(neighbour);
+                graph.add_edge(u, v);
+            }
+        }
+
+        Ok(graph)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseGraphError {
+    Malformed { line: usize },
+    SelfLoop { line: usize },
+}
+
+impl fmt::Display for ParseGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            ParseGraphError::Malformed { line } => write!(f, "Malformed line {}", line),
+            ParseGraphError::SelfLoop { line } => {
+                write!(f, "Vertex is its own neighbour on line {}", line)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::coloring::{Graph, ParseGraphError};
+
+    fn cycle(n: usize) -> Graph {
+        let mut graph = Graph::with_vertices(n);
+        for i in 0..n {
+            graph.add_edge(i, (i + 1) % n);
+        }
+        graph
+    }
+
+    fn validate(graph: &Graph, k: usize, colors: &[usize]) {
+        assert_eq!(colors.len(), graph.vertices());
+        assert!(colors.iter().all(|&color| color < k));
+        for &(u, v) in graph.edges() {
+            assert_ne!(colors[u], colors[v]);
+        }
+    }
+
+    #[test]
+    fn test_cycles() {
+        // The chromatic polynomial of a cycle is (k - 1)^n + (-1)^n (k - 1)
+        for n in 3..=7 {
+            let graph = cycle(n);
+            for k in 1..=4 {
+                let expected = (k - 1i64).pow(n as u32) + (-1i64).pow(n as u32) * (k - 1);
+                assert_eq!(
+                    graph.count_colorings(k as usize, usize::MAX),
+                    expected as usize
+                );
+            }
+            assert_eq!(graph.chromatic_number(), 2 + n % 2);
+        }
+
+        let graph = cycle(5);
+        for colors in graph.colorings(3) {
+            validate(&graph, 3, &colors);
+        }
+        assert!(graph.color(2).is_none());
+    }
+
+    #[test]
+    fn test_parse() {
+        let petersen: Graph = "
+            # The Petersen graph
+            a: b e f
+            b: c g
+            c: d h
+            d: e i
+            e: j
+            f: h i
+            g: i j
+            h: j
+            isolated
+        "
+        .parse()
+        .unwrap();
+
+        assert_eq!(petersen.vertices(), 11);
+        assert_eq!(petersen.edges().len(), 15);
+        assert_eq!(petersen.name(10), "isolated");
+        assert_eq!(petersen.chromatic_number(), 3);
+        validate(&petersen, 3, &petersen.color(3).unwrap());
+
+        let complete: Graph = "1: 2 3 4\n2: 3 4\n3: 4\n4: 1".parse().unwrap();
+        assert_eq!(complete.edges().len(), 6);
+        assert_eq!(complete.chromatic_number(), 4);
+        assert_eq!(complete.count_colorings(4, usize::MAX), 24);
+
+        assert_eq!(Graph::new().chromatic_number(), 0);
+        assert_eq!(
+            "a: a".parse::<Graph>(),
+            Err(ParseGraphError::SelfLoop { line: 1 })
+        );
+        assert_eq!(
+            "a b: c".parse::<Graph>(),
+            Err(ParseGraphError::Malformed { line: 1 })
+        );
+    }
+}
@@ -0,0 +1,343 @@
+use crate::DLXMatrix;
+
+use core::fmt;
+use core::str::FromStr;
+use std::collections::HashMap;
+
+// A generic exact cover problem with named items: choose options so that every primary item is in
+// exactly one of them, and every secondary item in at most one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    items: Vec<String>,
+    primary_items: usize,
+    options: Vec<Vec<usize>>,
+}
+
+impl Problem {
+    // A problem whose items are named by their indices, with the first primary_items of them
+    // primary. Each option lists the indices of its items, and must have at least one, with none
+    // repeated
+    pub fn new(items: usize, primary_items: usize, options: Vec<Vec<usize>>) -> Self {
+        assert!(primary_items <= items);
+        for option in &options {
+            assert!(!option.is_empty(), "Options must have at least one item");
+            for (i, &item) in option.iter().enumerate() {
+                assert!(item < items, "No item {}", item);
+                assert!(!option[..i].contains(&item), "Item {} repeated", item);
+            }
+        }
+
+        Self {
+            items: (0..items).map(|item| item.to_string()).collect(),
+            primary_items,
+            options,
+        }
+    }
+
+    pub fn items(&self) -> &[String] {
+        &self.items
+    }
+
+    pub fn primary_items(&self) -> usize {
+        self.primary_items
+    }
+
+    // Each option as the indices of its items, in the order they were given
+    pub fn options(&self) -> &[Vec<usize>] {
+        &self.options
+    }
+
+    pub fn option_names(&self, option: usize) -> impl Iterator<Item = &str> {
+        self.options[option]
+            .iter()
+            .map(|&item| self.items[item].as_str())
+    }
+
+    // Knuth's format from DLX1: a line listing the items, with any secondary items following a
+    // '|', then one line per option listing its items. Blank lines and lines starting with '|' are
+    // comments
+    pub fn parse_knuth(text: &str) -> Result<Self, ParseProblemError> {
+        let mut lines = numbered_lines(text).filter(|(_, line)| !line.starts_with('|'));
+
+        let (line, header) = lines.next().ok_or(ParseProblemError::NoItems)?;
+        let mut names = vec![];
+        let mut primary_items = None;
+
+        for name in header.split_whitespace() {
+            if name == "|" {
+                if primary_items.is_some() {
+                    return Err(ParseProblemError::Malformed { line });
+                }
+                primary_items = Some(names.len());
+            } else {
+                names.push(name);
+            }
+        }
+
+        let mut builder = Builder::new(line, &names, primary_items.unwrap_or(names.len()))?;
+        for (line, text) in lines {
+            builder.push(line, text.split_whitespace())?;
+        }
+
+        Ok(builder.finish())
+    }
+
+    // A header row naming the items, all of them primary, then one row of 0s and 1s per option
+    pub fn parse_csv(text: &str) -> Result<Self, ParseProblemError> {
+        let mut lines = numbered_lines(text);
+
+        let (line, header) = lines.next().ok_or(ParseProblemError::NoItems)?;
+        let names: Vec<&str> = header.split(',').map(|name| name.trim()).collect();
+
+        let mut builder = Builder::new(line, &names, names.len())?;
+        for (line, text) in lines {
+            let cells: Vec<&str> = text.split(',').map(|cell| cell.trim()).collect();
+            if cells.len() != names.len() {
+                return Err(ParseProblemError::Malformed { line });
+            }
+
+            let mut option = vec![];
+            for (&name, cell) in names.iter().zip(cells) {
+                match cell {
+                    "1" => option.push(name),
+                    "0" | "" => {}
+                    _ => return Err(ParseProblemError::Malformed { line }),
+                }
+            }
+            builder.push(line, option.into_iter())?;
+        }
+
+        Ok(builder.finish())
+    }
+
+    pub fn matrix(&self) -> DLXMatrix<u32> {
+        let primary = self.primary_items as u32;
+        let secondary = (self.items.len() - self.primary_items) as u32;
+        let mut matrix = DLXMatrix::with_secondary_columns(primary, secondary);
+
+        let mut columns = vec![];
+        for option in &self.options {
+            columns.clear();
+            columns.extend(option.iter().map(|&item| item as u32));
+            matrix.push_row(&columns);
+        }
+
+        matrix
+    }
+
+    // The indices of the chosen options, in increasing order
+    pub fn solve(&self) -> Option<Vec<usize>> {
+        self.solutions().next()
+    }
+
+    pub fn solutions(&self) -> impl Iterator<Item = Vec<usize>> {
+        self.matrix().solutions().row_indices().map(|mut options| {
+            options.sort_unstable();
+            options
+        })
+    }
+
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.matrix().count_solutions(limit)
+    }
+}
+
+impl FromStr for Problem {
+    type Err = ParseProblemError;
+
+    fn from_str(string: &str) -> Result<Self, ParseProblemError> {
+        Self::parse_knuth(string)
+    }
+}
+
+// Non-blank lines, trimmed, with their line numbers counting from 1
+fn numbered_lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+
+struct Builder<'a> {
+    indices: HashMap<&'a str, usize>,
+    problem: Problem,
+}
+
+impl<'a> Builder<'a> {
+    fn new(
+        line: usize,
+        names: &[&'a str],
+        primary_items: usize,
+    ) -> Result<Self, ParseProblemError> {
+        let mut indices = HashMap::with_capacity(names.len());
+
+        for (i, &name) in names.iter().enumerate() {
+            if name.is_empty() {
+                return Err(ParseProblemError::Malformed { line });
+            }
+            if indices.insert(name, i).is_some() {
+                return Err(ParseProblemError::DuplicateItem {
+                    line,
+                    name: name.to_string(),
+                });
+            }
+        }
+
+        if names.is_empty() {
+            return Err(ParseProblemError::NoItems);
+        }
+
+        Ok(Self {
+            indices,
+            problem: Problem {
+                items: names.iter().map(|name| name.to_string()).collect(),
+                primary_items,
+                options: vec![],
+            },
+        })
+    }
+
+    fn push<'b>(
+        &mut self,
+        line: usize,
+        names: impl Iterator<Item = &'b str>,
+    ) -> Result<(), ParseProblemError> {
+        let mut option: Vec<usize> = vec![];
+
+        for name in names {
+            let item = *self
+                .indices
+                .get(name)
+                .ok_or_else(|| ParseProblemError::UnknownItem {
+                    line,
+                    name: name.to_string(),
+                })?;
+
+            if option.contains(&item) {
+                return Err(ParseProblemError::DuplicateItem {
+                    line,
+                    name: name.to_string(),
+                });
+            }
+            option.push(item);
+        }
+
+        // An option with no items could be chosen any number of times
+        if option.is_empty() {
+            return Err(ParseProblemError::Malformed { line });
+        }
+
+        self.problem.options.push(option);
+        Ok(())
+    }
+
+    fn finish(self) -> Problem {
+        self.problem
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseProblemError {
+    NoItems,
+    Malformed { line: usize },
+    DuplicateItem { line: usize, name: String },
+    UnknownItem { line: usize, name: String },
+}
+
+impl fmt::Display for ParseProblemError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ParseProblemError::NoItems => write!(f, "No items were given"),
+            ParseProblemError::Malformed { line } => write!(f, "Malformed line {}", line),
+            ParseProblemError::DuplicateItem { line, name } => {
+                write!(f, "Item {:?} repeated on line {}", name, line)
+            }
+            ParseProblemError::UnknownItem { line, name } => {
+                write!(f, "Unknown item {:?} on line {}", name, line)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cover::{ParseProblemError, Problem};
+
+    // The example from Knuth's Dancing Links paper, whose only solution is options 0, 3, and 4
+    const KNUTH: &str = "| Knuth's example
+A B C D E F G
+C E F
+A D G
+B C F
+A D
+B G
+D E G
+";
+
+    #[test]
+    fn test_parse_knuth() {
+        let problem: Problem = KNUTH.parse().unwrap();
+        assert_eq!(problem.items().len(), 7);
+        assert_eq!(problem.primary_items(), 7);
+        assert_eq!(problem.options().len(), 6);
+        assert_eq!(problem.options()[1], vec![0, 3, 6]);
+        assert_eq!(problem.solve(), Some(vec![0, 3, 4]));
+        assert_eq!(problem.count_solutions(10), 1);
+
+        let names: Vec<&str> = problem.option_names(2).collect();
+        assert_eq!(names, vec!["B", "C", "F"]);
+    }
+
+    #[test]
+    fn test_secondary_items() {
+        // x may be left uncovered, but not covered twice
+        let problem = Problem::parse_knuth("a b | x\na x\nb x\na\nb\n").unwrap();
+        assert_eq!(problem.primary_items(), 2);
+
+        let mut solutions: Vec<_> = problem.solutions().collect();
+        solutions.sort();
+        assert_eq!(solutions, vec![vec![0, 3], vec![1, 2], vec![2, 3]]);
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let problem = Problem::parse_csv("a,b,c\n1,1,0\n0,0,1\n0,1,1\n1,0,0\n").unwrap();
+        assert_eq!(problem.options()[2], vec![1, 2]);
+        assert_eq!(problem.count_solutions(10), 2);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Problem::parse_knuth("| nothing\n"),
+            Err(ParseProblemError::NoItems)
+        );
+        assert_eq!(
+            Problem::parse_knuth("a b\na c\n"),
+            Err(ParseProblemError::UnknownItem {
+                line: 2,
+                name: String::from("c")
+            })
+        );
+        assert_eq!(
+            Problem::parse_knuth("a b a\n"),
+            Err(ParseProblemError::DuplicateItem {
+                line: 1,
+                name: String::from("a")
+            })
+        );
+        assert_eq!(
+            Problem::parse_knuth("a | b | c\n"),
+            Err(ParseProblemError::Malformed { line: 1 })
+        );
+        assert_eq!(
+            Problem::parse_csv("a,b\n1,0\n1\n"),
+            Err(ParseProblemError::Malformed { line: 3 })
+        );
+        assert_eq!(
+            Problem::parse_csv("a,b\n0,0\n"),
+            Err(ParseProblemError::Malformed { line: 2 })
+        );
+    }
+}
@@ -0,0 +1,365 @@
+use crate::DLXMatrix;
+
+use core::fmt;
+use core::str::FromStr;
+use std::collections::HashMap;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+enum Cell {
+    Block,
+    Empty,
+    Letter(char),
+}
+
+// A run of two or more cells across or down, which must be filled with a word
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Slot {
+    pub x: usize,
+    pub y: usize,
+    pub down: bool,
+    pub length: usize,
+}
+
+// A crossword grid to be filled from a dictionary, or a word rectangle when it has no blocks. Each
+// slot is a primary item and each cell a secondary item, colored by the letter a word puts there,
+// so that crossing words have to agree
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Crossword {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    distinct: bool,
+}
+
+impl Crossword {
+    pub fn rectangle(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::Empty; width * height],
+            distinct: true,
+        }
+    }
+
+    // Whether each word may be used only once, as it may by default. Symmetric word squares such
+    // as BAT/ARE/TEN read the same across and down, so they need this turned off
+    pub fn distinct(mut self, distinct: bool) -> Self {
+        self.distinct = distinct;
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn slots(&self) -> Vec<Slot> {
+        let mut slots = vec![];
+
+        for down in [false, true] {
+            let (outer, inner) = if down {
+                (self.width, self.height)
+            } else {
+                (self.height, self.width)
+            };
+
+            for a in 0..outer {
+                let mut start = 0;
+
+                for b in 0..=inner {
+                    let (x, y) = if down { (a, b) } else { (b, a) };
+                    let open = b < inner && self.cells[self.width * y + x] != Cell::Block;
+
+                    if !open {
+                        if b - start >= 2 {
+                            let (x, y) = if down { (a, start) } else { (start, a) };
+                            slots.push(Slot {
+                                x,
+                                y,
+                                down,
+                                length: b - start,
+                            });
+                        }
+                        start = b + 1;
+                    }
+                }
+            }
+        }
+
+        slots
+    }
+
+    pub fn fill(&self, dictionary: &[&str]) -> Option<Fill> {
+        self.fills(dictionary).next()
+    }
+
+    pub fn count(&self, dictionary: &[&str], limit: usize) -> usize {
+        self.encode(dictionary).0.count_solutions(limit)
+    }
+
+    pub fn fills(&self, dictionary: &[&str]) -> impl Iterator<Item = Fill> + '_ {
+        let (matrix, placements) = self.encode(dictionary);
+
+        matrix.solutions().row_indices().map(move |rows| {
+            let mut cells: Vec<char> = self
+                .cells
+                .iter()
+                .map(|&cell| match cell {
+                    Cell::Block => '#',
+                    Cell::Empty => '.',
+                    Cell::Letter(letter) => letter,
+                })
+                .collect();
+
+            for row in rows {
+                let (ref slot, ref word) = placements[row];
+                for (i, letter) in word.chars().enumerate() {
+                    cells[self.index(slot, i)] = letter;
+                }
+            }
+
+            Fill {
+                width: self.width,
+                cells,
+            }
+        })
+    }
+
+    fn index(&self, slot: &Slot, i: usize) -> usize {
+        if slot.down {
+            self.width * (slot.y + i) + slot.x
+        } else {
+            self.width * slot.y + slot.x + i
+        }
+    }
+
+    // One primary column per slot, then a colored secondary column per cell, then (for distinct
+    // words) an uncolored secondary column per word. Words are matched ignoring case, and those
+    // with anything but the letters A to Z are skipped
+    fn encode(&self, dictionary: &[&str]) -> (DLXMatrix<u32>, Vec<(Slot, String)>) {
+        let mut words: Vec<String> = dictionary
+            .iter()
+            .filter(|word| word.chars().all(|ch| ch.is_ascii_alphabetic()))
+            .map(|word| word.to_ascii_uppercase())
+            .collect();
+        words.sort_unstable();
+        words.dedup();
+
+        let mut by_length: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (i, word) in words.iter().enumerate() {
+            by_length.entry(word.chars().count()).or_default().push(i);
+        }
+
+        let slots = self.slots();
+        let cell_base = slots.len();
+        let word_base = cell_base + self.cells.len();
+        let word_columns = if self.distinct { words.len() } else { 0 };
+
+        let mut matrix = DLXMatrix::with_secondary_columns(
+            slots.len() as u32,
+            (self.cells.len() + word_columns) as u32,
+        );
+        let mut placements = vec![];
+        let mut row = vec![];
+
+        for (s, slot) in slots.iter().enumerate() {
+            for &w in by_length.get(&slot.length).into_iter().flatten() {
+                let word = &words[w];
+
+                row.clear();
+                row.push((s as u32, None));
+
+                let fits = word.chars().enumerate().all(|(i, letter)| {
+                    let cell = self.index(slot, i);
+                    row.push(((cell_base + cell) as u32, Some(letter as u32)));
+                    match self.cells[cell] {
+                        Cell::Letter(given) => given == letter,
+                        _ => true,
+                    }
+                });
+
+                if fits {
+                    if self.distinct {
+                        row.push(((word_base + w) as u32, None));
+                    }
+                    matrix.push_colored_row(&row);
+                    placements.push((slot.clone(), word.clone()));
+                }
+            }
+        }
+
+        (matrix, placements)
+    }
+}
+
+// Rows of '.' for empty cells, '#' for blocks, and letters for cells given in advance
+impl FromStr for Crossword {
+    type Err = ParseCrosswordError;
+
+    fn from_str(string: &str) -> Result<Self, ParseCrosswordError> {
+        let lines: Vec<&str> = string
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        let width = lines.first().map_or(0, |line| line.chars().count());
+        let mut cells = vec![];
+
+        for (y, line) in lines.iter().enumerate() {
+            if line.chars().count() != width {
+                return Err(ParseCrosswordError::Ragged { row: y });
+            }
+
+            for ch in line.chars() {
+                cells.push(match ch {
+                    '#' => Cell::Block,
+                    '.' => Cell::Empty,
+                    ch if ch.is_ascii_alphabetic() => Cell::Letter(ch.to_ascii_uppercase()),
+                    ch => return Err(ParseCrosswordError::InvalidCharacter { ch }),
+                });
+            }
+        }
+
+        Ok(Self {
+            width,
+            height: lines.len(),
+            cells,
+            distinct: true,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseCrosswordError {
+    Ragged { row: usize },
+    InvalidCharacter { ch: char },
+}
+
+impl fmt::Display for ParseCrosswordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            ParseCrosswordError::Ragged { row } => {
+                write!(f, "Row {} is not as wide as the first row", row + 1)
+            }
+            ParseCrosswordError::InvalidCharacter { ch } => {
+                write!(
+                    f,
+                    "Invalid character {:?} (expected a letter, '.', or '#')",
+                    ch
+                )
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Fill {
+    width: usize,
+    cells: Vec<char>,
+}
+
+impl Fill {
+    // The letter in the cell, or None for a block or a cell outside every slot
+    pub fn get(&self, x: usize, y: usize) -> Option<char> {
+        assert!(x < self.width);
+        Some(self.cells[self.width * y + x]).filter(|ch| ch.is_alphabetic())
+    }
+}
+
+impl fmt::Display for Fill {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (y, row) in self.cells.chunks(self.width.max(1)).enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", row.iter().collect::<String>())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::crossword::{Crossword, Fill, ParseCrosswordError};
+
+    const DICTIONARY: [&str; 6] = ["bit", "ace", "den", "bad", "ice", "ten"];
+
+    fn words(crossword: &Crossword, fill: &Fill) -> Vec<String> {
+        crossword
+            .slots()
+            .iter()
+            .map(|slot| {
+                (0..slot.length)
+                    .map(|i| match slot.down {
+                        true => fill.get(slot.x, slot.y + i),
+                        false => fill.get(slot.x + i, slot.y),
+                    })
+                    .map(Option::unwrap)
+                    .collect::<String>()
+                    .to_lowercase()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_word_rectangle() {
+        let crossword = Crossword::rectangle(3, 3);
+        assert_eq!(crossword.slots().len(), 6);
+
+        // BIT/ACE/DEN and its transpose
+        assert_eq!(crossword.count(&DICTIONARY, usize::MAX), 2);
+
+        let fill = crossword.fill(&DICTIONARY).unwrap();
+        let mut used = words(&crossword, &fill);
+        used.sort_unstable();
+        let mut expected = DICTIONARY.to_vec();
+        expected.sort_unstable();
+        assert_eq!(used, expected);
+
+        let crossword: Crossword = ".I.\n...\n...".parse().unwrap();
+        let fill = crossword.fill(&DICTIONARY).unwrap();
+        assert_eq!(fill.to_string(), "BIT\nACE\nDEN");
+        assert_eq!(crossword.count(&DICTIONARY, usize::MAX), 1);
+
+        let crossword: Crossword = "..X\n...\n...".parse().unwrap();
+        assert!(crossword.fill(&DICTIONARY).is_none());
+    }
+
+    #[test]
+    fn test_distinct() {
+        let square = ["bat", "are", "ten"];
+        let crossword = Crossword::rectangle(3, 3);
+        assert!(crossword.fill(&square).is_none());
+
+        let fill = crossword.distinct(false).fill(&square).unwrap();
+        assert_eq!(fill.to_string(), "BAT\nARE\nTEN");
+    }
+
+    #[test]
+    fn test_blocks() {
+        let crossword: Crossword = "..#\n...\n#..".parse().unwrap();
+        assert_eq!(crossword.slots().len(), 6);
+
+        let dictionary = ["te", "ate", "an", "ta", "eta", "en", "on", "tea"];
+        let fill = crossword.fill(&dictionary).unwrap();
+        for word in words(&crossword, &fill) {
+            assert!(dictionary.contains(&word.as_str()));
+        }
+        assert_eq!(fill.get(2, 0), None);
+
+        assert_eq!(
+            "..\n...".parse::<Crossword>(),
+            Err(ParseCrosswordError::Ragged { row: 1 })
+        );
+        assert_eq!(
+            "..\n.?".parse::<Crossword>(),
+            Err(ParseCrosswordError::InvalidCharacter { ch: '?' })
+        );
+    }
+}
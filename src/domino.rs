@@ -0,0 +1,227 @@
+use crate::polyomino::Board;
+use crate::DLXMatrix;
+
+use core::fmt;
+
+// A domino covering the cell given and the one to its right, or the one below it
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Domino {
+    pub x: usize,
+    pub y: usize,
+    pub vertical: bool,
+}
+
+impl Domino {
+    pub fn cells(&self) -> [(usize, usize); 2] {
+        if self.vertical {
+            [(self.x, self.y), (self.x, self.y + 1)]
+        } else {
+            [(self.x, self.y), (self.x + 1, self.y)]
+        }
+    }
+}
+
+// Covering every cell of a board with dominoes, or equivalently, finding a perfect matching of
+// the grid graph formed by its cells
+#[derive(Clone, Debug)]
+pub struct DominoTilings {
+    board: Board,
+}
+
+impl DominoTilings {
+    pub fn new(board: Board) -> Self {
+        Self { board }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn solve(&self) -> Option<DominoTiling> {
+        self.iter_solutions().next()
+    }
+
+    pub fn count(&self, limit: usize) -> usize {
+        self.encode().0.count_solutions(limit)
+    }
+
+    pub fn iter_solutions(&self) -> impl Iterator<Item = DominoTiling> + '_ {
+        let (matrix, dominoes) = self.encode();
+
+        matrix.solutions().row_indices().map(move |mut rows| {
+            rows.sort_unstable();
+            DominoTiling {
+                width: self.board.width(),
+                height: self.board.height(),
+                dominoes: rows.into_iter().map(|row| dominoes[row]).collect(),
+            }
+        })
+    }
+
+    // One column per cell of the board, with a row for each domino covering its two cells
+    fn encode(&self) -> (DLXMatrix<u32>, Vec<Domino>) {
+        let (width, height) = (self.board.width(), self.board.height());
+
+        let mut columns = vec![None; width * height];
+        let mut next = 0;
+        for y in 0..height {
+            for x in 0..width {
+                if self.board.contains(x, y) {
+                    columns[width * y + x] = Some(next);
+                    next += 1;
+                }
+            }
+        }
+
+        let mut matrix = DLXMatrix::new(next);
+        let mut dominoes = vec![];
+
+        for y in 0..height {
+            for x in 0..width {
+                for vertical in [false, true] {
+                    let domino = Domino { x, y, vertical };
+                    let [first, second] = domino.cells();
+
+                    if self.board.contains(first.0, first.1)
+                        && self.board.contains(second.0, second.1)
+                    {
+                        matrix.push_row(&[
+                            columns[width * first.1 + first.0].unwrap(),
+                            columns[width * second.1 + second.0].unwrap(),
+                        ]);
+                        dominoes.push(domino);
+                    }
+                }
+            }
+        }
+
+        (matrix, dominoes)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DominoTiling {
+    width: usize,
+    height: usize,
+    dominoes: Vec<Domino>,
+}
+
+impl DominoTiling {
+    pub fn dominoes(&self) -> &[Domino] {
+        &self.dominoes
+    }
+}
+
+// Horizontal dominoes are drawn as <>, vertical ones as ^ over v, and holes as '.'
+impl fmt::Display for DominoTiling {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let mut cells = vec!['.'; self.width * self.height];
+
+        for domino in &self.dominoes {
+            let [first, second] = domino.cells();
+            let (a, b) = if domino.vertical {
+                ('^', 'v')
+            } else {
+                ('<', '>')
+            };
+            cells[self.width * first.1 + first.0] = a;
+            cells[self.width * second.1 + second.0] = b;
+        }
+
+        for (y, row) in cells.chunks(self.width.max(1)).enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", row.iter().collect::<String>())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domino::DominoTilings;
+    use crate::polyomino::Board;
+
+    // Kasteleyn's formula for the number of tilings of a rectangle
+    fn kasteleyn(width: usize, height: usize) -> usize {
+        use core::f64::consts::PI;
+
+        let mut product = 1.0;
+        for j in 1..=width.div_ceil(2) {
+            for k in 1..=height.div_ceil(2) {
+                let a = (PI * j as f64 / (width + 1) as f64).cos();
+                let b = (PI * k as f64 / (height + 1) as f64).cos();
+                product *= 4.0 * (a * a + b * b);
+            }
+        }
+
+        if width % 2 == 1 && height % 2 == 1 {
+            0
+        } else {
+            product.round() as usize
+        }
+    }
+
+    #[test]
+    fn test_rectangles() {
+        for width in 1..=6 {
+            for height in 1..=6 {
+                let tilings = DominoTilings::new(Board::rectangle(width, height));
+                assert_eq!(tilings.count(usize::MAX), kasteleyn(width, height));
+            }
+        }
+
+        // Fibonacci numbers
+        let tilings = DominoTilings::new(Board::rectangle(12, 2));
+        assert_eq!(tilings.count(usize::MAX), 233);
+    }
+
+    #[test]
+    fn test_regions() {
+        // The Aztec diamond of order n has 2^(n(n + 1)/2) tilings
+        for n in 1..=5 {
+            let board = Board::aztec_diamond(n);
+            assert_eq!(board.area(), 2 * n * (n + 1));
+            assert_eq!(
+                DominoTilings::new(board).count(usize::MAX),
+                1 << (n * (n + 1) / 2)
+            );
+        }
+
+        // Splitting an L two cells thick at its corner, one way or the other, leaves two strips
+        // whose tilings are counted by Fibonacci numbers. Tilings which split both ways, leaving
+        // the 2x2 corner on its own, are counted twice
+        let mut fibonacci = vec![0, 1];
+        for i in 2..16 {
+            fibonacci.push(fibonacci[i - 1] + fibonacci[i - 2]);
+        }
+
+        for a in 0..6 {
+            for b in 0..6 {
+                let f = |i| fibonacci[i];
+                let expected = f(a + 3) * f(b + 1) + f(a + 1) * f(b + 3) - 2 * f(a + 1) * f(b + 1);
+                let tilings = DominoTilings::new(Board::l_shape(a + 2, b + 2, 2));
+                assert_eq!(tilings.count(usize::MAX), expected);
+            }
+        }
+
+        let board = Board::l_shape(4, 3, 1);
+        assert_eq!(board.area(), 6);
+        assert!(board.contains(0, 0) && board.contains(3, 2) && !board.contains(1, 1));
+    }
+
+    #[test]
+    fn test_mutilated_chessboard() {
+        let mut board = Board::rectangle(8, 8);
+        board.remove(0, 0);
+        board.remove(7, 7);
+        assert!(DominoTilings::new(board).solve().is_none());
+
+        let board = Board::parse("##..\n####\n..##");
+        let tiling = DominoTilings::new(board).solve().unwrap();
+        assert_eq!(tiling.dominoes().len(), 4);
+        assert_eq!(tiling.to_string().matches('.').count(), 4);
+    }
+}
@@ -0,0 +1,238 @@
+// A C interface to the solver, enabled by the ffi feature. The crate only builds an rlib by
+// default, so the shared library has to be asked for:
+//
+//     cargo rustc --release --lib --no-default-features --features ffi --crate-type cdylib
+//
+// The declarations are in include/lynx.h, which cbindgen generates from this file. Functions taking
+// pointers require them to be valid for the lengths given, and matrices to have come from
+// lynx_matrix_new and not yet been freed; C callers have no way of being told otherwise
+#![allow(clippy::missing_safety_doc)]
+
+use crate::sudoku::Sudoku;
+use crate::DLXMatrix;
+
+use core::ffi::{c_char, c_int};
+use core::ptr;
+use core::slice;
+use std::ffi::CStr;
+
+pub const LYNX_OK: c_int = 0;
+pub const LYNX_NO_SOLUTION: c_int = 1;
+pub const LYNX_INVALID_ARGUMENT: c_int = -1;
+
+// An opaque handle for C, which only ever sees pointers to it. Solving consumes a DLXMatrix, so
+// the rows are kept here and a fresh matrix is built each time, letting C solve more than once
+pub struct LynxMatrix {
+    primary_columns: u32,
+    secondary_columns: u32,
+    rows: Vec<Vec<u32>>,
+}
+
+impl LynxMatrix {
+    fn matrix(&self) -> DLXMatrix<u32> {
+        let mut matrix =
+            DLXMatrix::with_secondary_columns(self.primary_columns, self.secondary_columns);
+        for row in &self.rows {
+            matrix.push_row(row);
+        }
+        matrix
+    }
+}
+
+// Returns null if there are more than 2^32 - 1 columns in all
+#[no_mangle]
+pub extern "C" fn lynx_matrix_new(primary_columns: u32, secondary_columns: u32) -> *mut LynxMatrix {
+    if primary_columns.checked_add(secondary_columns).is_none() {
+        return ptr::null_mut();
+    }
+
+    Box::into_raw(Box::new(LynxMatrix {
+        primary_columns,
+        secondary_columns,
+        rows: vec![],
+    }))
+}
+
+// Stores the index of the new row in *row, if row isn't null. Fails without changing the matrix
+// if the row is empty or names a column that doesn't exist
+#[no_mangle]
+pub unsafe extern "C" fn lynx_matrix_push_row(
+    matrix: *mut LynxMatrix,
+    columns: *const u32,
+    length: usize,
+    row: *mut usize,
+) -> c_int {
+    let Some(matrix) = matrix.as_mut() else {
+        return LYNX_INVALID_ARGUMENT;
+    };
+    if columns.is_null() || length == 0 {
+        return LYNX_INVALID_ARGUMENT;
+    }
+
+    let columns = slice::from_raw_parts(columns, length);
+    let count = matrix.primary_columns + matrix.secondary_columns;
+    if columns.iter().any(|&column| column >= count) {
+        return LYNX_INVALID_ARGUMENT;
+    }
+
+    if !row.is_null() {
+        *row = matrix.rows.len();
+    }
+    matrix.rows.push(columns.to_vec());
+
+    LYNX_OK
+}
+
+// Finds some solution and stores the number of rows in it in *length. As many of their indices as
+// fit are written to rows in increasing order, so passing a capacity of 0 just measures it
+#[no_mangle]
+pub unsafe extern "C" fn lynx_matrix_solve(
+    matrix: *const LynxMatrix,
+    rows: *mut usize,
+    capacity: usize,
+    length: *mut usize,
+) -> c_int {
+    let Some(matrix) = matrix.as_ref() else {
+        return LYNX_INVALID_ARGUMENT;
+    };
+    if (rows.is_null() && capacity > 0) || length.is_null() {
+        return LYNX_INVALID_ARGUMENT;
+    }
+
+    let Some(mut solution) = matrix.matrix().solutions().row_indices().next() else {
+        return LYNX_NO_SOLUTION;
+    };
+    solution.sort_unstable();
+
+    *length = solution.len();
+    for (i, &index) in solution.iter().take(capacity).enumerate() {
+        *rows.add(i) = index;
+    }
+
+    LYNX_OK
+}
+
+// Freeing a null pointer does nothing
+#[no_mangle]
+pub unsafe extern "C" fn lynx_matrix_free(matrix: *mut LynxMatrix) {
+    if !matrix.is_null() {
+        drop(Box::from_raw(matrix));
+    }
+}
+
+// Reads a puzzle in any format Sudoku::from_str accepts from a NUL-terminated string, and writes
+// the solution to output as an 81-character line followed by a NUL, so output must have room for
+// 82 bytes
+#[no_mangle]
+pub unsafe extern "C" fn lynx_sudoku_solve(puzzle: *const c_char, output: *mut c_char) -> c_int {
+    if puzzle.is_null() || output.is_null() {
+        return LYNX_INVALID_ARGUMENT;
+    }
+
+    let Ok(puzzle) = CStr::from_ptr(puzzle).to_str() else {
+        return LYNX_INVALID_ARGUMENT;
+    };
+    let Ok(sudoku) = puzzle.parse::<Sudoku>() else {
+        return LYNX_INVALID_ARGUMENT;
+    };
+    let Some(solved) = sudoku.solve() else {
+        return LYNX_NO_SOLUTION;
+    };
+
+    let line = solved.to_line();
+    ptr::copy_nonoverlapping(line.as_ptr() as *const c_char, output, line.len());
+    *output.add(line.len()) = 0;
+
+    LYNX_OK
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ffi::*;
+
+    use core::ptr;
+    use std::ffi::CStr;
+
+    #[test]
+    fn test_matrix() {
+        unsafe {
+            let matrix = lynx_matrix_new(3, 1);
+
+            let mut row = 0;
+            for (i, columns) in [vec![0, 3], vec![1, 2], vec![0, 1], vec![2, 3]]
+                .iter()
+                .enumerate()
+            {
+                let status =
+                    lynx_matrix_push_row(matrix, columns.as_ptr(), columns.len(), &mut row);
+                assert_eq!(status, LYNX_OK);
+                assert_eq!(row, i);
+            }
+
+            let invalid = [4];
+            assert_eq!(
+                lynx_matrix_push_row(matrix, invalid.as_ptr(), 1, ptr::null_mut()),
+                LYNX_INVALID_ARGUMENT
+            );
+            assert_eq!(
+                lynx_matrix_push_row(matrix, invalid.as_ptr(), 0, ptr::null_mut()),
+                LYNX_INVALID_ARGUMENT
+            );
+
+            let mut length = 0;
+            assert_eq!(
+                lynx_matrix_solve(matrix, ptr::null_mut(), 0, &mut length),
+                LYNX_OK
+            );
+            assert_eq!(length, 2);
+
+            let mut rows = [usize::MAX; 3];
+            assert_eq!(
+                lynx_matrix_solve(matrix, rows.as_mut_ptr(), rows.len(), &mut length),
+                LYNX_OK
+            );
+            assert!(rows[..2] == [0, 1] || rows[..2] == [2, 3]);
+            assert_eq!(rows[2], usize::MAX);
+
+            lynx_matrix_free(matrix);
+            lynx_matrix_free(ptr::null_mut());
+
+            let matrix = lynx_matrix_new(1, 0);
+            assert_eq!(
+                lynx_matrix_solve(matrix, ptr::null_mut(), 0, &mut length),
+                LYNX_NO_SOLUTION
+            );
+            lynx_matrix_free(matrix);
+
+            assert!(lynx_matrix_new(u32::MAX, 1).is_null());
+        }
+    }
+
+    #[test]
+    fn test_sudoku() {
+        let puzzle =
+            c"53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let mut output = [1 as c_char; 82];
+
+        unsafe {
+            assert_eq!(
+                lynx_sudoku_solve(puzzle.as_ptr(), output.as_mut_ptr()),
+                LYNX_OK
+            );
+            let solved = CStr::from_ptr(output.as_ptr()).to_str().unwrap();
+            assert_eq!(
+                solved,
+                "534678912672195348198342567859761423426853791713924856961537284287419635345286179"
+            );
+
+            assert_eq!(
+                lynx_sudoku_solve(c"55".as_ptr(), output.as_mut_ptr()),
+                LYNX_INVALID_ARGUMENT
+            );
+            assert_eq!(
+                lynx_sudoku_solve(ptr::null(), output.as_mut_ptr()),
+                LYNX_INVALID_ARGUMENT
+            );
+        }
+    }
+}
@@ -0,0 +1,753 @@
+use crate::{DLXMatrix, Size, PURIFIED};
+
+use core::fmt;
+use core::fmt::Write;
+use core::str;
+
+// A minimal JSON reader and writer, enough for the puzzle interchange formats without pulling in
+// a serialization framework. f-puzzles import, the exact cover exchange format, and the solution
+// writers are all part of the default library, and the wasm, python, and ffi builds use them too,
+// whereas serde is only an optional dependency for deriving Serialize and Deserialize on Sudoku.
+// Building on serde_json would make every one of those depend on it, for the sake of a few small
+// documents whose shapes are checked by hand anyway
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries
+                .iter()
+                .find(|(other, _)| other == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Value::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Value::Number(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn entries(&self) -> &[(String, Value)] {
+        match self {
+            Value::Object(entries) => entries,
+            _ => &[],
+        }
+    }
+
+    // Absent, null, false, and empty values all mean a constraint is unused
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Value::Null | Value::Bool(false) => true,
+            Value::String(value) => value.is_empty(),
+            Value::Array(values) => values.is_empty(),
+            Value::Object(entries) => entries.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Value::Null => write!(f, "null")?,
+            Value::Bool(value) => write!(f, "{}", value)?,
+            // JSON has no infinities or NaN, so they're written as null, as JavaScript does
+            Value::Number(value) if !value.is_finite() => write!(f, "null")?,
+            Value::Number(value) => write!(f, "{}", value)?,
+            Value::String(value) => write_string(f, value)?,
+            Value::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")?;
+            }
+            Value::Object(entries) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn write_string<W: Write>(w: &mut W, string: &str) -> Result<(), fmt::Error> {
+    w.write_char('"')?;
+
+    for ch in string.chars() {
+        match ch {
+            '"' => w.write_str("\\\"")?,
+            '\\' => w.write_str("\\\\")?,
+            '\n' => w.write_str("\\n")?,
+            '\r' => w.write_str("\\r")?,
+            '\t' => w.write_str("\\t")?,
+            _ if (ch as u32) < 0x20 => write!(w, "\\u{:04x}", ch as u32)?,
+            _ => w.write_char(ch)?,
+        }
+    }
+
+    w.write_char('"')
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct JsonError {
+    pub offset: usize,
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "Invalid JSON at byte {}", self.offset)
+    }
+}
+
+impl str::FromStr for Value {
+    type Err = JsonError;
+
+    fn from_str(string: &str) -> Result<Self, JsonError> {
+        let mut parser = Parser {
+            bytes: string.as_bytes(),
+            offset: 0,
+            depth: 0,
+        };
+
+        let value = parser.value()?;
+        parser.whitespace();
+
+        if parser.offset < parser.bytes.len() {
+            return Err(parser.error());
+        }
+
+        Ok(value)
+    }
+}
+
+// Arrays and objects are parsed recursively, so nesting is limited to keep hostile input from
+// overflowing the stack
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self) -> JsonError {
+        JsonError {
+            offset: self.offset,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.offset).copied()
+    }
+
+    fn whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.offset += 1;
+        }
+    }
+
+    fn expect(&mut self, literal: &[u8]) -> Result<(), JsonError> {
+        if self.bytes[self.offset..].starts_with(literal) {
+            self.offset += literal.len();
+            Ok(())
+        } else {
+            Err(self.error())
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, JsonError> {
+        self.whitespace();
+
+        match self.peek().ok_or_else(|| self.error())? {
+            b'n' => self.expect(b"null").map(|()| Value::Null),
+            b't' => self.expect(b"true").map(|()| Value::Bool(true)),
+            b'f' => self.expect(b"false").map(|()| Value::Bool(false)),
+            b'"' => self.string().map(Value::String),
+            b'[' | b'{' => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error());
+                }
+
+                self.depth += 1;
+                let value = match self.peek() {
+                    Some(b'[') => self.array(),
+                    _ => self.object(),
+                };
+                self.depth -= 1;
+                value
+            }
+            b'-' | b'0'..=b'9' => self.number(),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, JsonError> {
+        self.offset += 1;
+        let mut values = vec![];
+
+        self.whitespace();
+        if self.peek() == Some(b']') {
+            self.offset += 1;
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+            self.whitespace();
+
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b']') => {
+                    self.offset += 1;
+                    return Ok(Value::Array(values));
+                }
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, JsonError> {
+        self.offset += 1;
+        let mut entries = vec![];
+
+        self.whitespace();
+        if self.peek() == Some(b'}') {
+            self.offset += 1;
+            return Ok(Value::Object(entries));
+        }
+
+        loop {
+            self.whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error());
+            }
+            let key = self.string()?;
+
+            self.whitespace();
+            self.expect(b":")?;
+            entries.push((key, self.value()?));
+            self.whitespace();
+
+            match self.peek() {
+                Some(b',') => self.offset += 1,
+                Some(b'}') => {
+                    self.offset += 1;
+                    return Ok(Value::Object(entries));
+                }
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Value, JsonError> {
+        let start = self.offset;
+
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+            self.offset += 1;
+        }
+
+        str::from_utf8(&self.bytes[start..self.offset])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .filter(|number: &f64| number.is_finite())
+            .map(Value::Number)
+            .ok_or(JsonError { offset: start })
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect(b"\"")?;
+        let mut bytes = vec![];
+
+        loop {
+            let byte = self.peek().ok_or_else(|| self.error())?;
+            self.offset += 1;
+
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escape = self.peek().ok_or_else(|| self.error())?;
+                    self.offset += 1;
+
+                    let ch = match escape {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return Err(self.error()),
+                    };
+
+                    let mut buffer = [0; 4];
+                    bytes.extend_from_slice(ch.encode_utf8(&mut buffer).as_bytes());
+                }
+                _ => bytes.push(byte),
+            }
+        }
+
+        String::from_utf8(bytes).map_err(|_| self.error())
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, JsonError> {
+        let first = self.hex4()?;
+
+        let code = if (0xd800..0xdc00).contains(&first) {
+            self.expect(b"\\u")?;
+            let second = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&second) {
+                return Err(self.error());
+            }
+            0x10000 + ((first - 0xd800) << 10) + (second - 0xdc00)
+        } else {
+            first
+        };
+
+        char::from_u32(code).ok_or_else(|| self.error())
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let digits = self
+            .bytes
+            .get(self.offset..self.offset + 4)
+            .and_then(|digits| str::from_utf8(digits).ok())
+            .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+            .ok_or_else(|| self.error())?;
+
+        self.offset += 4;
+        Ok(digits)
+    }
+}
+
+// The exchange format for exact cover problems, shared by lynx-dlx and the bindings for other
+// languages:
+//
+//     {"columns": 3, "secondary": 1, "rows": [{"label": "a", "cols": [0, 3]}, {"cols": [1, 2]}]}
+//
+// columns is the number of primary columns, and secondary the number of secondary columns
+// numbered after them, which may be left out if there are none. Labels are optional strings
+// carried along for the caller's benefit. Rows with colored columns also have "colors", giving a
+// color or null for each of their columns in turn
+impl<S: Size> DLXMatrix<S> {
+    pub fn to_json(&self) -> String {
+        self.to_json_with_labels(&[])
+    }
+
+    // Labels are given to rows in order, and any rows past the end of labels are left without
+    pub fn to_json_with_labels(&self, labels: &[Option<String>]) -> String {
+        let number = |value: usize| Value::Number(value as f64);
+        let primary_columns = self.primary_columns.to_usize_unwrap();
+        let columns = self.columns.to_usize_unwrap();
+
+        let rows = (0..self.row_starts.len())
+            .map(|i| {
+                let row = self.row(i);
+                let mut entries = vec![];
+
+                if let Some(Some(label)) = labels.get(i) {
+                    entries.push((String::from("label"), Value::String(label.clone())));
+                }
+
+                let cols = row
+                    .iter()
+                    .map(|&(column, _)| number(column.to_usize_unwrap()))
+                    .collect();
+                entries.push((String::from("cols"), Value::Array(cols)));
+
+                if row.iter().any(|&(_, color)| color.is_some()) {
+                    let colors = row
+                        .iter()
+                        .map(|&(_, color)| {
+                            color.map_or(Value::Null, |color| number(color as usize))
+                        })
+                        .collect();
+                    entries.push((String::from("colors"), Value::Array(colors)));
+                }
+
+                Value::Object(entries)
+            })
+            .collect();
+
+        Value::Object(vec![
+            (String::from("columns"), number(primary_columns)),
+            (String::from("secondary"), number(columns - primary_columns)),
+            (String::from("rows"), Value::Array(rows)),
+        ])
+        .to_string()
+    }
+
+    // Returns the matrix along with the label of each row
+    pub fn from_json(text: &str) -> Result<(Self, Vec<Option<String>>), ExchangeError> {
+        let value: Value = text.parse().map_err(ExchangeError::Json)?;
+
+        let primary_columns = match value.get("columns") {
+            Some(columns) => {
+                integer(columns).ok_or(ExchangeError::InvalidField { field: "columns" })?
+            }
+            None => return Err(ExchangeError::MissingField { field: "columns" }),
+        };
+        let secondary_columns = match value.get("secondary") {
+            Some(Value::Null) | None => 0,
+            Some(secondary) => {
+                integer(secondary).ok_or(ExchangeError::InvalidField { field: "secondary" })?
+            }
+        };
+        let rows = match value.get("rows") {
+            Some(rows) => rows
+                .as_array()
+                .ok_or(ExchangeError::InvalidField { field: "rows" })?,
+            None => return Err(ExchangeError::MissingField { field: "rows" }),
+        };
+
+        let columns = primary_columns
+            .checked_add(secondary_columns)
+            .filter(|&columns| S::from_usize(columns).is_some())
+            .ok_or(ExchangeError::InvalidField { field: "columns" })?;
+
+        let mut matrix = DLXMatrix::with_secondary_columns(
+            S::from_usize_unwrap(primary_columns),
+            S::from_usize_unwrap(secondary_columns),
+        );
+        let mut labels = vec![];
+
+        // Every node of the matrix, the column headers included, is indexed by S
+        let mut nodes = columns + 1;
+
+        for (i, row) in rows.iter().enumerate() {
+            let invalid = ExchangeError::InvalidRow { row: i };
+
+            let label = match row.get("label") {
+                Some(Value::Null) | None => None,
+                Some(label) => Some(label.as_str().ok_or(invalid)?.to_string()),
+            };
+
+            let cols = row
+                .get("cols")
+                .and_then(Value::as_array)
+                .filter(|cols| !cols.is_empty())
+                .ok_or(invalid)?;
+            let mut row_columns = Vec::with_capacity(cols.len());
+            for col in cols {
+                let column = integer(col)
+                    .filter(|&column| column < columns)
+                    .ok_or(invalid)?;
+                if row_columns.contains(&column) {
+                    return Err(invalid);
+                }
+                row_columns.push(column);
+            }
+
+            let colors = match row.get("colors") {
+                Some(Value::Null) | None => vec![None; cols.len()],
+                Some(colors) => {
+                    let colors = colors
+                        .as_array()
+                        .filter(|colors| colors.len() == cols.len());
+                    let colors = colors.ok_or(invalid)?;
+                    let mut row_colors = Vec::with_capacity(colors.len());
+                    for (color, &column) in colors.iter().zip(&row_columns) {
+                        let color = match color {
+                            Value::Null => None,
+                            _ => Some(
+                                integer(color)
+                                    .and_then(|color| u32::try_from(color).ok())
+                                    .filter(|&color| color < PURIFIED - 1)
+                                    .filter(|_| column >= primary_columns)
+                                    .ok_or(invalid)?,
+                            ),
+                        };
+                        row_colors.push(color);
+                    }
+                    row_colors
+                }
+            };
+
+            nodes += row_columns.len();
+            if S::from_usize(nodes).is_none() {
+                return Err(ExchangeError::TooLarge { row: i });
+            }
+
+            let row: Vec<(S, Option<u32>)> = row_columns
+                .into_iter()
+                .map(S::from_usize_unwrap)
+                .zip(colors)
+                .collect();
+            if row.iter().any(|&(_, color)| color.is_some()) {
+                matrix.push_colored_row(&row);
+            } else {
+                matrix.push_row(&row.iter().map(|&(column, _)| column).collect::<Vec<_>>());
+            }
+            labels.push(label);
+        }
+
+        Ok((matrix, labels))
+    }
+}
+
+// Solutions are exchanged as the indices of their rows, along with the labels of those rows if
+// the problem had any:
+//
+//     {"rows": [0, 2], "labels": ["a", null]}
+pub fn solution_to_json(rows: &[usize], labels: &[Option<String>]) -> String {
+    let mut entries = vec![(
+        String::from("rows"),
+        Value::Array(rows.iter().map(|&row| Value::Number(row as f64)).collect()),
+    )];
+
+    if !labels.is_empty() {
+        let labels = rows
+            .iter()
+            .map(|&row| match labels.get(row) {
+                Some(Some(label)) => Value::String(label.clone()),
+                _ => Value::Null,
+            })
+            .collect();
+        entries.push((String::from("labels"), Value::Array(labels)));
+    }
+
+    Value::Object(entries).to_string()
+}
+
+pub fn solution_from_json(text: &str) -> Result<Vec<usize>, ExchangeError> {
+    let value: Value = text.parse().map_err(ExchangeError::Json)?;
+    let rows = value
+        .get("rows")
+        .ok_or(ExchangeError::MissingField { field: "rows" })?;
+
+    rows.as_array()
+        .and_then(|rows| rows.iter().map(integer).collect())
+        .ok_or(ExchangeError::InvalidField { field: "rows" })
+}
+
+fn integer(value: &Value) -> Option<usize> {
+    value
+        .as_f64()
+        .filter(|&value| value >= 0.0 && value.fract() == 0.0 && value < usize::MAX as f64)
+        .map(|value| value as usize)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExchangeError {
+    Json(JsonError),
+    MissingField { field: &'static str },
+    InvalidField { field: &'static str },
+    InvalidRow { row: usize },
+    TooLarge { row: usize },
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ExchangeError::Json(error) => write!(f, "{}", error),
+            ExchangeError::MissingField { field } => write!(f, "Missing field {:?}", field),
+            ExchangeError::InvalidField { field } => write!(f, "Invalid field {:?}", field),
+            ExchangeError::InvalidRow { row } => write!(f, "Invalid row {}", row),
+            ExchangeError::TooLarge { row } => {
+                write!(
+                    f,
+                    "Too many nodes for the matrix's index type at row {}",
+                    row
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::json::{solution_from_json, solution_to_json, ExchangeError, JsonError, Value};
+    use crate::DLXMatrix;
+    use core::str::FromStr;
+
+    #[test]
+    fn test_json() {
+        let value =
+            Value::from_str(r#" {"a": [1, -2.5e1, true, null], "b": {"c": "x\"é😀"}, "d": []} "#)
+                .unwrap();
+
+        assert_eq!(
+            value.get("a").unwrap().as_array().unwrap()[1].as_f64(),
+            Some(-25.0)
+        );
+        assert_eq!(
+            value.get("b").and_then(|b| b.get("c")).unwrap().as_str(),
+            Some("x\"é😀")
+        );
+        assert!(value.get("d").unwrap().is_empty());
+        assert!(value.get("e").is_none());
+
+        let string = value.to_string();
+        assert_eq!(Value::from_str(&string).unwrap(), value);
+
+        assert_eq!(Value::from_str("[1,]"), Err(JsonError { offset: 3 }));
+        assert_eq!(Value::from_str("{} x"), Err(JsonError { offset: 3 }));
+        assert!(Value::from_str("\"abc").is_err());
+
+        assert_eq!(Value::from_str("1e999"), Err(JsonError { offset: 0 }));
+        assert_eq!(Value::from_str("[-1e999]"), Err(JsonError { offset: 1 }));
+        let numbers = Value::Array(vec![Value::Number(f64::INFINITY), Value::Number(f64::NAN)]);
+        assert_eq!(numbers.to_string(), "[null,null]");
+    }
+
+    #[test]
+    fn test_nesting() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(Value::from_str(&nested(128)).is_ok());
+        assert_eq!(
+            Value::from_str(&nested(129)),
+            Err(JsonError { offset: 128 })
+        );
+
+        let objects = format!("{}1{}", r#"{"a":"#.repeat(129), "}".repeat(129));
+        assert_eq!(Value::from_str(&objects), Err(JsonError { offset: 640 }));
+
+        // Deep enough to overflow the stack without the limit
+        assert!(Value::from_str(&"[".repeat(300000)).is_err());
+        assert!(Value::from_str(&"{\"a\":".repeat(300000)).is_err());
+    }
+
+    #[test]
+    fn test_exchange() {
+        let mut matrix = DLXMatrix::<u32>::with_secondary_columns(3, 1);
+        matrix.push_row(&[0, 3]);
+        matrix.push_row(&[1, 2]);
+        matrix.push_colored_row(&[(0, None), (3, Some(5))]);
+        matrix.push_row(&[2, 1]);
+
+        let labels = vec![Some(String::from("a")), None, Some(String::from("\"c\""))];
+        let json = matrix.to_json_with_labels(&labels);
+        assert_eq!(
+            json,
+            r#"{"columns":3,"secondary":1,"rows":[{"label":"a","cols":[0,3]},{"cols":[1,2]},{"label":"\"c\"","cols":[0,3],"colors":[null,5]},{"cols":[2,1]}]}"#
+        );
+
+        let (parsed, parsed_labels) = DLXMatrix::<u32>::from_json(&json).unwrap();
+        assert_eq!(parsed.to_json_with_labels(&labels), json);
+        assert_eq!(parsed_labels[..3], labels[..]);
+        assert_eq!(parsed_labels[3], None);
+
+        let mut solutions: Vec<_> = parsed.solutions().row_indices().collect();
+        for rows in &mut solutions {
+            rows.sort_unstable();
+        }
+        solutions.sort();
+        assert_eq!(
+            solutions,
+            vec![vec![0, 1], vec![0, 3], vec![1, 2], vec![2, 3]]
+        );
+
+        let (matrix, labels) =
+            DLXMatrix::<u8>::from_json(r#"{"columns": 2, "rows": [{"cols": [0, 1]}]}"#).unwrap();
+        assert_eq!(matrix.row(0), vec![(0, None), (1, None)]);
+        assert_eq!(labels, vec![None]);
+        assert_eq!(
+            matrix.to_json(),
+            r#"{"columns":2,"secondary":0,"rows":[{"cols":[0,1]}]}"#
+        );
+
+        let solution = solution_to_json(&[0, 2], &parsed_labels);
+        assert_eq!(solution, r#"{"rows":[0,2],"labels":["a","\"c\""]}"#);
+        assert_eq!(solution_from_json(&solution), Ok(vec![0, 2]));
+        assert_eq!(solution_to_json(&[1], &[]), r#"{"rows":[1]}"#);
+    }
+
+    #[test]
+    fn test_exchange_errors() {
+        let parse = |text: &str| DLXMatrix::<u8>::from_json(text).map(|_| ());
+
+        assert_eq!(
+            parse(r#"{"rows": []}"#),
+            Err(ExchangeError::MissingField { field: "columns" })
+        );
+        assert_eq!(
+            parse(r#"{"columns": 1.5, "rows": []}"#),
+            Err(ExchangeError::InvalidField { field: "columns" })
+        );
+        assert_eq!(
+            parse(r#"{"columns": 200, "secondary": 100, "rows": []}"#),
+            Err(ExchangeError::InvalidField { field: "columns" })
+        );
+        assert_eq!(
+            parse(r#"{"columns": 1, "rows": {}}"#),
+            Err(ExchangeError::InvalidField { field: "rows" })
+        );
+        assert!(matches!(parse("{"), Err(ExchangeError::Json(_))));
+
+        for row in [
+            r#"{"cols": []}"#,
+            r#"{"cols": [2]}"#,
+            r#"{"cols": [0, 0]}"#,
+            r#"{"cols": [0], "label": 1}"#,
+            r#"{"cols": [1], "colors": [1, 2]}"#,
+            r#"{"cols": [0], "colors": [1]}"#,
+        ] {
+            let text = format!(
+                r#"{{"columns": 1, "secondary": 1, "rows": [{{"cols": [0]}}, {}]}}"#,
+                row
+            );
+            assert_eq!(parse(&text), Err(ExchangeError::InvalidRow { row: 1 }));
+        }
+
+        // 201 column headers and 27 rows of two nodes is 255 nodes, as many as u8 can index
+        let rows = |count| vec![r#"{"cols": [0, 1]}"#; count].join(",");
+        let text = format!(r#"{{"columns": 200, "rows": [{}]}}"#, rows(27));
+        assert!(parse(&text).is_ok());
+        let text = format!(r#"{{"columns": 200, "rows": [{}]}}"#, rows(28));
+        assert_eq!(parse(&text), Err(ExchangeError::TooLarge { row: 27 }));
+        assert!(DLXMatrix::<u16>::from_json(&text).is_ok());
+
+        assert_eq!(
+            solution_from_json(r#"{"rows": [-1]}"#),
+            Err(ExchangeError::InvalidField { field: "rows" })
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use crate::latin::LatinSquare;
+use crate::DLXMatrix;
+
+use core::fmt;
+
+// The values a row of the matrix places, as (x, y, value)
+type Placement = Vec<(usize, usize, u8)>;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Add,
+    // The larger of two cells minus the smaller
+    Subtract,
+    Multiply,
+    // The larger of two cells divided by the smaller, exactly
+    Divide,
+    // A single cell holding the target itself
+    Given,
+}
+
+impl Operation {
+    fn apply(self, values: &[u8]) -> Option<u64> {
+        let (low, high) = match *values {
+            [a, b] => (a.min(b) as u64, a.max(b) as u64),
+            _ => (0, 0),
+        };
+
+        match self {
+            Operation::Add => Some(values.iter().map(|&value| value as u64).sum()),
+            Operation::Multiply => Some(values.iter().map(|&value| value as u64).product()),
+            Operation::Subtract => Some(high - low),
+            Operation::Divide if high % low == 0 => Some(high / low),
+            Operation::Divide => None,
+            Operation::Given => Some(values[0] as u64),
+        }
+    }
+
+    // The sizes of cage the operation makes sense for
+    fn sizes(self) -> (usize, usize) {
+        match self {
+            Operation::Add | Operation::Multiply => (1, usize::MAX),
+            Operation::Subtract | Operation::Divide => (2, 2),
+            Operation::Given => (1, 1),
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let symbol = match self {
+            Operation::Add => "+",
+            Operation::Subtract => "-",
+            Operation::Multiply => "*",
+            Operation::Divide => "/",
+            Operation::Given => "",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Cage {
+    cells: Vec<(usize, usize)>,
+    operation: Operation,
+    target: u64,
+}
+
+impl Cage {
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    pub fn target(&self) -> u64 {
+        self.target
+    }
+
+    // Every way of filling the cage that hits the target without repeating a value within a row or
+    // column, with values in the same order as the cells
+    fn fillings(&self, order: usize) -> Vec<Vec<u8>> {
+        let mut fillings = vec![];
+        let mut values = Vec::with_capacity(self.cells.len());
+        self.extend(order, &mut values, &mut fillings);
+        fillings
+    }
+
+    fn extend(&self, order: usize, values: &mut Vec<u8>, fillings: &mut Vec<Vec<u8>>) {
+        let i = values.len();
+        if i == self.cells.len() {
+            if self.operation.apply(values) == Some(self.target) {
+                fillings.push(values.clone());
+            }
+            return;
+        }
+
+        let (x, y) = self.cells[i];
+
+        for value in 1..=order as u8 {
+            let clashes = self.cells[..i]
+                .iter()
+                .zip(values.iter())
+                .any(|(&(x0, y0), &v0)| v0 == value && (x0 == x || y0 == y));
+
+            // Sums and products only grow, so a partial filling already past the target is dropped
+            let partial = match self.operation {
+                Operation::Add => values.iter().map(|&v| v as u64).sum::<u64>() + value as u64,
+                Operation::Multiply => {
+                    values.iter().map(|&v| v as u64).product::<u64>() * value as u64
+                }
+                _ => 0,
+            };
+
+            if !clashes && partial <= self.target {
+                values.push(value);
+                self.extend(order, values, fillings);
+                values.pop();
+            }
+        }
+    }
+}
+
+// A Latin square of the given order, divided into cages whose cells must combine under an
+// operation to give a target. Cells outside every cage are constrained by the square alone
+#[derive(Clone, Debug)]
+pub struct KenKen {
+    order: usize,
+    cages: Vec<Cage>,
+    cage_ids: Vec<Option<usize>>,
+}
+
+impl KenKen {
+    pub fn new(order: usize) -> Self {
+        assert!(
+            (1..=255).contains(&order),
+            "Order must be in the range 1..=255 (got {})",
+            order
+        );
+
+        Self {
+            order,
+            cages: vec![],
+            cage_ids: vec![None; order * order],
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    pub fn add_cage(
+        &mut self,
+        cells: &[(usize, usize)],
+        operation: Operation,
+        target: u64,
+    ) -> Result<(), CageError> {
+        let (min, max) = operation.sizes();
+        if cells.len() < min || cells.len() > max {
+            return Err(CageError::InvalidSize {
+                size: cells.len(),
+                operation,
+            });
+        }
+
+        for (i, &(x, y)) in cells.iter().enumerate() {
+            if x >= self.order || y >= self.order {
+                return Err(CageError::OutOfBounds { x, y });
+            }
+
+            if self.cage_ids[self.order * y + x].is_some() || cells[..i].contains(&(x, y)) {
+                return Err(CageError::Overlapping { x, y });
+            }
+        }
+
+        let cage = Cage {
+            cells: cells.to_vec(),
+            operation,
+            target,
+        };
+
+        if cage.fillings(self.order).is_empty() {
+            return Err(CageError::ImpossibleTarget { operation, target });
+        }
+
+        for &(x, y) in cells {
+            self.cage_ids[self.order * y + x] = Some(self.cages.len());
+        }
+        self.cages.push(cage);
+
+        Ok(())
+    }
+
+    pub fn solve(&self) -> Option<LatinSquare> {
+        let (matrix, placements) = self.encode();
+        let mut solution = matrix.solve()?;
+        let mut solved = LatinSquare::new(self.order);
+
+        while let Some(row) = solution.next() {
+            for &(x, y, value) in &placements[row.index(&solution)] {
+                solved.set(x, y, value);
+            }
+        }
+
+        Some(solved)
+    }
+
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.encode().0.count_solutions(limit)
+    }
+
+    // The Latin square encoding, with one column per cell, per row and value, and per column and
+    // value. A row places a whole filling of a cage at once, or a single value in a cell outside
+    // every cage
+    fn encode(&self) -> (DLXMatrix<u32>, Vec<Placement>) {
+        let n = self.order;
+        let mut matrix = DLXMatrix::new((3 * n * n) as u32);
+        let mut placements = vec![];
+        let mut row = vec![];
+
+        let mut push = |cells: &[(usize, usize)], values: &[u8]| {
+            row.clear();
+            for (&(x, y), &value) in cells.iter().zip(values) {
+                let digit = (value - 1) as usize;
+                row.push((n * y + x) as u32);
+                row.push((n * n + n * y + digit) as u32);
+                row.push((2 * n * n + n * x + digit) as u32);
+            }
+            matrix.push_row(&row);
+
+            placements.push(
+                cells
+                    .iter()
+                    .zip(values)
+                    .map(|(&(x, y), &value)| (x, y, value))
+                    .collect(),
+            );
+        };
+
+        for cage in &self.cages {
+            for filling in cage.fillings(n) {
+                push(&cage.cells, &filling);
+            }
+        }
+
+        for y in 0..n {
+            for x in 0..n {
+                if self.cage_ids[n * y + x].is_none() {
+                    for value in 1..=n as u8 {
+                        push(&[(x, y)], &[value]);
+                    }
+                }
+            }
+        }
+
+        (matrix, placements)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub enum CageError {
+    InvalidSize { size: usize, operation: Operation },
+    OutOfBounds { x: usize, y: usize },
+    Overlapping { x: usize, y: usize },
+    ImpossibleTarget { operation: Operation, target: u64 },
+}
+
+impl fmt::Display for CageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            CageError::InvalidSize { size, operation } => {
+                write!(f, "A {:?} cage can't have {} cell(s)", operation, size)?;
+            }
+            CageError::OutOfBounds { x, y } => {
+                write!(f, "Cage cell ({}, {}) is outside the grid", x, y)?;
+            }
+            CageError::Overlapping { x, y } => {
+                write!(f, "Cell ({}, {}) belongs to more than one cage", x, y)?;
+            }
+            CageError::ImpossibleTarget { operation, target } => {
+                write!(f, "No filling of the cage gives {}{}", target, operation)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::kenken::{CageError, KenKen, Operation};
+    use crate::latin::LatinSquare;
+
+    fn validate(kenken: &KenKen, square: &LatinSquare) {
+        assert!(square.is_complete());
+
+        for cage in kenken.cages() {
+            let values: Vec<u8> = cage
+                .cells()
+                .iter()
+                .map(|&(x, y)| square.get(x, y))
+                .collect();
+            assert_eq!(cage.operation().apply(&values), Some(cage.target()));
+        }
+
+        for i in 0..kenken.order() {
+            let mut row: Vec<u8> = (0..kenken.order()).map(|x| square.get(x, i)).collect();
+            let mut column: Vec<u8> = (0..kenken.order()).map(|y| square.get(i, y)).collect();
+            row.sort_unstable();
+            column.sort_unstable();
+            assert_eq!(row, (1..=kenken.order() as u8).collect::<Vec<_>>());
+            assert_eq!(column, row);
+        }
+    }
+
+    #[test]
+    fn test_kenken() {
+        // 1 2 3 4
+        // 3 4 1 2
+        // 2 1 4 3
+        // 4 3 2 1
+        let mut kenken = KenKen::new(4);
+        let cages = [
+            (vec![(0, 0), (0, 1)], Operation::Add, 4),
+            (vec![(1, 0), (2, 0)], Operation::Multiply, 6),
+            (vec![(3, 0), (3, 1)], Operation::Multiply, 8),
+            (vec![(1, 1), (2, 1)], Operation::Subtract, 3),
+            (vec![(0, 2), (1, 2), (0, 3)], Operation::Multiply, 8),
+            (vec![(2, 2), (3, 2)], Operation::Subtract, 1),
+            (vec![(1, 3)], Operation::Given, 3),
+            (vec![(2, 3), (3, 3)], Operation::Add, 3),
+        ];
+        for (cells, operation, target) in cages {
+            kenken.add_cage(&cells, operation, target).unwrap();
+        }
+
+        let solved = kenken.solve().unwrap();
+        validate(&kenken, &solved);
+        assert_eq!(solved.get(3, 3), 1);
+        assert_eq!(solved.get(1, 0), 2);
+
+        assert_eq!(kenken.count_solutions(usize::MAX), 1);
+    }
+
+    #[test]
+    fn test_uncaged_cells() {
+        // With no cages at all, every Latin square is a solution
+        assert_eq!(KenKen::new(3).count_solutions(usize::MAX), 12);
+
+        let mut kenken = KenKen::new(3);
+        kenken
+            .add_cage(&[(0, 0), (1, 0), (2, 0)], Operation::Multiply, 6)
+            .unwrap();
+        kenken.add_cage(&[(0, 1)], Operation::Given, 3).unwrap();
+
+        kenken.add_cage(&[(1, 2)], Operation::Given, 3).unwrap();
+        let solved = kenken.solve().unwrap();
+        validate(&kenken, &solved);
+
+        kenken.add_cage(&[(2, 2)], Operation::Given, 3).unwrap();
+        assert!(kenken.solve().is_none());
+    }
+
+    #[test]
+    fn test_cage_errors() {
+        let mut kenken = KenKen::new(4);
+        kenken
+            .add_cage(&[(0, 0), (1, 0)], Operation::Add, 3)
+            .unwrap();
+
+        assert!(matches!(
+            kenken.add_cage(&[(2, 0), (3, 0), (2, 1)], Operation::Subtract, 1),
+            Err(CageError::InvalidSize { size: 3, .. })
+        ));
+        assert!(matches!(
+            kenken.add_cage(&[(4, 0)], Operation::Given, 1),
+            Err(CageError::OutOfBounds { x: 4, y: 0 })
+        ));
+        assert!(matches!(
+            kenken.add_cage(&[(1, 0), (2, 0)], Operation::Add, 5),
+            Err(CageError::Overlapping { x: 1, y: 0 })
+        ));
+        assert!(matches!(
+            kenken.add_cage(&[(2, 0), (3, 0)], Operation::Divide, 5),
+            Err(CageError::ImpossibleTarget { target: 5, .. })
+        ));
+        assert!(matches!(
+            kenken.add_cage(&[(2, 0), (3, 0)], Operation::Add, 2),
+            Err(CageError::ImpossibleTarget { target: 2, .. })
+        ));
+    }
+}
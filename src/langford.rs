@@ -0,0 +1,124 @@
+use crate::DLXMatrix;
+
+// Arranging two copies of each of the numbers 1 to n in a row. In a Langford pairing the copies of
+// k have k numbers between them, and in a Skolem sequence they are k places apart
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Langford {
+    n: usize,
+    skolem: bool,
+}
+
+impl Langford {
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0, "There must be at least one pair");
+        Self { n, skolem: false }
+    }
+
+    pub fn skolem(n: usize) -> Self {
+        Self {
+            skolem: true,
+            ..Self::new(n)
+        }
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_skolem(&self) -> bool {
+        self.skolem
+    }
+
+    pub fn solve(&self) -> Option<Vec<usize>> {
+        self.enumerate().next()
+    }
+
+    // Every sequence is counted along with its reversal, so this is twice the usual count
+    pub fn count(&self, limit: usize) -> usize {
+        self.encode().0.count_solutions(limit)
+    }
+
+    pub fn enumerate(&self) -> impl Iterator<Item = Vec<usize>> {
+        let (matrix, placements) = self.encode();
+        let length = 2 * self.n;
+
+        matrix.solutions().row_indices().map(move |rows| {
+            let mut sequence = vec![0; length];
+            for row in rows {
+                let (k, first, second) = placements[row];
+                sequence[first] = k;
+                sequence[second] = k;
+            }
+            sequence
+        })
+    }
+
+    // One column per number, then one per position, with a row for each place a pair could go
+    fn encode(&self) -> (DLXMatrix<u32>, Vec<(usize, usize, usize)>) {
+        let n = self.n;
+        let mut matrix = DLXMatrix::new(3 * n as u32);
+        let mut placements = vec![];
+
+        for k in 1..=n {
+            let distance = if self.skolem { k } else { k + 1 };
+
+            for first in 0..(2 * n).saturating_sub(distance) {
+                let second = first + distance;
+                matrix.push_row(&[(k - 1) as u32, (n + first) as u32, (n + second) as u32]);
+                placements.push((k, first, second));
+            }
+        }
+
+        (matrix, placements)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::langford::Langford;
+
+    fn validate(problem: Langford, sequence: &[usize]) {
+        let n = problem.n();
+        assert_eq!(sequence.len(), 2 * n);
+
+        for k in 1..=n {
+            let positions: Vec<usize> = (0..2 * n).filter(|&i| sequence[i] == k).collect();
+            let distance = if problem.is_skolem() { k } else { k + 1 };
+            assert_eq!(positions.len(), 2);
+            assert_eq!(positions[1] - positions[0], distance);
+        }
+    }
+
+    #[test]
+    fn test_langford() {
+        // OEIS A014552, doubled for reversals
+        for (n, count) in [(1, 0), (2, 0), (3, 2), (4, 2), (5, 0), (7, 52), (8, 300)] {
+            assert_eq!(Langford::new(n).count(usize::MAX), count);
+        }
+
+        let solved = Langford::new(3).solve().unwrap();
+        assert!(solved == [2, 3, 1, 2, 1, 3] || solved == [3, 1, 2, 1, 3, 2]);
+
+        let problem = Langford::new(7);
+        let sequences: Vec<Vec<usize>> = problem.enumerate().collect();
+        assert_eq!(sequences.len(), 52);
+        for sequence in &sequences {
+            validate(problem, sequence);
+        }
+        assert!(Langford::new(5).solve().is_none());
+    }
+
+    #[test]
+    fn test_skolem() {
+        // OEIS A059106
+        for (n, count) in [(1, 1), (2, 0), (3, 0), (4, 6), (5, 10), (6, 0), (8, 504)] {
+            assert_eq!(Langford::skolem(n).count(usize::MAX), count);
+        }
+
+        let problem = Langford::skolem(5);
+        for sequence in problem.enumerate() {
+            validate(problem, &sequence);
+        }
+        assert_eq!(problem.count(3), 3);
+    }
+}
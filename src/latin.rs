@@ -0,0 +1,204 @@
+use crate::DLXMatrix;
+
+use core::fmt;
+
+type Placement = (usize, usize, u8);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LatinSquare {
+    order: usize,
+    cells: Vec<u8>,
+}
+
+impl LatinSquare {
+    pub fn new(order: usize) -> Self {
+        assert!(
+            (1..=255).contains(&order),
+            "Order must be in the range 1..=255 (got {})",
+            order
+        );
+
+        Self {
+            order,
+            cells: vec![0; order * order],
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        assert!(x < self.order && y < self.order);
+        assert!(value as usize <= self.order);
+        self.cells[self.order * y + x] = value;
+    }
+
+    pub fn clear(&mut self, x: usize, y: usize) {
+        self.set(x, y, 0);
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        assert!(x < self.order && y < self.order);
+        self.cells[self.order * y + x]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, &value)| (i % self.order, i / self.order, value))
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.cells.iter().all(|&value| value != 0)
+    }
+
+    pub fn solve(&self) -> Option<LatinSquare> {
+        let (matrix, placements) = self.encode()?;
+        let mut solution = matrix.solve()?;
+        let mut solved = Self::new(self.order);
+
+        while let Some(row) = solution.next() {
+            let (x, y, value) = placements[row.index(&solution)];
+            solved.set(x, y, value);
+        }
+
+        Some(solved)
+    }
+
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        match self.encode() {
+            Some((matrix, _)) => matrix.count_solutions(limit),
+            None => 0,
+        }
+    }
+
+    // Sudoku's encoding without the boxes: one column per cell, per row and digit, and per column
+    // and digit. Returns None if two givens share a row or column
+    fn encode(&self) -> Option<(DLXMatrix<u32>, Vec<Placement>)> {
+        let n = self.order;
+        let mut rows = vec![false; n * n];
+        let mut columns = vec![false; n * n];
+
+        for (x, y, value) in self.iter().filter(|&(_, _, value)| value != 0) {
+            let digit = (value - 1) as usize;
+
+            if rows[n * y + digit] || columns[n * x + digit] {
+                return None;
+            }
+
+            rows[n * y + digit] = true;
+            columns[n * x + digit] = true;
+        }
+
+        let mut matrix = DLXMatrix::new((3 * n * n) as u32);
+        let mut placements = vec![];
+
+        for (x, y, given) in self.iter() {
+            for value in 1..=n as u8 {
+                let digit = (value - 1) as usize;
+
+                let allowed = if given == 0 {
+                    !rows[n * y + digit] && !columns[n * x + digit]
+                } else {
+                    value == given
+                };
+
+                if allowed {
+                    matrix.push_row(&[
+                        (n * y + x) as u32,
+                        (n * n + n * y + digit) as u32,
+                        (2 * n * n + n * x + digit) as u32,
+                    ]);
+                    placements.push((x, y, value));
+                }
+            }
+        }
+
+        Some((matrix, placements))
+    }
+}
+
+impl fmt::Display for LatinSquare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let width = self.order.to_string().len();
+
+        for y in 0..self.order {
+            for x in 0..self.order {
+                if x > 0 {
+                    write!(f, " ")?;
+                }
+
+                match self.get(x, y) {
+                    0 => write!(f, "{:>width$}", ".", width = width)?,
+                    value => write!(f, "{:>width$}", value, width = width)?,
+                }
+            }
+
+            if y + 1 < self.order {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::latin::LatinSquare;
+
+    fn validate(square: &LatinSquare) {
+        let n = square.order();
+        assert!(square.is_complete());
+
+        for i in 0..n {
+            let mut row = (0..n).map(|x| square.get(x, i)).collect::<Vec<_>>();
+            let mut column = (0..n).map(|y| square.get(i, y)).collect::<Vec<_>>();
+            row.sort_unstable();
+            column.sort_unstable();
+            assert_eq!(row, (1..=n as u8).collect::<Vec<_>>());
+            assert_eq!(column, row);
+        }
+    }
+
+    #[test]
+    fn test_latin_square() {
+        for n in 1..=12 {
+            validate(&LatinSquare::new(n).solve().unwrap());
+        }
+
+        // The number of Latin squares of orders 1 through 4
+        for (n, count) in [(1, 1), (2, 2), (3, 12), (4, 576)] {
+            assert_eq!(LatinSquare::new(n).count_solutions(usize::MAX), count);
+        }
+
+        let mut square = LatinSquare::new(4);
+        square.set(0, 0, 1);
+        square.set(0, 1, 1);
+        assert_eq!(square.count_solutions(usize::MAX), 0);
+        assert!(square.solve().is_none());
+
+        square.clear(0, 1);
+        square.set(1, 0, 2);
+        square.set(0, 1, 3);
+        let solved = square.solve().unwrap();
+        validate(&solved);
+        assert_eq!(
+            (solved.get(0, 0), solved.get(1, 0), solved.get(0, 1)),
+            (1, 2, 3)
+        );
+
+        assert_eq!(
+            LatinSquare::new(3)
+                .solve()
+                .unwrap()
+                .to_string()
+                .lines()
+                .count(),
+            3
+        );
+        assert_eq!(square.to_string().lines().next(), Some("1 2 . ."));
+    }
+}
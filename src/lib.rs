@@ -1,8 +1,34 @@
 use core::cmp;
 use core::fmt;
+use std::time::Instant;
 use std::vec;
 
+pub mod binairo;
+pub mod coloring;
+pub mod cover;
+pub mod crossword;
+pub mod domino;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod json;
+pub mod kenken;
+pub mod langford;
+pub mod latin;
+pub mod magic;
+pub mod partition;
+pub mod polycube;
+pub mod polyomino;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod queens;
+pub mod rng;
+pub mod schedule;
+pub mod steiner;
 pub mod sudoku;
+pub mod testgen;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod writer;
 
 fn on_integer_overflow<T>() -> T {
     panic!("Integer overflow");
@@ -36,21 +62,48 @@ struct Node<S: Size> {
 
 pub struct DLXMatrix<S: Size> {
     columns: S,
+    primary_columns: S,
     buffer: Vec<Node<S>>,
+    row_starts: Vec<S>,
+    // The color of each node plus one, so that 0 is uncolored, or empty until some row is colored
+    colors: Vec<u32>,
 }
 
+// Marks the nodes of rows whose color matches one already chosen for the column, so that choosing
+// them doesn't purify the column a second time
+const PURIFIED: u32 = u32::MAX;
+
 impl<S: Size> DLXMatrix<S> {
     pub fn new(columns: S) -> Self {
+        Self::with_secondary_columns(columns, S::zero())
+    }
+
+    pub fn with_secondary_columns(primary_columns: S, secondary_columns: S) -> Self {
+        let columns = primary_columns + secondary_columns;
+
         let buffer = (0..=columns.to_usize_unwrap())
             .map(|i| {
                 let i = S::from_usize_unwrap(i);
 
-                let left = if i.is_zero() { columns } else { i - S::one() };
-
-                let right = if i == columns {
-                    S::zero()
+                // Secondary columns are left out of the header list, so they are never chosen for
+                // branching and need not be covered, but selecting a row still removes every row
+                // that conflicts with it
+                let (left, right) = if i == columns {
+                    if primary_columns.is_zero() {
+                        (i, i)
+                    } else {
+                        (primary_columns - S::one(), S::zero())
+                    }
+                } else if i < primary_columns {
+                    let left = if i.is_zero() { columns } else { i - S::one() };
+                    let right = if i + S::one() == primary_columns {
+                        columns
+                    } else {
+                        i + S::one()
+                    };
+                    (left, right)
                 } else {
-                    i + S::one()
+                    (i, i)
                 };
 
                 Node {
@@ -62,14 +115,30 @@ impl<S: Size> DLXMatrix<S> {
                 }
             })
             .collect();
-        DLXMatrix { columns, buffer }
+
+        DLXMatrix {
+            columns,
+            primary_columns,
+            buffer,
+            row_starts: vec![],
+            colors: vec![],
+        }
     }
 
     pub fn columns(&self) -> S {
         self.columns
     }
 
-    pub fn push_row(&mut self, columns: &[S]) {
+    pub fn primary_columns(&self) -> S {
+        self.primary_columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.row_starts.len()
+    }
+
+    // Returns the index of the new row, which solutions can report in place of its columns
+    pub fn push_row(&mut self, columns: &[S]) -> usize {
         assert!(!columns.is_empty(), "Rows must be non-empty");
 
         let row = self.buffer.len();
@@ -127,6 +196,10 @@ impl<S: Size> DLXMatrix<S> {
             }
         }
 
+        unsafe {
+            self.buffer.set_len(self.buffer.len() + columns.len());
+        }
+
         for i in 0..columns.len() {
             let node = S::from_usize_unwrap(row + i);
             unsafe {
@@ -138,14 +211,169 @@ impl<S: Size> DLXMatrix<S> {
             }
         }
 
-        unsafe {
-            self.buffer.set_len(self.buffer.len() + columns.len());
+        if !self.colors.is_empty() {
+            self.colors.resize(self.buffer.len(), 0);
+        }
+
+        self.row_starts.push(S::from_usize_unwrap(row));
+        self.row_starts.len() - 1
+    }
+
+    // A row whose secondary columns may be given colors. A colored column can be shared by any
+    // number of selected rows so long as they all give it the same color, while an uncolored one
+    // still allows at most one row, as with push_row
+    pub fn push_colored_row(&mut self, columns: &[(S, Option<u32>)]) -> usize {
+        for &(column, color) in columns {
+            if let Some(color) = color {
+                assert!(
+                    column >= self.primary_columns,
+                    "Only secondary columns can be colored (got {})",
+                    column
+                );
+                assert!(
+                    color < PURIFIED - 1,
+                    "Colors must be less than {}",
+                    PURIFIED - 1
+                );
+            }
+        }
+
+        let row = self.buffer.len();
+        let index = self.push_row(
+            &columns
+                .iter()
+                .map(|&(column, _)| column)
+                .collect::<Vec<_>>(),
+        );
+
+        self.colors.resize(self.buffer.len(), 0);
+        for (i, &(_, color)) in columns.iter().enumerate() {
+            self.colors[row + i] = color.map_or(0, |color| color + 1);
+        }
+
+        index
+    }
+
+    // The columns of a row in the order they were given, along with their colors for rows pushed
+    // with push_colored_row
+    pub fn row(&self, row: usize) -> Vec<(S, Option<u32>)> {
+        let start = self.row_starts[row].to_usize_unwrap();
+        let end = self
+            .row_starts
+            .get(row + 1)
+            .map_or(self.buffer.len(), |end| end.to_usize_unwrap());
+
+        (start..end)
+            .map(|node| {
+                let color = self.colors.get(node).copied().unwrap_or(0);
+                (self.buffer[node].column, color.checked_sub(1))
+            })
+            .collect()
+    }
+
+    fn row_index(&self, node: S) -> usize {
+        debug_assert!(node > self.columns);
+        row_index(&self.row_starts, node)
+    }
+
+    pub fn solve(self) -> Option<Solution<S>> {
+        self.solve_with_statistics().0
+    }
+
+    pub fn solve_with_statistics(self) -> (Option<Solution<S>>, Statistics) {
+        match self.solve_until(None) {
+            Ok(result) => result,
+            Err(TimedOut) => unreachable!(),
         }
     }
 
-    pub fn solve(mut self) -> Option<Solution<S>> {
+    // Gives up once the deadline has passed, which is checked every few thousand nodes
+    pub fn solve_with_deadline(self, deadline: Instant) -> Result<Option<Solution<S>>, TimedOut> {
+        self.solve_until(Some(deadline))
+            .map(|(solution, _)| solution)
+    }
+
+    fn solve_until(
+        mut self,
+        deadline: Option<Instant>,
+    ) -> Result<(Option<Solution<S>>, Statistics), TimedOut> {
+        const CLOCK_INTERVAL: u64 = 4096;
+
+        let mut rows = vec![];
+        let mut statistics = Statistics::default();
+        let mut timed_out = false;
+        let mut found = false;
+
+        // Backtracking doesn't add a node, so only selections look at the clock
+        let mut observe = |event| {
+            match event {
+                Event::Select(_) => {
+                    statistics.nodes += 1;
+
+                    if let Some(deadline) = deadline {
+                        timed_out |=
+                            statistics.nodes % CLOCK_INTERVAL == 0 && Instant::now() >= deadline;
+                    }
+                }
+                Event::Backtrack(_) => statistics.backtracks += 1,
+            }
+            timed_out
+        };
+
+        // The search also stops when the observer gives up, so a solution is only counted once it
+        // has actually been visited, and then takes precedence over the deadline
+        self.search(
+            &mut rows,
+            &mut observe,
+            &mut |_| {
+                found = true;
+                true
+            },
+            None::<&mut fn(&[S], S) -> bool>,
+        );
+
+        if timed_out && !found {
+            return Err(TimedOut);
+        }
+
+        let solution = if found {
+            Some(Solution {
+                matrix: self,
+                rows: rows.into_iter(),
+            })
+        } else {
+            None
+        };
+
+        Ok((solution, statistics))
+    }
+
+    // Reports each row as it is selected and again if it is backtracked, in search order
+    pub fn solve_traced<O>(mut self, mut observe: O) -> Option<Solution<S>>
+    where
+        O: FnMut(SearchEvent),
+    {
+        let row_starts = self.row_starts.clone();
         let mut rows = vec![];
-        if self.solve_recursive(&mut rows) {
+
+        let mut observe = |event| {
+            observe(match event {
+                Event::Select(node) => SearchEvent::Select {
+                    row: row_index(&row_starts, node),
+                },
+                Event::Backtrack(node) => SearchEvent::Backtrack {
+                    row: row_index(&row_starts, node),
+                },
+            });
+            false
+        };
+
+        if self.search(
+            &mut rows,
+            &mut observe,
+            &mut |_| true,
+            None::<&mut fn(&[S], S) -> bool>,
+        ) {
             Some(Solution {
                 matrix: self,
                 rows: rows.into_iter(),
@@ -155,6 +383,65 @@ impl<S: Size> DLXMatrix<S> {
         }
     }
 
+    // The filter is shown the indices of the rows selected so far and may veto any remaining row,
+    // pruning the search by constraints which exact cover cannot express. Rows are rechecked after
+    // every selection, so a column whose rows are all vetoed is a dead end straight away
+    pub fn solve_with_filter<F>(mut self, mut filter: F) -> Option<Solution<S>>
+    where
+        F: FnMut(&[usize], usize) -> bool,
+    {
+        let row_starts = self.row_starts.clone();
+        let mut rows = vec![];
+        let mut selected = vec![];
+
+        let mut accept = |solution: &[S], row: S| {
+            selected.clear();
+            selected.extend(solution.iter().map(|&node| row_index(&row_starts, node)));
+            filter(&selected, row_index(&row_starts, row))
+        };
+
+        unsafe {
+            self.hide_rejected_rows(&[], &mut accept);
+        }
+
+        let found = self.search(&mut rows, &mut |_| false, &mut |_| true, Some(&mut accept));
+
+        if found {
+            Some(Solution {
+                matrix: self,
+                rows: rows.into_iter(),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn solutions(self) -> Solutions<S> {
+        Solutions {
+            matrix: self,
+            stack: vec![],
+            started: false,
+        }
+    }
+
+    pub fn count_solutions(mut self, limit: usize) -> usize {
+        let mut count = 0;
+
+        if limit > 0 {
+            self.search(
+                &mut vec![],
+                &mut |_| false,
+                &mut |_| {
+                    count += 1;
+                    count >= limit
+                },
+                None::<&mut fn(&[S], S) -> bool>,
+            );
+        }
+
+        count
+    }
+
     unsafe fn get_unchecked(&self, i: S) -> &Node<S> {
         self.buffer.get_unchecked(S::to_usize_unwrap(i))
     }
@@ -163,7 +450,21 @@ impl<S: Size> DLXMatrix<S> {
         self.buffer.get_unchecked_mut(S::to_usize_unwrap(i))
     }
 
-    fn solve_recursive(&mut self, solution: &mut Vec<S>) -> bool {
+    // Visits every solution until the callback returns true, in which case the matrix is left in
+    // the state of the accepted solution. The search is also abandoned, returning true, as soon as
+    // the observer returns true
+    fn search<O, F, A>(
+        &mut self,
+        solution: &mut Vec<S>,
+        observe: &mut O,
+        visit: &mut F,
+        mut accept: Option<&mut A>,
+    ) -> bool
+    where
+        O: FnMut(Event<S>) -> bool,
+        F: FnMut(&[S]) -> bool,
+        A: FnMut(&[S], S) -> bool,
+    {
         if let Some(column) = self.choose_column() {
             let mut rows = ColumnIterator::new(column);
             rows.next(self);
@@ -173,20 +474,30 @@ impl<S: Size> DLXMatrix<S> {
                     self.select_row(row);
                 }
                 solution.push(row);
+                if observe(Event::Select(row)) {
+                    return true;
+                }
+
+                let hidden = match accept.as_deref_mut() {
+                    Some(accept) => unsafe { self.hide_rejected_rows(solution, accept) },
+                    None => vec![],
+                };
 
-                if self.solve_recursive(solution) {
+                if self.search(solution, observe, visit, accept.as_deref_mut()) {
                     return true;
                 }
 
                 unsafe {
+                    self.restore_hidden_rows(&hidden);
                     self.deselect_row(row);
                 }
                 solution.pop();
+                observe(Event::Backtrack(row));
             }
 
             false
         } else {
-            true
+            visit(solution)
         }
     }
 
@@ -207,12 +518,28 @@ impl<S: Size> DLXMatrix<S> {
         }
     }
 
+    fn color(&self, node: S) -> u32 {
+        self.colors
+            .get(node.to_usize_unwrap())
+            .copied()
+            .unwrap_or(0)
+    }
+
     unsafe fn select_row(&mut self, row: S) {
         let mut elements = RowIterator::new(row);
 
         while let Some(element) = elements.next(self) {
             let column = self.get_unchecked(element).column;
 
+            match self.color(element) {
+                0 => {}
+                PURIFIED => continue,
+                _ => {
+                    self.purify(element);
+                    continue;
+                }
+            }
+
             let mut conflicting_rows = ColumnIterator::new(column);
             conflicting_rows.next(self);
 
@@ -230,6 +557,15 @@ impl<S: Size> DLXMatrix<S> {
         while let Some(element) = elements.next(self) {
             let column = self.get_unchecked(element).column;
 
+            match self.color(element) {
+                0 => {}
+                PURIFIED => continue,
+                _ => {
+                    self.unpurify(element);
+                    continue;
+                }
+            }
+
             self.restore_column(column);
 
             let mut conflicting_rows = ReverseColumnIterator::new(column);
@@ -241,6 +577,114 @@ impl<S: Size> DLXMatrix<S> {
         }
     }
 
+    // Hides the rows giving the element's column a different color, and marks those giving it the
+    // same color so that selecting them later leaves the column alone
+    unsafe fn purify(&mut self, element: S) {
+        let color = self.color(element);
+        let column = self.get_unchecked(element).column;
+
+        let mut rows = ColumnIterator::new(column);
+        rows.next(self);
+
+        while let Some(row) = rows.next(self) {
+            if row == element {
+                continue;
+            }
+
+            if self.color(row) == color {
+                self.colors[row.to_usize_unwrap()] = PURIFIED;
+            } else {
+                self.remove_row(row);
+            }
+        }
+    }
+
+    unsafe fn unpurify(&mut self, element: S) {
+        let color = self.color(element);
+        let column = self.get_unchecked(element).column;
+
+        let mut rows = ReverseColumnIterator::new(column);
+        rows.next(self);
+
+        while let Some(row) = rows.next(self) {
+            if row == element {
+                continue;
+            }
+
+            if self.color(row) == PURIFIED {
+                self.colors[row.to_usize_unwrap()] = color;
+            } else {
+                self.restore_row(row);
+            }
+        }
+    }
+
+    // Unlinks every remaining row which the filter rejects, returning them for restore_hidden_rows
+    unsafe fn hide_rejected_rows<A>(&mut self, solution: &[S], accept: &mut A) -> Vec<S>
+    where
+        A: FnMut(&[S], S) -> bool,
+    {
+        let mut seen = vec![false; self.rows()];
+        let mut rejected = vec![];
+
+        let mut columns = RowIterator::new(self.columns);
+        columns.next(self);
+
+        while let Some(column) = columns.next(self) {
+            let mut rows = ColumnIterator::new(column);
+            rows.next(self);
+
+            while let Some(row) = rows.next(self) {
+                let index = self.row_index(row);
+
+                if !seen[index] {
+                    seen[index] = true;
+
+                    if !accept(solution, row) {
+                        rejected.push(row);
+                    }
+                }
+            }
+        }
+
+        for &row in &rejected {
+            self.remove_row(row);
+            self.unlink_node(row);
+        }
+
+        rejected
+    }
+
+    unsafe fn restore_hidden_rows(&mut self, hidden: &[S]) {
+        for &row in hidden.iter().rev() {
+            self.relink_node(row);
+            self.restore_row(row);
+        }
+    }
+
+    // remove_row leaves the given node linked, since it's normally in a column being removed
+    unsafe fn unlink_node(&mut self, node: S) {
+        let (column, up, down) = {
+            let node_ref = self.get_unchecked(node);
+            (node_ref.column, node_ref.up, node_ref.down)
+        };
+
+        self.get_unchecked_mut(column).column = self.get_unchecked(column).column - S::one();
+        self.get_unchecked_mut(up).down = down;
+        self.get_unchecked_mut(down).up = up;
+    }
+
+    unsafe fn relink_node(&mut self, node: S) {
+        let (column, up, down) = {
+            let node_ref = self.get_unchecked(node);
+            (node_ref.column, node_ref.up, node_ref.down)
+        };
+
+        self.get_unchecked_mut(column).column = self.get_unchecked(column).column + S::one();
+        self.get_unchecked_mut(up).down = node;
+        self.get_unchecked_mut(down).up = node;
+    }
+
     unsafe fn remove_row(&mut self, row: S) {
         let mut elements = RowIterator::new(row);
         elements.next(self);
@@ -355,6 +799,11 @@ dlx_matrix_iter_impl!(ReverseRowIterator, left);
 dlx_matrix_iter_impl!(ColumnIterator, down);
 dlx_matrix_iter_impl!(ReverseColumnIterator, up);
 
+// Rows occupy contiguous runs of nodes in insertion order, so any node of a row identifies it
+fn row_index<S: Size>(row_starts: &[S], node: S) -> usize {
+    row_starts.partition_point(|&start| start <= node) - 1
+}
+
 pub struct Solution<S: Size> {
     matrix: DLXMatrix<S>,
     rows: vec::IntoIter<S>,
@@ -368,6 +817,151 @@ impl<S: Size> Iterator for Solution<S> {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SearchEvent {
+    Select { row: usize },
+    Backtrack { row: usize },
+}
+
+// SearchEvent as seen inside the search, identifying rows by node until someone needs the index
+enum Event<S> {
+    Select(S),
+    Backtrack(S),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TimedOut;
+
+impl fmt::Display for TimedOut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "Search timed out")
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Statistics {
+    pub nodes: u64,
+    pub backtracks: u64,
+}
+
+pub struct Solutions<S: Size> {
+    matrix: DLXMatrix<S>,
+    stack: Vec<(S, S)>,
+    started: bool,
+}
+
+impl<S: Size> Solutions<S> {
+    // Selects rows greedily until either every primary column is covered (returning true) or some
+    // column has no remaining rows
+    fn descend(&mut self) -> bool {
+        while let Some(column) = self.matrix.choose_column() {
+            let row = unsafe { self.matrix.get_unchecked(column).down };
+
+            if row == column {
+                return false;
+            }
+
+            unsafe {
+                self.matrix.select_row(row);
+            }
+            self.stack.push((column, row));
+        }
+
+        true
+    }
+
+    // Replaces the most recently selected row with the next row in its column, unwinding the stack
+    // when a column is exhausted
+    fn advance(&mut self) -> bool {
+        while let Some((column, row)) = self.stack.pop() {
+            let next = unsafe {
+                self.matrix.deselect_row(row);
+                self.matrix.get_unchecked(row).down
+            };
+
+            if next != column {
+                unsafe {
+                    self.matrix.select_row(next);
+                }
+                self.stack.push((column, next));
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn next_stack(&mut self) -> bool {
+        let mut found = if self.started {
+            self.advance() && self.descend()
+        } else {
+            self.started = true;
+            self.descend()
+        };
+
+        while !found {
+            if !self.advance() {
+                return false;
+            }
+            found = self.descend();
+        }
+
+        true
+    }
+
+    pub fn row_indices(self) -> RowIndices<S> {
+        RowIndices { solutions: self }
+    }
+}
+
+impl<S: Size> Iterator for Solutions<S> {
+    type Item = Vec<Vec<S>>;
+
+    fn next(&mut self) -> Option<Vec<Vec<S>>> {
+        if !self.next_stack() {
+            return None;
+        }
+
+        let rows = self
+            .stack
+            .iter()
+            .map(|&(_, row)| {
+                let mut elements = RowIterator::new(row);
+                let mut columns = vec![];
+                while let Some(element) = elements.next(&self.matrix) {
+                    columns.push(unsafe { self.matrix.get_unchecked(element).column });
+                }
+                columns
+            })
+            .collect();
+
+        Some(rows)
+    }
+}
+
+pub struct RowIndices<S: Size> {
+    solutions: Solutions<S>,
+}
+
+impl<S: Size> Iterator for RowIndices<S> {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Vec<usize>> {
+        if !self.solutions.next_stack() {
+            return None;
+        }
+
+        let matrix = &self.solutions.matrix;
+        Some(
+            self.solutions
+                .stack
+                .iter()
+                .map(|&(_, row)| matrix.row_index(row))
+                .collect(),
+        )
+    }
+}
+
 pub struct SolutionRow<S: Size> {
     row: S,
     cursor: S,
@@ -383,6 +977,10 @@ impl<S: Size> SolutionRow<S> {
         }
     }
 
+    pub fn index(&self, solution: &Solution<S>) -> usize {
+        solution.matrix.row_index(self.row)
+    }
+
     pub fn next(&mut self, solution: &Solution<S>) -> Option<S> {
         if self.exhausted {
             return None;
@@ -405,9 +1003,12 @@ impl<S: Size> SolutionRow<S> {
 
 #[cfg(test)]
 mod test {
-    use crate::DLXMatrix;
+    use crate::{DLXMatrix, SearchEvent, TimedOut};
+    use core::time::Duration;
+    use std::time::Instant;
 
     #[test]
+    #[allow(unused_variables)]
     fn test_simple() {
         let mut matrix = DLXMatrix::new(5usize);
         matrix.push_row(&[0]);
@@ -423,6 +1024,276 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_secondary_columns() {
+        let mut matrix = DLXMatrix::with_secondary_columns(3usize, 2);
+        matrix.push_row(&[0, 3]);
+        matrix.push_row(&[1, 3]);
+        matrix.push_row(&[1, 4]);
+        matrix.push_row(&[2, 3]);
+        matrix.push_row(&[2]);
+        assert_eq!(matrix.columns(), 5);
+        assert_eq!(matrix.primary_columns(), 3);
+
+        let mut solution = matrix.solve().unwrap();
+
+        let mut rows = vec![];
+        while let Some(mut row) = solution.next() {
+            let mut row_vec = vec![];
+            while let Some(column) = row.next(&solution) {
+                row_vec.push(column);
+            }
+            row_vec.sort_unstable();
+            rows.push(row_vec);
+        }
+        rows.sort_unstable();
+
+        assert_eq!(rows, vec![vec![0, 3], vec![1, 4], vec![2]]);
+    }
+
+    #[test]
+    fn test_colored_columns() {
+        let build = || {
+            let mut matrix = DLXMatrix::with_secondary_columns(2usize, 2);
+            matrix.push_colored_row(&[(0, None), (2, Some(0))]);
+            matrix.push_colored_row(&[(1, None), (2, Some(0)), (3, Some(5))]);
+            matrix.push_colored_row(&[(1, None), (2, Some(1))]);
+            matrix.push_colored_row(&[(0, None), (2, Some(1)), (3, Some(5))]);
+            matrix.push_colored_row(&[(0, None), (1, None), (2, None)]);
+            matrix.push_row(&[0, 3]);
+            matrix
+        };
+
+        let mut solutions = build()
+            .solutions()
+            .row_indices()
+            .map(|mut rows| {
+                rows.sort_unstable();
+                rows
+            })
+            .collect::<Vec<_>>();
+        solutions.sort_unstable();
+
+        assert_eq!(solutions, vec![vec![0, 1], vec![2, 3], vec![2, 5], vec![4]]);
+        assert_eq!(build().count_solutions(usize::MAX), 4);
+
+        // Both rows use column 3, which the first leaves uncolored
+        let mut matrix = DLXMatrix::with_secondary_columns(2usize, 2);
+        matrix.push_row(&[0, 3]);
+        matrix.push_colored_row(&[(1, None), (3, Some(0))]);
+        assert!(matrix.solve().is_none());
+    }
+
+    #[test]
+    fn test_secondary_columns_only() {
+        let mut matrix = DLXMatrix::with_secondary_columns(0usize, 2);
+        matrix.push_row(&[0, 1]);
+        assert!(matrix.solve().unwrap().next().is_none());
+    }
+
+    #[test]
+    fn test_count_solutions() {
+        let build = || {
+            let mut matrix = DLXMatrix::new(4usize);
+            matrix.push_row(&[0, 1]);
+            matrix.push_row(&[2, 3]);
+            matrix.push_row(&[0]);
+            matrix.push_row(&[1]);
+            matrix.push_row(&[2]);
+            matrix.push_row(&[3]);
+            matrix.push_row(&[1, 2]);
+            matrix
+        };
+
+        assert_eq!(build().count_solutions(usize::MAX), 5);
+        assert_eq!(build().count_solutions(2), 2);
+        assert_eq!(build().count_solutions(0), 0);
+        assert_eq!(DLXMatrix::new(0usize).count_solutions(usize::MAX), 1);
+    }
+
+    #[test]
+    fn test_solutions() {
+        let build = || {
+            let mut matrix = DLXMatrix::new(4usize);
+            matrix.push_row(&[0, 1]);
+            matrix.push_row(&[2, 3]);
+            matrix.push_row(&[0]);
+            matrix.push_row(&[1]);
+            matrix.push_row(&[2]);
+            matrix.push_row(&[3]);
+            matrix.push_row(&[1, 2]);
+            matrix
+        };
+
+        let mut solutions = build()
+            .solutions()
+            .map(|rows| {
+                let mut rows = rows
+                    .into_iter()
+                    .map(|mut row| {
+                        row.sort_unstable();
+                        row
+                    })
+                    .collect::<Vec<_>>();
+                rows.sort_unstable();
+                rows
+            })
+            .collect::<Vec<_>>();
+        solutions.sort_unstable();
+
+        assert_eq!(
+            solutions,
+            vec![
+                vec![vec![0], vec![1], vec![2], vec![3]],
+                vec![vec![0], vec![1], vec![2, 3]],
+                vec![vec![0], vec![1, 2], vec![3]],
+                vec![vec![0, 1], vec![2], vec![3]],
+                vec![vec![0, 1], vec![2, 3]],
+            ]
+        );
+
+        let mut matrix = DLXMatrix::new(2usize);
+        matrix.push_row(&[0]);
+        assert!(matrix.solutions().next().is_none());
+
+        let mut solutions = DLXMatrix::new(0usize).solutions();
+        assert_eq!(solutions.next(), Some(vec![]));
+        assert_eq!(solutions.next(), None);
+        assert_eq!(solutions.next(), None);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let mut matrix = DLXMatrix::new(3usize);
+        matrix.push_row(&[0, 1]);
+        matrix.push_row(&[0, 2]);
+        matrix.push_row(&[1, 2]);
+        matrix.push_row(&[1]);
+
+        let (solution, statistics) = matrix.solve_with_statistics();
+        assert!(solution.is_some());
+        assert_eq!(statistics.nodes, 3);
+        assert_eq!(statistics.backtracks, 1);
+
+        let mut matrix = DLXMatrix::new(2usize);
+        matrix.push_row(&[0]);
+
+        let (solution, statistics) = matrix.solve_with_statistics();
+        assert!(solution.is_none());
+        assert_eq!(statistics.nodes, 0);
+        assert_eq!(statistics.backtracks, 0);
+    }
+
+    #[test]
+    fn test_solve_with_deadline() {
+        // Pairs can never cover an odd number of columns, but it takes a long search to find out
+        let pairs = || {
+            let mut matrix = DLXMatrix::new(21usize);
+            for i in 0..21 {
+                for j in i + 1..21 {
+                    matrix.push_row(&[i, j]);
+                }
+            }
+            matrix
+        };
+
+        let past = Instant::now();
+        assert_eq!(pairs().solve_with_deadline(past).err(), Some(TimedOut));
+
+        // The clock is only read every few thousand nodes, so small searches finish regardless
+        let mut matrix = DLXMatrix::new(2usize);
+        matrix.push_row(&[0, 1]);
+        assert!(matrix.solve_with_deadline(past).unwrap().is_some());
+
+        let future = Instant::now() + Duration::from_secs(60);
+        let mut matrix = DLXMatrix::new(2usize);
+        matrix.push_row(&[0, 1]);
+        assert!(matrix.solve_with_deadline(future).unwrap().is_some());
+
+        let mut matrix = DLXMatrix::new(2usize);
+        matrix.push_row(&[0]);
+        assert!(matrix.solve_with_deadline(future).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_solve_traced() {
+        let mut matrix = DLXMatrix::new(3usize);
+        matrix.push_row(&[0, 1]);
+        matrix.push_row(&[0, 2]);
+        matrix.push_row(&[1, 2]);
+        matrix.push_row(&[1]);
+
+        let mut events = vec![];
+        assert!(matrix.solve_traced(|event| events.push(event)).is_some());
+        assert_eq!(
+            events,
+            vec![
+                SearchEvent::Select { row: 0 },
+                SearchEvent::Backtrack { row: 0 },
+                SearchEvent::Select { row: 1 },
+                SearchEvent::Select { row: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_row_indices() {
+        let mut matrix = DLXMatrix::new(4u8);
+        assert_eq!(matrix.push_row(&[0, 1]), 0);
+        assert_eq!(matrix.push_row(&[2, 3]), 1);
+        assert_eq!(matrix.push_row(&[1, 2]), 2);
+        assert_eq!(matrix.push_row(&[3, 0]), 3);
+        assert_eq!(matrix.push_row(&[3]), 4);
+        assert_eq!(matrix.rows(), 5);
+
+        let mut indices = matrix.solutions().row_indices().collect::<Vec<_>>();
+        for rows in indices.iter_mut() {
+            rows.sort_unstable();
+        }
+        indices.sort_unstable();
+        assert_eq!(indices, vec![vec![0, 1], vec![2, 3]]);
+
+        let mut matrix = DLXMatrix::new(3u8);
+        matrix.push_row(&[2]);
+        matrix.push_row(&[0]);
+        matrix.push_row(&[1, 0]);
+
+        let mut solution = matrix.solve().unwrap();
+        let mut rows = vec![];
+        while let Some(row) = solution.next() {
+            rows.push(row.index(&solution));
+        }
+        rows.sort_unstable();
+        assert_eq!(rows, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_solve_with_filter() {
+        let matrix = || {
+            let mut matrix = DLXMatrix::new(4u8);
+            matrix.push_row(&[0, 1]);
+            matrix.push_row(&[2, 3]);
+            matrix.push_row(&[1, 2]);
+            matrix.push_row(&[3, 0]);
+            matrix
+        };
+
+        let mut solution = matrix().solve_with_filter(|_, row| row != 0).unwrap();
+        let mut rows = vec![];
+        while let Some(row) = solution.next() {
+            rows.push(row.index(&solution));
+        }
+        rows.sort_unstable();
+        assert_eq!(rows, vec![2, 3]);
+
+        // Rows 0 and 1 may not be combined, nor rows 2 and 3, in either order
+        let solution = matrix()
+            .solve_with_filter(|selected, row| !selected.iter().any(|&other| other / 2 == row / 2));
+        assert!(solution.is_none());
+
+        assert!(matrix().solve_with_filter(|_, _| true).is_some());
+    }
+
     #[test]
     fn test_simple2() {
         let mut matrix = DLXMatrix::new(5usize);
@@ -0,0 +1,297 @@
+use crate::DLXMatrix;
+
+use core::fmt;
+use std::collections::HashMap;
+
+// A set of cells, as indices n * y + x, whose values must add up to the target
+type Line = (Vec<usize>, u32);
+
+// The cells a row of the matrix fills, and the values it puts in them
+type Placement = (Vec<usize>, Vec<u32>);
+
+// Normal magic squares, holding each of the numbers 1 to n^2 once with every row, column, and
+// main diagonal adding up to n(n^2 + 1)/2. The number of squares grows very quickly with the
+// order, so this is only practical up to order 5 or so
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MagicSquares {
+    order: usize,
+    most_perfect: bool,
+}
+
+impl MagicSquares {
+    pub fn new(order: usize) -> Self {
+        assert!(order > 0, "Order must be at least 1");
+        Self {
+            order,
+            most_perfect: false,
+        }
+    }
+
+    // Most-perfect squares, which have a doubly even order. Every 2x2 block, wrapping around the
+    // edges, adds up to 2(n^2 + 1), and every pair of cells half the order apart along a diagonal
+    // adds up to n^2 + 1
+    pub fn most_perfect(order: usize) -> Self {
+        assert!(
+            order.is_multiple_of(4),
+            "Most-perfect squares have an order divisible by 4 (got {})",
+            order
+        );
+        Self {
+            most_perfect: true,
+            ..Self::new(order)
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    pub fn is_most_perfect(&self) -> bool {
+        self.most_perfect
+    }
+
+    pub fn magic_sum(&self) -> u32 {
+        let n = self.order as u32;
+        n * (n * n + 1) / 2
+    }
+
+    pub fn find(&self) -> Option<MagicSquare> {
+        self.squares().next()
+    }
+
+    // Rotations and reflections of a square all count separately
+    pub fn count(&self, limit: usize) -> usize {
+        self.encode().0.count_solutions(limit)
+    }
+
+    pub fn squares(&self) -> impl Iterator<Item = MagicSquare> {
+        let (matrix, placements) = self.encode();
+        let order = self.order;
+
+        matrix.solutions().row_indices().map(move |rows| {
+            let mut cells = vec![0; order * order];
+            for row in rows {
+                let (ref line, ref values) = placements[row];
+                for (&cell, &value) in line.iter().zip(values) {
+                    cells[cell] = value;
+                }
+            }
+            MagicSquare { order, cells }
+        })
+    }
+
+    // The lines, along with how many of them at the start partition the square. For a plain magic
+    // square those are its rows, and for a most-perfect square the diagonal pairs
+    fn lines(&self) -> (Vec<Line>, usize) {
+        let n = self.order;
+        let sum = self.magic_sum();
+        let mut lines = vec![];
+        let mut partition = n;
+
+        if self.most_perfect {
+            let complement = (n * n + 1) as u32;
+
+            // Moving half the order along either diagonal reaches the same cell, so these pairs
+            // cover both directions
+            let half = n / 2;
+            for y in 0..half {
+                for x in 0..n {
+                    let pair = vec![n * y + x, n * (y + half) + (x + half) % n];
+                    lines.push((pair, complement));
+                }
+            }
+            partition = lines.len();
+
+            for y in 0..n {
+                for x in 0..n {
+                    let (right, down) = ((x + 1) % n, (y + 1) % n);
+                    let block = vec![n * y + x, n * y + right, n * down + x, n * down + right];
+                    lines.push((block, 2 * complement));
+                }
+            }
+        }
+
+        for y in 0..n {
+            lines.push(((0..n).map(|x| n * y + x).collect(), sum));
+        }
+        for x in 0..n {
+            lines.push(((0..n).map(|y| n * y + x).collect(), sum));
+        }
+        lines.push(((0..n).map(|i| n * i + i).collect(), sum));
+        lines.push(((0..n).map(|i| n * i + n - 1 - i).collect(), sum));
+
+        (lines, partition)
+    }
+
+    // One primary column per line and per value, then a secondary column per cell colored by the
+    // value it holds. A row fills a whole line at once, and only the lines partitioning the square
+    // cover values, so each value is used exactly once while the other lines have to agree
+    fn encode(&self) -> (DLXMatrix<u32>, Vec<Placement>) {
+        let n = self.order;
+        let (lines, partition) = self.lines();
+        let value_base = lines.len();
+        let cell_base = value_base + n * n;
+
+        let mut matrix =
+            DLXMatrix::with_secondary_columns((lines.len() + n * n) as u32, (n * n) as u32);
+        let mut placements = vec![];
+        let mut fillings: HashMap<(usize, u32), Vec<Vec<u32>>> = HashMap::new();
+        let mut row = vec![];
+
+        for (l, (cells, target)) in lines.iter().enumerate() {
+            let fillings = fillings
+                .entry((cells.len(), *target))
+                .or_insert_with(|| self.fillings(cells.len(), *target));
+
+            for values in fillings.iter() {
+                row.clear();
+                row.push((l as u32, None));
+                if l < partition {
+                    row.extend(values.iter().map(|&v| ((value_base as u32) + v - 1, None)));
+                }
+                row.extend(
+                    cells
+                        .iter()
+                        .zip(values)
+                        .map(|(&cell, &v)| ((cell_base + cell) as u32, Some(v))),
+                );
+
+                matrix.push_colored_row(&row);
+                placements.push((cells.clone(), values.clone()));
+            }
+        }
+
+        (matrix, placements)
+    }
+
+    // Every sequence of distinct values from 1 to n^2 with the given length and sum
+    fn fillings(&self, length: usize, target: u32) -> Vec<Vec<u32>> {
+        fn extend(
+            max: u32,
+            length: usize,
+            remaining: u32,
+            values: &mut Vec<u32>,
+            fillings: &mut Vec<Vec<u32>>,
+        ) {
+            if values.len() == length {
+                if remaining == 0 {
+                    fillings.push(values.clone());
+                }
+                return;
+            }
+
+            for value in 1..=max.min(remaining) {
+                if !values.contains(&value) {
+                    values.push(value);
+                    extend(max, length, remaining - value, values, fillings);
+                    values.pop();
+                }
+            }
+        }
+
+        let mut fillings = vec![];
+        let max = (self.order * self.order) as u32;
+        extend(max, length, target, &mut vec![], &mut fillings);
+        fillings
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct MagicSquare {
+    order: usize,
+    cells: Vec<u32>,
+}
+
+impl MagicSquare {
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u32 {
+        assert!(x < self.order && y < self.order);
+        self.cells[self.order * y + x]
+    }
+}
+
+impl fmt::Display for MagicSquare {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let width = (self.order * self.order).to_string().len();
+
+        for y in 0..self.order {
+            for x in 0..self.order {
+                if x > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{:>width$}", self.get(x, y), width = width)?;
+            }
+
+            if y + 1 < self.order {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::magic::{MagicSquare, MagicSquares};
+
+    fn validate(problem: MagicSquares, square: &MagicSquare) {
+        let n = problem.order();
+        let sum = problem.magic_sum();
+
+        let mut values: Vec<u32> = (0..n * n).map(|i| square.get(i % n, i / n)).collect();
+        values.sort_unstable();
+        assert_eq!(values, (1..=(n * n) as u32).collect::<Vec<_>>());
+
+        for i in 0..n {
+            assert_eq!((0..n).map(|x| square.get(x, i)).sum::<u32>(), sum);
+            assert_eq!((0..n).map(|y| square.get(i, y)).sum::<u32>(), sum);
+        }
+        assert_eq!((0..n).map(|i| square.get(i, i)).sum::<u32>(), sum);
+        assert_eq!((0..n).map(|i| square.get(n - 1 - i, i)).sum::<u32>(), sum);
+    }
+
+    #[test]
+    fn test_magic_squares() {
+        assert_eq!(MagicSquares::new(1).count(usize::MAX), 1);
+        assert_eq!(MagicSquares::new(2).count(usize::MAX), 0);
+
+        // The Lo Shu square in each of its eight orientations
+        let problem = MagicSquares::new(3);
+        let squares: Vec<MagicSquare> = problem.squares().collect();
+        assert_eq!(squares.len(), 8);
+        for square in &squares {
+            validate(problem, square);
+            assert_eq!(square.get(1, 1), 5);
+        }
+
+        let problem = MagicSquares::new(4);
+        validate(problem, &problem.find().unwrap());
+    }
+
+    #[test]
+    fn test_most_perfect() {
+        // There are 384 of order 4 counting rotations and reflections, though finding them all
+        // takes a while
+        let problem = MagicSquares::most_perfect(4);
+        for square in problem.squares().take(8) {
+            validate(problem, &square);
+            for y in 0..4 {
+                for x in 0..4 {
+                    let block = square.get(x, y)
+                        + square.get((x + 1) % 4, y)
+                        + square.get(x, (y + 1) % 4)
+                        + square.get((x + 1) % 4, (y + 1) % 4);
+                    assert_eq!(block, 34);
+                    assert_eq!(square.get(x, y) + square.get((x + 2) % 4, (y + 2) % 4), 17);
+                }
+            }
+        }
+
+        let square = MagicSquares::new(3).find().unwrap();
+        assert_eq!(square.to_string().lines().count(), 3);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::DLXMatrix;
+
+use core::fmt;
+use std::collections::{HashMap, HashSet};
+
+// A builder for partitioning a set of named elements with labelled subsets, for when a problem
+// is easier to state in terms of its own names than in column indices:
+//
+//     let universe = Universe::new()
+//         .elements(["a", "b", "c"])
+//         .subset(["a", "b"], "ab")?
+//         .subset(["c"], "c")?;
+//
+// Elements have to be added before the subsets which name them. Every element must be covered by exactly one chosen subset, and every optional element by at
+// most one. Solutions are given as the labels of the chosen subsets, in the order they were added
+#[derive(Clone, Debug)]
+pub struct Universe<L> {
+    elements: Vec<String>,
+    optional: Vec<String>,
+    subsets: Vec<(Vec<String>, L)>,
+    names: HashSet<String>,
+}
+
+impl<L> Universe<L> {
+    pub fn new() -> Self {
+        Self {
+            elements: vec![],
+            optional: vec![],
+            subsets: vec![],
+            names: HashSet::new(),
+        }
+    }
+
+    pub fn element(mut self, name: &str) -> Self {
+        self.elements.push(name.to_string());
+        self.names.insert(name.to_string());
+        self
+    }
+
+    pub fn elements<'a>(self, names: impl IntoIterator<Item = &'a str>) -> Self {
+        names.into_iter().fold(self, Self::element)
+    }
+
+    // An element which may be left out of every chosen subset
+    pub fn optional(mut self, name: &str) -> Self {
+        self.optional.push(name.to_string());
+        self.names.insert(name.to_string());
+        self
+    }
+
+    pub fn subset<'a>(
+        mut self,
+        names: impl IntoIterator<Item = &'a str>,
+        label: L,
+    ) -> Result<Self, SubsetError> {
+        let mut subset: Vec<String> = vec![];
+
+        for name in names {
+            if !self.names.contains(name) {
+                return Err(SubsetError::UnknownElement {
+                    name: name.to_string(),
+                });
+            }
+
+            if subset.iter().any(|other| other == name) {
+                return Err(SubsetError::DuplicateElement {
+                    name: name.to_string(),
+                });
+            }
+
+            subset.push(name.to_string());
+        }
+
+        if subset.is_empty() {
+            return Err(SubsetError::Empty);
+        }
+
+        self.subsets.push((subset, label));
+        Ok(self)
+    }
+
+    pub fn labels(&self) -> impl Iterator<Item = &L> {
+        self.subsets.iter().map(|(_, label)| label)
+    }
+
+    pub fn solve(&self) -> Option<Vec<&L>> {
+        self.solutions().next()
+    }
+
+    pub fn solutions(&self) -> impl Iterator<Item = Vec<&L>> + '_ {
+        self.matrix().solutions().row_indices().map(|mut rows| {
+            rows.sort_unstable();
+            rows.into_iter().map(|row| &self.subsets[row].1).collect()
+        })
+    }
+
+    pub fn count(&self, limit: usize) -> usize {
+        self.matrix().count_solutions(limit)
+    }
+
+    // Elements become primary columns and optional elements secondary ones, in the order they
+    // were added, with a row per subset. Row indices are subset indices
+    pub fn matrix(&self) -> DLXMatrix<u32> {
+        let mut columns = HashMap::new();
+        for (i, name) in self.elements.iter().chain(&self.optional).enumerate() {
+            assert!(
+                columns.insert(name.as_str(), i as u32).is_none(),
+                "Element {:?} was added more than once",
+                name
+            );
+        }
+
+        let mut matrix = DLXMatrix::with_secondary_columns(
+            self.elements.len() as u32,
+            self.optional.len() as u32,
+        );
+
+        for (names, _) in &self.subsets {
+            let row: Vec<u32> = names.iter().map(|name| columns[name.as_str()]).collect();
+            matrix.push_row(&row);
+        }
+
+        matrix
+    }
+}
+
+impl<L> Default for Universe<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SubsetError {
+    Empty,
+    UnknownElement { name: String },
+    DuplicateElement { name: String },
+}
+
+impl fmt::Display for SubsetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            SubsetError::Empty => {
+                write!(f, "Subset is empty")?;
+            }
+            SubsetError::UnknownElement { name } => {
+                write!(f, "Subset names unknown element {:?}", name)?;
+            }
+            SubsetError::DuplicateElement { name } => {
+                write!(f, "Subset names element {:?} more than once", name)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::partition::{SubsetError, Universe};
+
+    #[test]
+    fn test_universe() {
+        let universe = Universe::new()
+            .elements(["a", "b", "c", "d"])
+            .subset(["a", "b"], "ab")
+            .unwrap()
+            .subset(["c", "d"], "cd")
+            .unwrap()
+            .subset(["a"], "a")
+            .unwrap()
+            .subset(["b", "c"], "bc")
+            .unwrap()
+            .subset(["d"], "d")
+            .unwrap();
+
+        assert_eq!(universe.count(usize::MAX), 2);
+
+        let mut solutions: Vec<Vec<&&str>> = universe.solutions().collect();
+        solutions.sort_unstable();
+        assert_eq!(solutions, vec![vec![&"a", &"bc", &"d"], vec![&"ab", &"cd"]]);
+        assert_eq!(universe.labels().count(), 5);
+    }
+
+    #[test]
+    fn test_optional() {
+        // Everyone gets a seat, and at most one of them the window seat
+        let universe = Universe::new()
+            .elements(["alice", "bob", "carol"])
+            .optional("window")
+            .subset(["alice", "window"], 1)
+            .unwrap()
+            .subset(["bob", "window"], 2)
+            .unwrap()
+            .subset(["bob"], 3)
+            .unwrap()
+            .subset(["carol", "window"], 4)
+            .unwrap()
+            .subset(["carol"], 5)
+            .unwrap();
+
+        let mut solutions: Vec<Vec<&i32>> = universe.solutions().collect();
+        solutions.sort_unstable();
+        assert_eq!(solutions, vec![vec![&1, &3, &5]]);
+
+        let universe: Universe<()> = Universe::new().element("a");
+        assert!(universe.solve().is_none());
+        assert_eq!(Universe::<()>::new().solve(), Some(vec![]));
+    }
+
+    #[test]
+    fn test_subset_errors() {
+        let universe = Universe::new().elements(["a", "b"]).optional("c");
+
+        assert_eq!(
+            universe.clone().subset(["a", "d"], ()).unwrap_err(),
+            SubsetError::UnknownElement {
+                name: String::from("d")
+            }
+        );
+        assert_eq!(
+            universe.clone().subset(["c", "b", "c"], ()).unwrap_err(),
+            SubsetError::DuplicateElement {
+                name: String::from("c")
+            }
+        );
+        assert_eq!(
+            universe.clone().subset([], ()).unwrap_err(),
+            SubsetError::Empty
+        );
+
+        // Elements added afterwards aren't known yet
+        assert!(Universe::new().subset(["a"], ()).is_err());
+        assert!(universe.subset(["a", "c"], ()).is_ok());
+    }
+}
@@ -0,0 +1,388 @@
+use crate::polyomino::normalize;
+use crate::DLXMatrix;
+
+use core::fmt;
+
+type Cell = (usize, usize, usize);
+
+// A piece and the indices of the region cells it covers
+type Placement = (usize, Vec<usize>);
+
+// A three-dimensional piece as the cells it covers, shifted so that it touches all three axes
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Piece {
+    name: char,
+    cells: Vec<Cell>,
+}
+
+impl Piece {
+    pub fn new(name: char, cells: &[Cell]) -> Self {
+        assert!(!cells.is_empty(), "Pieces must have at least one cell");
+        Self {
+            name,
+            cells: normalize(
+                cells
+                    .iter()
+                    .map(|&(x, y, z)| [x as isize, y as isize, z as isize]),
+            )
+            .into_iter()
+            .map(|[x, y, z]| (x, y, z))
+            .collect(),
+        }
+    }
+
+    pub fn name(&self) -> char {
+        self.name
+    }
+
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    // The distinct orientations of the piece: the 24 rotations, and with reflections allowed, the
+    // 24 rotations of its mirror image as well. Symmetric pieces have fewer
+    pub fn orientations(&self, reflections: bool) -> Vec<Vec<Cell>> {
+        const PERMUTATIONS: [([usize; 3], bool); 6] = [
+            ([0, 1, 2], true),
+            ([1, 2, 0], true),
+            ([2, 0, 1], true),
+            ([0, 2, 1], false),
+            ([2, 1, 0], false),
+            ([1, 0, 2], false),
+        ];
+
+        let mut orientations: Vec<Vec<Cell>> = vec![];
+
+        // Each orientation permutes the axes and flips some of them. It is a rotation when an odd
+        // permutation comes with an odd number of flips, or an even one with an even number
+        for (axes, even) in PERMUTATIONS {
+            for flips in 0..8u32 {
+                if !reflections && even != (flips.count_ones() % 2 == 0) {
+                    continue;
+                }
+
+                let cells = normalize(self.cells.iter().map(|&(x, y, z)| {
+                    let cell = [x as isize, y as isize, z as isize];
+                    let mut moved = [0; 3];
+                    for (i, &axis) in axes.iter().enumerate() {
+                        moved[i] = if flips & (1 << i) == 0 {
+                            cell[axis]
+                        } else {
+                            -cell[axis]
+                        };
+                    }
+                    moved
+                }))
+                .into_iter()
+                .map(|[x, y, z]| (x, y, z))
+                .collect::<Vec<Cell>>();
+
+                if !orientations.contains(&cells) {
+                    orientations.push(cells);
+                }
+            }
+        }
+
+        orientations
+    }
+}
+
+// The seven pieces of Piet Hein's Soma cube, which together fill a 3x3x3 cube. A and B are mirror
+// images of each other, so the puzzle is meant to be solved without reflections
+pub fn soma() -> Vec<Piece> {
+    [
+        ('V', vec![(0, 0, 0), (1, 0, 0), (0, 1, 0)]),
+        ('L', vec![(0, 0, 0), (1, 0, 0), (2, 0, 0), (0, 1, 0)]),
+        ('T', vec![(0, 0, 0), (1, 0, 0), (2, 0, 0), (1, 1, 0)]),
+        ('Z', vec![(0, 0, 0), (1, 0, 0), (1, 1, 0), (2, 1, 0)]),
+        ('A', vec![(0, 0, 0), (1, 0, 0), (0, 1, 0), (0, 1, 1)]),
+        ('B', vec![(0, 0, 0), (1, 0, 0), (0, 1, 0), (1, 0, 1)]),
+        ('P', vec![(0, 0, 0), (1, 0, 0), (0, 1, 0), (0, 0, 1)]),
+    ]
+    .into_iter()
+    .map(|(name, cells)| Piece::new(name, &cells))
+    .collect()
+}
+
+// The space to be filled, held layer by layer from the bottom, and row by row within a layer
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Region {
+    width: usize,
+    depth: usize,
+    height: usize,
+    cells: Vec<bool>,
+}
+
+impl Region {
+    pub fn cuboid(width: usize, depth: usize, height: usize) -> Self {
+        Self {
+            width,
+            depth,
+            height,
+            cells: vec![true; width * depth * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: usize, y: usize, z: usize) -> bool {
+        x < self.width && y < self.depth && z < self.height && self.cells[self.index(x, y, z)]
+    }
+
+    pub fn remove(&mut self, x: usize, y: usize, z: usize) {
+        assert!(x < self.width && y < self.depth && z < self.height);
+        let i = self.index(x, y, z);
+        self.cells[i] = false;
+    }
+
+    pub fn volume(&self) -> usize {
+        self.cells.iter().filter(|&&cell| cell).count()
+    }
+
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        self.width * (self.depth * z + y) + x
+    }
+}
+
+// Filling every cell of a region with each of the pieces exactly once
+#[derive(Clone, Debug)]
+pub struct Packing {
+    region: Region,
+    pieces: Vec<Piece>,
+    reflections: bool,
+}
+
+impl Packing {
+    pub fn new(region: Region, pieces: Vec<Piece>) -> Self {
+        Self {
+            region,
+            pieces,
+            reflections: false,
+        }
+    }
+
+    // Whether pieces may be replaced by their mirror images, which can't be done with real pieces
+    // in three dimensions. They may not by default
+    pub fn reflections(mut self, reflections: bool) -> Self {
+        self.reflections = reflections;
+        self
+    }
+
+    pub fn solve(&self) -> Option<Filling> {
+        self.iter_solutions().next()
+    }
+
+    pub fn count(&self, limit: usize) -> usize {
+        match self.encode() {
+            Some((matrix, _)) => matrix.count_solutions(limit),
+            None => 0,
+        }
+    }
+
+    pub fn iter_solutions(&self) -> impl Iterator<Item = Filling> + '_ {
+        let encoded = self.encode();
+        let region = &self.region;
+
+        encoded.into_iter().flat_map(move |(matrix, placements)| {
+            matrix.solutions().row_indices().map(move |rows| {
+                let mut cells = vec![None; region.cells.len()];
+                for row in rows {
+                    let (piece, covered) = &placements[row];
+                    for &i in covered {
+                        cells[i] = Some(*piece);
+                    }
+                }
+
+                Filling {
+                    width: region.width,
+                    depth: region.depth,
+                    names: self.pieces.iter().map(|piece| piece.name).collect(),
+                    cells,
+                }
+            })
+        })
+    }
+
+    // As for polyominoes: one column per piece, then one per cell of the region, with one row for
+    // each way of placing a piece. Returns None if the volumes don't match
+    fn encode(&self) -> Option<(DLXMatrix<u32>, Vec<Placement>)> {
+        let region = &self.region;
+        let volume: usize = self.pieces.iter().map(|piece| piece.cells.len()).sum();
+        if volume != region.volume() {
+            return None;
+        }
+
+        let mut columns = vec![None; region.cells.len()];
+        let mut next = self.pieces.len();
+        for (i, _) in region.cells.iter().enumerate().filter(|(_, &cell)| cell) {
+            columns[i] = Some(next as u32);
+            next += 1;
+        }
+
+        let mut matrix = DLXMatrix::new(next as u32);
+        let mut placements = vec![];
+        let mut row = vec![];
+
+        for (piece, shape) in self.pieces.iter().enumerate() {
+            for orientation in shape.orientations(self.reflections) {
+                for dz in 0..region.height {
+                    for dy in 0..region.depth {
+                        for dx in 0..region.width {
+                            if !orientation
+                                .iter()
+                                .all(|&(x, y, z)| region.contains(x + dx, y + dy, z + dz))
+                            {
+                                continue;
+                            }
+
+                            let covered: Vec<usize> = orientation
+                                .iter()
+                                .map(|&(x, y, z)| region.index(x + dx, y + dy, z + dz))
+                                .collect();
+
+                            row.clear();
+                            row.push(piece as u32);
+                            row.extend(covered.iter().filter_map(|&i| columns[i]));
+                            matrix.push_row(&row);
+                            placements.push((piece, covered));
+                        }
+                    }
+                }
+            }
+        }
+
+        Some((matrix, placements))
+    }
+}
+
+// The index of the piece filling each cell, or None for a hole
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Filling {
+    width: usize,
+    depth: usize,
+    names: Vec<char>,
+    cells: Vec<Option<usize>>,
+}
+
+impl Filling {
+    pub fn get(&self, x: usize, y: usize, z: usize) -> Option<usize> {
+        assert!(x < self.width && y < self.depth);
+        self.cells[self.width * (self.depth * z + y) + x]
+    }
+}
+
+// Each layer as a grid of piece names, from the bottom up, separated by blank lines
+impl fmt::Display for Filling {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let layer = (self.width * self.depth).max(1);
+
+        for (z, cells) in self.cells.chunks(layer).enumerate() {
+            if z > 0 {
+                writeln!(f)?;
+                writeln!(f)?;
+            }
+
+            for (y, row) in cells.chunks(self.width.max(1)).enumerate() {
+                if y > 0 {
+                    writeln!(f)?;
+                }
+
+                for cell in row {
+                    match *cell {
+                        Some(piece) => write!(f, "{}", self.names[piece])?,
+                        None => write!(f, ".")?,
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::polycube::{soma, Filling, Packing, Piece, Region};
+
+    fn validate(region: &Region, pieces: &[Piece], filling: &Filling, reflections: bool) {
+        let cells: Vec<(usize, usize, usize)> = (0..region.height())
+            .flat_map(|z| (0..region.depth()).map(move |y| (y, z)))
+            .flat_map(|(y, z)| (0..region.width()).map(move |x| (x, y, z)))
+            .collect();
+
+        for (i, piece) in pieces.iter().enumerate() {
+            let filled: Vec<_> = cells
+                .iter()
+                .copied()
+                .filter(|&(x, y, z)| filling.get(x, y, z) == Some(i))
+                .collect();
+
+            let placed = Piece::new(piece.name(), &filled);
+            assert!(piece
+                .orientations(reflections)
+                .contains(&placed.cells().to_vec()));
+        }
+
+        for (x, y, z) in cells {
+            assert_eq!(region.contains(x, y, z), filling.get(x, y, z).is_some());
+        }
+    }
+
+    #[test]
+    fn test_orientations() {
+        let counts: Vec<usize> = soma()
+            .iter()
+            .map(|piece| piece.orientations(false).len())
+            .collect();
+        assert_eq!(counts, vec![12, 24, 12, 12, 12, 12, 8]);
+
+        // A and B are each other's reflections
+        let pieces = soma();
+        let (a, b) = (&pieces[4], &pieces[5]);
+        assert!(!a.orientations(false).contains(&b.cells().to_vec()));
+        assert!(a.orientations(true).contains(&b.cells().to_vec()));
+    }
+
+    #[test]
+    fn test_soma_cube() {
+        let region = Region::cuboid(3, 3, 3);
+        let packing = Packing::new(region.clone(), soma());
+
+        let filling = packing.solve().unwrap();
+        validate(&region, &soma(), &filling, false);
+        assert_eq!(filling.to_string().lines().count(), 11);
+
+        // 240 essentially different solutions, each in all 48 symmetries of the cube
+        assert_eq!(packing.count(usize::MAX), 11520);
+    }
+
+    #[test]
+    fn test_holes() {
+        // Two L trominoes fill a 2x2x2 cube missing two opposite corners in several ways
+        let mut region = Region::cuboid(2, 2, 2);
+        region.remove(0, 0, 0);
+        region.remove(1, 1, 1);
+        assert_eq!(region.volume(), 6);
+
+        let v = Piece::new('V', &[(0, 0, 0), (1, 0, 0), (0, 1, 0)]);
+        let pieces = vec![v.clone(), Piece::new('W', v.cells())];
+        let packing = Packing::new(region.clone(), pieces.clone());
+
+        let filling = packing.solve().unwrap();
+        validate(&region, &pieces, &filling, false);
+        assert_eq!(filling.get(0, 0, 0), None);
+
+        let packing = Packing::new(Region::cuboid(2, 2, 2), pieces);
+        assert!(packing.solve().is_none());
+    }
+}
@@ -0,0 +1,430 @@
+use crate::DLXMatrix;
+
+use core::fmt;
+
+type Cell = (usize, usize);
+
+// A piece and the indices of the board cells it covers
+type Placement = (usize, Vec<usize>);
+
+// A piece as the cells it covers, shifted so that it touches both axes
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Piece {
+    name: char,
+    cells: Vec<Cell>,
+}
+
+impl Piece {
+    pub fn new(name: char, cells: &[Cell]) -> Self {
+        assert!(!cells.is_empty(), "Pieces must have at least one cell");
+        Self {
+            name,
+            cells: normalize(cells.iter().map(|&(x, y)| [x as isize, y as isize]))
+                .into_iter()
+                .map(|[x, y]| (x, y))
+                .collect(),
+        }
+    }
+
+    // A picture of the piece, with '#' for each of its cells
+    pub fn parse(name: char, picture: &str) -> Self {
+        Self::new(name, &picture_cells(picture, |ch| ch == '#'))
+    }
+
+    pub fn name(&self) -> char {
+        self.name
+    }
+
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    // The distinct rotations of the piece, followed by those of its reflection if reflections are
+    // allowed. Symmetric pieces have fewer than eight
+    pub fn orientations(&self, reflections: bool) -> Vec<Vec<Cell>> {
+        let mut orientations: Vec<Vec<Cell>> = vec![];
+        let transforms = if reflections { 8 } else { 4 };
+
+        for transform in 0..transforms {
+            let cells: Vec<Cell> = normalize(self.cells.iter().map(|&(x, y)| {
+                let (x, y) = (x as isize, y as isize);
+                let (x, y) = if transform >= 4 { (-x, y) } else { (x, y) };
+                match transform % 4 {
+                    0 => [x, y],
+                    1 => [-y, x],
+                    2 => [-x, -y],
+                    _ => [y, -x],
+                }
+            }))
+            .into_iter()
+            .map(|[x, y]| (x, y))
+            .collect();
+
+            if !orientations.contains(&cells) {
+                orientations.push(cells);
+            }
+        }
+
+        orientations
+    }
+}
+
+// The twelve pentominoes, named by Golomb's letters
+pub fn pentominoes() -> Vec<Piece> {
+    [
+        ('F', ".##\n##.\n.#."),
+        ('I', "#####"),
+        ('L', "####\n#..."),
+        ('N', "##..\n.###"),
+        ('P', "##\n##\n#."),
+        ('T', "###\n.#.\n.#."),
+        ('U', "#.#\n###"),
+        ('V', "#..\n#..\n###"),
+        ('W', "#..\n##.\n.##"),
+        ('X', ".#.\n###\n.#."),
+        ('Y', "####\n.#.."),
+        ('Z', "##.\n.#.\n.##"),
+    ]
+    .into_iter()
+    .map(|(name, picture)| Piece::parse(name, picture))
+    .collect()
+}
+
+// Sorted with the last axis most significant, and shifted so that the smallest coordinates are 0.
+// Polycubes use this too, with a third axis
+pub(crate) fn normalize<const N: usize>(
+    cells: impl Iterator<Item = [isize; N]>,
+) -> Vec<[usize; N]> {
+    let cells: Vec<[isize; N]> = cells.collect();
+    let mut low = [0; N];
+    for (axis, low) in low.iter_mut().enumerate() {
+        *low = cells.iter().map(|cell| cell[axis]).min().unwrap_or(0);
+    }
+
+    let mut cells: Vec<[usize; N]> = cells
+        .into_iter()
+        .map(|cell| core::array::from_fn(|axis| (cell[axis] - low[axis]) as usize))
+        .collect();
+    cells.sort_unstable_by(|a, b| a.iter().rev().cmp(b.iter().rev()));
+    cells.dedup();
+    cells
+}
+
+fn picture_cells(picture: &str, is_cell: impl Fn(char) -> bool) -> Vec<Cell> {
+    picture
+        .lines()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|&(_, ch)| is_cell(ch))
+                .map(move |(x, _)| (x, y))
+        })
+        .collect()
+}
+
+// The region to be packed, which need not be a rectangle or even connected
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Board {
+    width: usize,
+    height: usize,
+    cells: Vec<bool>,
+}
+
+impl Board {
+    pub fn rectangle(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![true; width * height],
+        }
+    }
+
+    // The Aztec diamond of order n, whose rows are 2, 4, ..., 2n, 2n, ..., 4, 2 cells long
+    pub fn aztec_diamond(n: usize) -> Self {
+        let mut board = Self::rectangle(2 * n, 2 * n);
+
+        for y in 0..2 * n {
+            for x in 0..2 * n {
+                // Measured from the centre of the board, in half cells
+                let dx = (2 * x + 1).abs_diff(2 * n);
+                let dy = (2 * y + 1).abs_diff(2 * n);
+                if dx + dy > 2 * n {
+                    board.remove(x, y);
+                }
+            }
+        }
+
+        board
+    }
+
+    // The bottom left corner of a rectangle, made of its first few columns and last few rows
+    pub fn l_shape(width: usize, height: usize, thickness: usize) -> Self {
+        assert!(thickness <= width.min(height));
+        let mut board = Self::rectangle(width, height);
+
+        for y in 0..height - thickness {
+            for x in thickness..width {
+                board.remove(x, y);
+            }
+        }
+
+        board
+    }
+
+    // A picture of the board, with '.' or a space for each hole and anything else for a cell
+    pub fn parse(picture: &str) -> Self {
+        let cells = picture_cells(picture, |ch| ch != '.' && !ch.is_whitespace());
+        let width = picture.lines().map(|line| line.chars().count()).max();
+        let mut board = Self::rectangle(width.unwrap_or(0), picture.lines().count());
+
+        board.cells.fill(false);
+        for (x, y) in cells {
+            board.cells[board.width * y + x] = true;
+        }
+
+        board
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.cells[self.width * y + x]
+    }
+
+    pub fn remove(&mut self, x: usize, y: usize) {
+        assert!(x < self.width && y < self.height);
+        self.cells[self.width * y + x] = false;
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.iter().filter(|&&cell| cell).count()
+    }
+}
+
+// Covering every cell of a board with each of the pieces exactly once
+#[derive(Clone, Debug)]
+pub struct Packing {
+    board: Board,
+    pieces: Vec<Piece>,
+    reflections: bool,
+}
+
+impl Packing {
+    pub fn new(board: Board, pieces: Vec<Piece>) -> Self {
+        Self {
+            board,
+            pieces,
+            reflections: true,
+        }
+    }
+
+    // Whether pieces may be flipped over, as well as rotated. They may by default
+    pub fn reflections(mut self, reflections: bool) -> Self {
+        self.reflections = reflections;
+        self
+    }
+
+    pub fn solve(&self) -> Option<Tiling> {
+        self.iter_solutions().next()
+    }
+
+    pub fn count(&self, limit: usize) -> usize {
+        match self.encode() {
+            Some((matrix, _)) => matrix.count_solutions(limit),
+            None => 0,
+        }
+    }
+
+    pub fn iter_solutions(&self) -> impl Iterator<Item = Tiling> + '_ {
+        let encoded = self.encode();
+        let (width, height) = (self.board.width, self.board.height);
+
+        encoded.into_iter().flat_map(move |(matrix, placements)| {
+            matrix.solutions().row_indices().map(move |rows| {
+                let mut cells = vec![None; width * height];
+                for row in rows {
+                    let (piece, covered) = &placements[row];
+                    for &i in covered {
+                        cells[i] = Some(*piece);
+                    }
+                }
+
+                Tiling {
+                    width,
+                    names: self.pieces.iter().map(|piece| piece.name).collect(),
+                    cells,
+                }
+            })
+        })
+    }
+
+    // One column per piece, then one per cell of the board, with one row for each way of placing
+    // a piece. Returns None if the areas don't match, in which case there is no packing
+    fn encode(&self) -> Option<(DLXMatrix<u32>, Vec<Placement>)> {
+        let area: usize = self.pieces.iter().map(|piece| piece.cells.len()).sum();
+        if area != self.board.area() {
+            return None;
+        }
+
+        let mut columns = vec![None; self.board.cells.len()];
+        let mut next = self.pieces.len();
+        for (i, _) in self
+            .board
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &cell)| cell)
+        {
+            columns[i] = Some(next as u32);
+            next += 1;
+        }
+
+        let mut matrix = DLXMatrix::new(next as u32);
+        let mut placements = vec![];
+        let mut row = vec![];
+
+        for (piece, shape) in self.pieces.iter().enumerate() {
+            for orientation in shape.orientations(self.reflections) {
+                for dy in 0..self.board.height {
+                    for dx in 0..self.board.width {
+                        if !orientation
+                            .iter()
+                            .all(|&(x, y)| self.board.contains(x + dx, y + dy))
+                        {
+                            continue;
+                        }
+
+                        let covered: Vec<usize> = orientation
+                            .iter()
+                            .map(|&(x, y)| self.board.width * (y + dy) + x + dx)
+                            .collect();
+
+                        row.clear();
+                        row.push(piece as u32);
+                        row.extend(covered.iter().filter_map(|&i| columns[i]));
+                        matrix.push_row(&row);
+                        placements.push((piece, covered));
+                    }
+                }
+            }
+        }
+
+        Some((matrix, placements))
+    }
+}
+
+// The index of the piece covering each cell, or None for a hole
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Tiling {
+    width: usize,
+    names: Vec<char>,
+    cells: Vec<Option<usize>>,
+}
+
+impl Tiling {
+    pub fn get(&self, x: usize, y: usize) -> Option<usize> {
+        assert!(x < self.width);
+        self.cells[self.width * y + x]
+    }
+}
+
+impl fmt::Display for Tiling {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (y, row) in self.cells.chunks(self.width.max(1)).enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+
+            for cell in row {
+                match *cell {
+                    Some(piece) => write!(f, "{}", self.names[piece])?,
+                    None => write!(f, ".")?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::polyomino::{pentominoes, Board, Packing, Piece, Tiling};
+
+    // Every cell of the board is covered, by a piece of the right shape
+    fn validate(board: &Board, pieces: &[Piece], tiling: &Tiling) {
+        for (i, piece) in pieces.iter().enumerate() {
+            let cells: Vec<(usize, usize)> = (0..board.height())
+                .flat_map(|y| (0..board.width()).map(move |x| (x, y)))
+                .filter(|&(x, y)| tiling.get(x, y) == Some(i))
+                .collect();
+
+            let placed = Piece::new(piece.name(), &cells);
+            assert!(piece.orientations(true).contains(&placed.cells().to_vec()));
+        }
+
+        for y in 0..board.height() {
+            for x in 0..board.width() {
+                assert_eq!(board.contains(x, y), tiling.get(x, y).is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_orientations() {
+        let counts: Vec<usize> = pentominoes()
+            .iter()
+            .map(|piece| piece.orientations(true).len())
+            .collect();
+        assert_eq!(counts, vec![8, 2, 8, 8, 8, 4, 4, 4, 4, 1, 8, 4]);
+
+        let f = &pentominoes()[0];
+        assert_eq!(f.orientations(false).len(), 4);
+    }
+
+    #[test]
+    fn test_pentomino_rectangle() {
+        let board = Board::rectangle(10, 6);
+        let packing = Packing::new(board.clone(), pentominoes());
+        let tiling = packing.solve().unwrap();
+        validate(&board, &pentominoes(), &tiling);
+        assert_eq!(tiling.to_string().lines().count(), 6);
+
+        // The 3x20 rectangle has two packings, each counted once per symmetry of the rectangle
+        let packing = Packing::new(Board::rectangle(20, 3), pentominoes());
+        assert_eq!(packing.count(usize::MAX), 8);
+    }
+
+    #[test]
+    fn test_holes() {
+        // Dana Scott's 8x8 board with the middle four squares removed
+        let mut board = Board::rectangle(8, 8);
+        for (x, y) in [(3, 3), (4, 3), (3, 4), (4, 4)] {
+            board.remove(x, y);
+        }
+
+        let tiling = Packing::new(board.clone(), pentominoes()).solve().unwrap();
+        validate(&board, &pentominoes(), &tiling);
+        assert_eq!(tiling.get(3, 3), None);
+
+        let board = Board::parse("##.\n###");
+        assert_eq!(board.area(), 5);
+        assert!(!board.contains(2, 0));
+
+        let pieces = vec![Piece::parse('a', "##\n#."), Piece::parse('b', "##")];
+        let packing = Packing::new(board.clone(), pieces.clone());
+        assert_eq!(packing.count(usize::MAX), 2);
+        validate(&board, &pieces, &packing.solve().unwrap());
+
+        // The areas differ
+        let packing = Packing::new(Board::rectangle(2, 2), pieces);
+        assert!(packing.solve().is_none());
+    }
+}
@@ -0,0 +1,281 @@
+// Python bindings, enabled by the python feature. Building the extension module itself goes through
+// maturin, which reads pyproject.toml:
+//
+//     maturin develop --release
+//
+// and then from Python:
+//
+//     import lynx
+//     puzzle = lynx.generate(seed=1)
+//     print(puzzle.solve())
+//
+// Row indices and grids cross into Python as plain lists and strings, which pyo3 has to copy, but
+// none of them are large enough for that to matter next to solving
+use crate::rng::SplitMix64;
+use crate::sudoku::Sudoku;
+use crate::DLXMatrix;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// A DLXMatrix is consumed by solving, so this keeps the rows and builds a fresh matrix whenever
+// it's asked to solve, letting Python solve the same matrix as many times as it likes
+#[pyclass(name = "DLXMatrix", module = "lynx")]
+pub struct PyDLXMatrix {
+    primary_columns: u32,
+    secondary_columns: u32,
+    rows: Vec<Vec<u32>>,
+}
+
+impl PyDLXMatrix {
+    fn matrix(&self) -> DLXMatrix<u32> {
+        let mut matrix =
+            DLXMatrix::with_secondary_columns(self.primary_columns, self.secondary_columns);
+        for row in &self.rows {
+            matrix.push_row(row);
+        }
+        matrix
+    }
+}
+
+#[pymethods]
+impl PyDLXMatrix {
+    #[new]
+    #[pyo3(signature = (primary_columns, secondary_columns = 0))]
+    fn new(primary_columns: u32, secondary_columns: u32) -> PyResult<Self> {
+        if primary_columns.checked_add(secondary_columns).is_none() {
+            return Err(PyValueError::new_err("Too many columns"));
+        }
+
+        Ok(Self {
+            primary_columns,
+            secondary_columns,
+            rows: vec![],
+        })
+    }
+
+    #[getter]
+    fn columns(&self) -> u32 {
+        self.primary_columns + self.secondary_columns
+    }
+
+    #[getter]
+    fn primary_columns(&self) -> u32 {
+        self.primary_columns
+    }
+
+    #[getter]
+    fn rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    // Returns the index of the new row
+    fn push_row(&mut self, columns: Vec<u32>) -> PyResult<usize> {
+        if columns.is_empty() {
+            return Err(PyValueError::new_err("Rows must be non-empty"));
+        }
+
+        if let Some(&column) = columns.iter().find(|&&column| column >= self.columns()) {
+            return Err(PyValueError::new_err(format!(
+                "Columns must be in the range 0..{} (got {})",
+                self.columns(),
+                column
+            )));
+        }
+
+        self.rows.push(columns);
+        Ok(self.rows.len() - 1)
+    }
+
+    // The indices of the rows in some solution, in increasing order, or None
+    fn solve(&self) -> Option<Vec<usize>> {
+        self.solutions(Some(1)).pop()
+    }
+
+    #[pyo3(signature = (limit = None))]
+    fn solutions(&self, limit: Option<usize>) -> Vec<Vec<usize>> {
+        self.matrix()
+            .solutions()
+            .row_indices()
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|mut rows| {
+                rows.sort_unstable();
+                rows
+            })
+            .collect()
+    }
+
+    #[pyo3(signature = (limit = None))]
+    fn count_solutions(&self, limit: Option<usize>) -> usize {
+        self.matrix().count_solutions(limit.unwrap_or(usize::MAX))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "DLXMatrix(columns={}, primary_columns={}, rows={})",
+            self.columns(),
+            self.primary_columns,
+            self.rows.len()
+        )
+    }
+}
+
+#[pyclass(name = "Sudoku", module = "lynx", skip_from_py_object)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PySudoku(Sudoku);
+
+#[pymethods]
+impl PySudoku {
+    // An empty grid, or a puzzle in any format Sudoku::from_str accepts
+    #[new]
+    #[pyo3(signature = (puzzle = None))]
+    fn new(puzzle: Option<&str>) -> PyResult<Self> {
+        match puzzle {
+            Some(puzzle) => puzzle
+                .parse()
+                .map(PySudoku)
+                .map_err(|error| PyValueError::new_err(error.to_string())),
+            None => Ok(PySudoku(Sudoku::new())),
+        }
+    }
+
+    // Values run from 1 to 9, with 0 for an empty cell
+    fn get(&self, x: usize, y: usize) -> PyResult<u8> {
+        check_cell(x, y)?;
+        Ok(self.0.get(x, y))
+    }
+
+    fn set(&mut self, x: usize, y: usize, value: u8) -> PyResult<()> {
+        check_cell(x, y)?;
+        if value > 9 {
+            return Err(PyValueError::new_err(format!(
+                "Values must be in the range 0..=9 (got {})",
+                value
+            )));
+        }
+
+        self.0.set(x, y, value);
+        Ok(())
+    }
+
+    fn solve(&self) -> Option<PySudoku> {
+        self.0.solve().map(PySudoku)
+    }
+
+    #[pyo3(signature = (limit = None))]
+    fn count_solutions(&self, limit: Option<usize>) -> usize {
+        self.0.count_solutions(limit.unwrap_or(usize::MAX))
+    }
+
+    fn has_unique_solution(&self) -> bool {
+        self.0.has_unique_solution()
+    }
+
+    fn is_solved(&self) -> bool {
+        self.0.is_solved()
+    }
+
+    fn clue_count(&self) -> usize {
+        self.0.clue_count()
+    }
+
+    fn to_line(&self) -> String {
+        self.0.to_line()
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Sudoku({:?})", self.0.to_line())
+    }
+
+    fn __eq__(&self, other: &PySudoku) -> bool {
+        self.0 == other.0
+    }
+}
+
+fn check_cell(x: usize, y: usize) -> PyResult<()> {
+    if x >= 9 || y >= 9 {
+        return Err(PyValueError::new_err(format!(
+            "Cell ({}, {}) is outside the grid",
+            x, y
+        )));
+    }
+
+    Ok(())
+}
+
+// A minimal puzzle with a unique solution, the same way lynx-sudoku generate makes them. Without
+// a seed the time is used instead
+#[pyfunction]
+#[pyo3(signature = (seed = None))]
+fn generate(seed: Option<u64>) -> PySudoku {
+    let seed = seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or(0)
+    });
+
+    let mut rng = SplitMix64::new(seed);
+    let solution = Sudoku::random_filled(&mut rng);
+    let puzzle = solution
+        .minimize(&mut rng)
+        .expect("filled grids have a unique solution");
+
+    PySudoku(puzzle)
+}
+
+#[pymodule]
+fn lynx(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyDLXMatrix>()?;
+    module.add_class::<PySudoku>()?;
+    module.add_function(wrap_pyfunction!(generate, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::python::{generate, PyDLXMatrix, PySudoku};
+
+    #[test]
+    fn test_matrix() {
+        let mut matrix = PyDLXMatrix::new(3, 1).unwrap();
+        for columns in [vec![0, 3], vec![1, 2], vec![0, 1], vec![2, 3]] {
+            matrix.push_row(columns).unwrap();
+        }
+
+        assert!(matrix.push_row(vec![]).is_err());
+        assert!(matrix.push_row(vec![4]).is_err());
+        assert_eq!(matrix.rows(), 4);
+
+        let mut solutions = matrix.solutions(None);
+        solutions.sort_unstable();
+        assert_eq!(solutions, vec![vec![0, 1], vec![2, 3]]);
+        assert!(matrix.solve().is_some());
+        assert_eq!(matrix.count_solutions(Some(1)), 1);
+        assert_eq!(matrix.count_solutions(None), 2);
+    }
+
+    #[test]
+    fn test_sudoku() {
+        let puzzle = generate(Some(7));
+        assert!(puzzle.has_unique_solution());
+        assert_eq!(PySudoku::new(Some(&puzzle.to_line())).unwrap(), puzzle);
+
+        let solved = puzzle.solve().unwrap();
+        assert!(solved.is_solved());
+        assert_eq!(solved.clue_count(), 81);
+
+        let mut empty = PySudoku::new(None).unwrap();
+        assert_eq!(empty.count_solutions(Some(2)), 2);
+        empty.set(0, 0, 5).unwrap();
+        assert_eq!(empty.get(0, 0).unwrap(), 5);
+        assert!(empty.set(9, 0, 1).is_err());
+        assert!(empty.set(0, 0, 10).is_err());
+        assert!(PySudoku::new(Some("55")).is_err());
+    }
+}
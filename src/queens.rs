@@ -0,0 +1,151 @@
+use crate::DLXMatrix;
+
+use core::fmt;
+
+// Placing n queens on an n by n board so that none attacks another. Every row and column holds
+// exactly one queen, while a diagonal may be left empty, so the diagonals are secondary columns
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Queens {
+    n: usize,
+}
+
+impl Queens {
+    pub fn new(n: usize) -> Self {
+        assert!(n > 0, "The board must have at least one square");
+        Self { n }
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn solve(&self) -> Option<Board> {
+        self.iter_solutions().next()
+    }
+
+    pub fn count(&self) -> usize {
+        self.encode().count_solutions(usize::MAX)
+    }
+
+    pub fn iter_solutions(&self) -> impl Iterator<Item = Board> {
+        let n = self.n;
+
+        self.encode().solutions().row_indices().map(move |rows| {
+            let mut columns = vec![0; n];
+            for row in rows {
+                columns[row / n] = row % n;
+            }
+            Board { columns }
+        })
+    }
+
+    // One option per square, numbered row by row, covering its row, its column, and both of its
+    // diagonals. Ties between columns go to the earliest, and the search goes much faster when
+    // those are the rows and columns at the middle of the board, which constrain the most
+    // diagonals, so they are numbered from the middle outwards, alternating rows and columns
+    fn encode(&self) -> DLXMatrix<u32> {
+        let n = self.n;
+        let diagonals = 2 * n - 1;
+        let mut matrix = DLXMatrix::with_secondary_columns(2 * n as u32, 2 * diagonals as u32);
+
+        let rank = |i: usize| {
+            let middle = (n - 1) / 2;
+            let offset = 2 * i.abs_diff(middle);
+            if i < middle {
+                offset
+            } else {
+                offset.saturating_sub(1)
+            }
+        };
+
+        for y in 0..n {
+            for x in 0..n {
+                matrix.push_row(&[
+                    2 * rank(y) as u32,
+                    2 * rank(x) as u32 + 1,
+                    (2 * n + x + y) as u32,
+                    (2 * n + diagonals + x + n - 1 - y) as u32,
+                ]);
+            }
+        }
+
+        matrix
+    }
+}
+
+// The column of the queen in each row
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Board {
+    columns: Vec<usize>,
+}
+
+impl Board {
+    pub fn columns(&self) -> &[usize] {
+        &self.columns
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let n = self.columns.len();
+
+        for (y, &column) in self.columns.iter().enumerate() {
+            for x in 0..n {
+                if x > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{}", if x == column { 'Q' } else { '.' })?;
+            }
+
+            if y + 1 < n {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::queens::{Board, Queens};
+
+    fn validate(board: &Board) {
+        let columns = board.columns();
+
+        for (i, &a) in columns.iter().enumerate() {
+            for (j, &b) in columns.iter().enumerate().skip(i + 1) {
+                assert_ne!(a, b);
+                assert_ne!(a.abs_diff(b), j - i);
+            }
+        }
+    }
+
+    #[test]
+    fn test_count() {
+        let counts = [1, 0, 0, 2, 10, 4, 40, 92, 352];
+
+        for (i, &count) in counts.iter().enumerate() {
+            assert_eq!(Queens::new(i + 1).count(), count);
+        }
+    }
+
+    #[test]
+    fn test_solutions() {
+        let queens = Queens::new(8);
+        let solutions: Vec<Board> = queens.iter_solutions().collect();
+        assert_eq!(solutions.len(), 92);
+        solutions.iter().for_each(validate);
+
+        validate(&queens.solve().unwrap());
+        assert!(Queens::new(3).solve().is_none());
+    }
+
+    #[test]
+    fn test_display() {
+        let board = Queens::new(4).solve().unwrap();
+        let text = board.to_string();
+        assert_eq!(text.lines().count(), 4);
+        assert_eq!(text.matches('Q').count(), 4);
+    }
+}
@@ -0,0 +1,125 @@
+pub trait Rng {
+    fn next_u64(&mut self) -> u64;
+
+    fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "Bound must be positive");
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    fn shuffle<T>(&mut self, slice: &mut [T])
+    where
+        Self: Sized,
+    {
+        for i in (1..slice.len()).rev() {
+            slice.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl Rng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
+
+// With the rand feature, every generator from the rand ecosystem can be passed wherever an Rng is
+// taken, so that lynx can share a seeded generator with the rest of an application
+#[cfg(feature = "rand")]
+impl<R: rand_core::RngCore + ?Sized> Rng for R {
+    fn next_u64(&mut self) -> u64 {
+        rand_core::RngCore::next_u64(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rng::{Rng, SplitMix64};
+
+    #[test]
+    fn test_deterministic() {
+        let mut a = SplitMix64::new(42);
+        let mut b = SplitMix64::new(42);
+        let mut c = SplitMix64::new(43);
+
+        for _ in 0..100 {
+            let value = a.next_u64();
+            assert_eq!(value, b.next_u64());
+            assert_ne!(value, c.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_below() {
+        let mut rng = SplitMix64::new(0);
+        let mut seen = [false; 10];
+
+        for _ in 0..1000 {
+            let value = rng.below(10);
+            assert!(value < 10);
+            seen[value] = true;
+        }
+
+        assert!(seen.iter().all(|&seen| seen));
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut rng = SplitMix64::new(1);
+        let mut values = (0..50).collect::<Vec<_>>();
+        rng.shuffle(&mut values);
+
+        assert_ne!(values, (0..50).collect::<Vec<_>>());
+        values.sort_unstable();
+        assert_eq!(values, (0..50).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_rng_core() {
+        use crate::sudoku::Sudoku;
+
+        // A generator from outside lynx, standing in for one from rand
+        struct Counter(u64);
+
+        impl rand_core::RngCore for Counter {
+            fn next_u32(&mut self) -> u32 {
+                rand_core::RngCore::next_u64(self) as u32
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                self.0 = self.0.wrapping_add(1);
+                SplitMix64::new(self.0).next_u64()
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                rand_core::impls::fill_bytes_via_next(self, dest)
+            }
+        }
+
+        let mut rng = Counter(0);
+        assert!(rng.below(10) < 10);
+
+        let a = Sudoku::random_filled(&mut Counter(5));
+        assert!(a.is_solved());
+        assert_eq!(a, Sudoku::random_filled(&mut Counter(5)));
+
+        let rng: &mut dyn rand_core::RngCore = &mut Counter(5);
+        assert_eq!(Sudoku::random_filled(&mut &mut *rng), a);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::DLXMatrix;
+
+// Placing each of a set of events at one of its candidate slots, where each candidate also ties up
+// some resources (rooms, people, equipment) for that slot, and no resource can be in two places at
+// once. Events, slots, and resources are named, and referred to by the indices returned when they
+// are added
+#[derive(Clone, Debug, Default)]
+pub struct Schedule {
+    events: Vec<String>,
+    slots: Vec<String>,
+    resources: Vec<String>,
+    candidates: Vec<Assignment>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Assignment {
+    pub event: usize,
+    pub slot: usize,
+    pub resources: Vec<usize>,
+}
+
+impl Schedule {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_event(&mut self, name: &str) -> usize {
+        self.events.push(name.to_string());
+        self.events.len() - 1
+    }
+
+    pub fn add_slot(&mut self, name: &str) -> usize {
+        self.slots.push(name.to_string());
+        self.slots.len() - 1
+    }
+
+    pub fn add_resource(&mut self, name: &str) -> usize {
+        self.resources.push(name.to_string());
+        self.resources.len() - 1
+    }
+
+    // One way of holding the event, which is only chosen if all of the resources are free in that
+    // slot. Returns the index of the candidate
+    pub fn add_candidate(&mut self, event: usize, slot: usize, resources: &[usize]) -> usize {
+        assert!(event < self.events.len(), "No event {}", event);
+        assert!(slot < self.slots.len(), "No slot {}", slot);
+
+        let mut sorted = resources.to_vec();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert!(
+            sorted
+                .iter()
+                .all(|&resource| resource < self.resources.len()),
+            "No resource among {:?}",
+            resources
+        );
+
+        self.candidates.push(Assignment {
+            event,
+            slot,
+            resources: sorted,
+        });
+        self.candidates.len() - 1
+    }
+
+    pub fn event(&self, event: usize) -> &str {
+        &self.events[event]
+    }
+
+    pub fn slot(&self, slot: usize) -> &str {
+        &self.slots[slot]
+    }
+
+    pub fn resource(&self, resource: usize) -> &str {
+        &self.resources[resource]
+    }
+
+    pub fn candidates(&self) -> &[Assignment] {
+        &self.candidates
+    }
+
+    // The chosen candidate for each event, in the order the events were added
+    pub fn solve(&self) -> Option<Vec<&Assignment>> {
+        self.solutions().next()
+    }
+
+    pub fn solutions(&self) -> impl Iterator<Item = Vec<&Assignment>> + '_ {
+        self.matrix().solutions().row_indices().map(|rows| {
+            let mut assignments: Vec<&Assignment> =
+                rows.into_iter().map(|row| &self.candidates[row]).collect();
+            assignments.sort_unstable_by_key(|assignment| assignment.event);
+            assignments
+        })
+    }
+
+    pub fn count(&self, limit: usize) -> usize {
+        self.matrix().count_solutions(limit)
+    }
+
+    // One primary column per event, then a secondary column for each resource in each slot, with
+    // a row per candidate. Row indices are candidate indices
+    pub fn matrix(&self) -> DLXMatrix<u32> {
+        let events = self.events.len();
+        let slots = self.slots.len();
+
+        let mut matrix =
+            DLXMatrix::with_secondary_columns(events as u32, (self.resources.len() * slots) as u32);
+        let mut row = vec![];
+
+        for candidate in &self.candidates {
+            row.clear();
+            row.push(candidate.event as u32);
+            row.extend(
+                candidate
+                    .resources
+                    .iter()
+                    .map(|&resource| (events + slots * resource + candidate.slot) as u32),
+            );
+            matrix.push_row(&row);
+        }
+
+        matrix
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::schedule::Schedule;
+
+    #[test]
+    fn test_schedule() {
+        let mut schedule = Schedule::new();
+        let (morning, afternoon) = (schedule.add_slot("morning"), schedule.add_slot("afternoon"));
+        let room = schedule.add_resource("room");
+        let (alice, bob) = (schedule.add_resource("alice"), schedule.add_resource("bob"));
+
+        // Only one room, so the two classes have to be held at different times, and Bob can only
+        // teach in the morning
+        let maths = schedule.add_event("maths");
+        let history = schedule.add_event("history");
+        for slot in [morning, afternoon] {
+            schedule.add_candidate(maths, slot, &[room, alice]);
+        }
+        schedule.add_candidate(history, morning, &[room, bob]);
+
+        let solution = schedule.solve().unwrap();
+        assert_eq!(solution.len(), 2);
+        assert_eq!((solution[0].event, solution[0].slot), (maths, afternoon));
+        assert_eq!((solution[1].event, solution[1].slot), (history, morning));
+        assert_eq!(schedule.count(usize::MAX), 1);
+        assert_eq!(schedule.slot(solution[0].slot), "afternoon");
+
+        // Alice can't teach both classes at once, whatever the rooms
+        let second = schedule.add_resource("second room");
+        let art = schedule.add_event("art");
+        schedule.add_candidate(art, afternoon, &[second, alice]);
+        assert!(schedule.solve().is_none());
+
+        schedule.add_candidate(art, morning, &[second, alice, alice]);
+        assert_eq!(
+            schedule.candidates().last().unwrap().resources,
+            vec![alice, second]
+        );
+        assert_eq!(schedule.count(usize::MAX), 1);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::DLXMatrix;
+
+pub type Triple = [usize; 3];
+
+// Steiner triple systems S(2, 3, n): sets of triples drawn from the points 0 to n - 1 such that
+// every pair of points lies in exactly one triple
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SteinerTripleSystems {
+    n: usize,
+}
+
+impl SteinerTripleSystems {
+    pub fn new(n: usize) -> Self {
+        Self { n }
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    // Kirkman's condition, which is both necessary and sufficient
+    pub fn exists(&self) -> bool {
+        self.n % 6 == 1 || self.n % 6 == 3
+    }
+
+    // The triples of some system, each in increasing order, and in lexicographic order overall
+    pub fn solve(&self) -> Option<Vec<Triple>> {
+        self.enumerate().next()
+    }
+
+    // Systems are counted as sets of triples, so isomorphic systems on different labellings of the
+    // points all count separately
+    pub fn count(&self, limit: usize) -> usize {
+        if !self.exists() {
+            return 0;
+        }
+        self.encode().0.count_solutions(limit)
+    }
+
+    pub fn enumerate(&self) -> impl Iterator<Item = Vec<Triple>> {
+        // Without this, the search would take a long time to rule out the impossible orders
+        let limit = if self.exists() { usize::MAX } else { 0 };
+        let (matrix, triples) = self.encode();
+
+        matrix
+            .solutions()
+            .row_indices()
+            .take(limit)
+            .map(move |mut rows| {
+                rows.sort_unstable();
+                rows.into_iter().map(|row| triples[row]).collect()
+            })
+    }
+
+    // One column per pair of points, with a row for each triple covering its three pairs
+    fn encode(&self) -> (DLXMatrix<u32>, Vec<Triple>) {
+        let n = self.n;
+        let pair = |i: usize, j: usize| (j * (j - 1) / 2 + i) as u32;
+
+        let mut matrix = DLXMatrix::new((n * n.saturating_sub(1) / 2) as u32);
+        let mut triples = vec![];
+
+        for a in 0..n {
+            for b in a + 1..n {
+                for c in b + 1..n {
+                    matrix.push_row(&[pair(a, b), pair(a, c), pair(b, c)]);
+                    triples.push([a, b, c]);
+                }
+            }
+        }
+
+        (matrix, triples)
+    }
+}
+
+// Whether the triples form a Steiner triple system on the points 0 to n - 1
+pub fn is_steiner_triple_system(n: usize, triples: &[Triple]) -> bool {
+    let mut covered = vec![false; n * n];
+
+    for &[a, b, c] in triples {
+        if a.max(b).max(c) >= n || a == b || a == c || b == c {
+            return false;
+        }
+
+        for (i, j) in [(a, b), (a, c), (b, c)] {
+            if covered[n * i.min(j) + i.max(j)] {
+                return false;
+            }
+            covered[n * i.min(j) + i.max(j)] = true;
+        }
+    }
+
+    triples.len() * 6 == n * n.saturating_sub(1)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::steiner::{is_steiner_triple_system, SteinerTripleSystems};
+
+    #[test]
+    fn test_exists() {
+        let orders: Vec<usize> = (0..30)
+            .filter(|&n| SteinerTripleSystems::new(n).exists())
+            .collect();
+        assert_eq!(orders, vec![1, 3, 7, 9, 13, 15, 19, 21, 25, 27]);
+
+        assert!(SteinerTripleSystems::new(5).solve().is_none());
+        assert_eq!(SteinerTripleSystems::new(11).count(usize::MAX), 0);
+    }
+
+    #[test]
+    fn test_solve() {
+        for n in [1, 3, 7, 9, 13, 15] {
+            let triples = SteinerTripleSystems::new(n).solve().unwrap();
+            assert!(is_steiner_triple_system(n, &triples));
+        }
+
+        // The Fano plane is the only system on 7 points, but it can be labelled in 30 ways
+        let systems = SteinerTripleSystems::new(7);
+        assert_eq!(systems.count(usize::MAX), 30);
+        assert_eq!(systems.solve().unwrap().len(), 7);
+        assert!(systems
+            .enumerate()
+            .all(|triples| is_steiner_triple_system(7, &triples)));
+
+        assert_eq!(SteinerTripleSystems::new(9).count(usize::MAX), 840);
+    }
+
+    #[test]
+    fn test_is_steiner_triple_system() {
+        assert!(is_steiner_triple_system(3, &[[0, 1, 2]]));
+        assert!(!is_steiner_triple_system(3, &[]));
+        assert!(!is_steiner_triple_system(3, &[[0, 1, 1]]));
+        assert!(!is_steiner_triple_system(4, &[[0, 1, 2], [0, 1, 3]]));
+    }
+}
@@ -0,0 +1,368 @@
+use crate::sudoku::Sudoku;
+
+use std::collections::{HashMap, HashSet};
+
+const PERMUTATIONS: [[u8; 3]; 6] = [
+    [0, 1, 2],
+    [0, 2, 1],
+    [1, 0, 2],
+    [1, 2, 0],
+    [2, 0, 1],
+    [2, 1, 0],
+];
+
+// Blanks sort after every digit, so that rows full of clues come first and the many ways of
+// ordering empty rows are only explored once the columns are pinned down
+const BLANK: u8 = 10;
+
+// A validity-preserving relabelling of the grid: an optional transposition, then the source row and
+// column for each row and column of the result, then a relabelling of the digits
+#[derive(Clone, Debug)]
+struct Transform {
+    transpose: bool,
+    rows: [u8; 9],
+    columns: [u8; 9],
+    digits: [u8; 10],
+}
+
+impl Transform {
+    fn apply(&self, sudoku: &Sudoku) -> Sudoku {
+        let mut transformed = Sudoku::new();
+
+        for y in 0..9 {
+            for x in 0..9 {
+                let (row, column) = (self.rows[y] as usize, self.columns[x] as usize);
+                let value = source(sudoku, self.transpose, row, column);
+                transformed.set(x, y, self.digits[value as usize]);
+            }
+        }
+
+        transformed
+    }
+
+    fn invert(&self, transformed: &Sudoku) -> Sudoku {
+        let mut digits = [0; 10];
+        for (value, &digit) in self.digits.iter().enumerate() {
+            digits[digit as usize] = value as u8;
+        }
+
+        let mut sudoku = Sudoku::new();
+
+        for y in 0..9 {
+            for x in 0..9 {
+                let (row, column) = (self.rows[y] as usize, self.columns[x] as usize);
+                let value = digits[transformed.get(x, y) as usize];
+
+                if self.transpose {
+                    sudoku.set(row, column, value);
+                } else {
+                    sudoku.set(column, row, value);
+                }
+            }
+        }
+
+        sudoku
+    }
+}
+
+#[derive(Clone)]
+struct Partial {
+    transpose: bool,
+    columns: usize,
+    rows: [u8; 9],
+    digits: [u8; 10],
+    next: u8,
+}
+
+impl Sudoku {
+    // The lexicographically least grid among all those equivalent to this one under band, stack,
+    // row, and column permutations, transposition, and relabelling of digits. Two puzzles are
+    // isomorphic exactly when their canonical forms are equal
+    pub fn canonical_form(&self) -> Sudoku {
+        let transform = self.canonical_transform();
+        transform.apply(self)
+    }
+
+    // Rows of the result are fixed one at a time, keeping every partial transform which ties for
+    // the least rows so far
+    fn canonical_transform(&self) -> Transform {
+        let arrangements = arrangements();
+        let mut beam = vec![];
+
+        for transpose in [false, true] {
+            for columns in 0..arrangements.len() {
+                beam.push(Partial {
+                    transpose,
+                    columns,
+                    rows: [0; 9],
+                    digits: [0; 10],
+                    next: 1,
+                });
+            }
+        }
+
+        for depth in 0..9 {
+            let mut best = [u8::MAX; 9];
+            let mut next_beam = vec![];
+            let mut seen = HashSet::new();
+
+            for partial in &beam {
+                let used = partial.rows[..depth]
+                    .iter()
+                    .fold(0u16, |used, &row| used | 1 << row);
+
+                let bands = if depth % 3 == 0 {
+                    (0..3)
+                        .filter(|&band| used & (0b111 << (3 * band)) == 0)
+                        .collect::<Vec<_>>()
+                } else {
+                    vec![partial.rows[depth - depth % 3] / 3]
+                };
+
+                for row in bands.into_iter().flat_map(|band| 3 * band..3 * band + 3) {
+                    if used & (1 << row) != 0 {
+                        continue;
+                    }
+
+                    let mut extended = partial.clone();
+                    extended.rows[depth] = row;
+
+                    let mut line = [0; 9];
+                    for (x, cell) in line.iter_mut().enumerate() {
+                        let column = arrangements[partial.columns][x] as usize;
+                        let value = match source(self, partial.transpose, row as usize, column) {
+                            0 => BLANK,
+                            value => {
+                                let digit = &mut extended.digits[value as usize];
+                                if *digit == 0 {
+                                    *digit = extended.next;
+                                    extended.next += 1;
+                                }
+                                *digit
+                            }
+                        };
+                        *cell = value;
+                    }
+
+                    if line > best {
+                        continue;
+                    }
+
+                    if line < best {
+                        best = line;
+                        next_beam.clear();
+                        seen.clear();
+                    }
+
+                    // Partials which differ only in the order of rows already placed behave
+                    // identically from here on
+                    let key = (
+                        extended.transpose,
+                        extended.columns,
+                        extended.digits,
+                        used | 1 << row,
+                    );
+                    if seen.insert(key) {
+                        next_beam.push(extended);
+                    }
+                }
+            }
+
+            beam = next_beam;
+        }
+
+        let mut best = beam.swap_remove(0);
+
+        // Digits absent from the puzzle take the remaining labels
+        for value in 1..=9 {
+            if best.digits[value] == 0 {
+                best.digits[value] = best.next;
+                best.next += 1;
+            }
+        }
+
+        Transform {
+            transpose: best.transpose,
+            rows: best.rows,
+            columns: arrangements[best.columns],
+            digits: best.digits,
+        }
+    }
+}
+
+fn source(sudoku: &Sudoku, transpose: bool, row: usize, column: usize) -> u8 {
+    if transpose {
+        sudoku.get(row, column)
+    } else {
+        sudoku.get(column, row)
+    }
+}
+
+// Every column order reachable by permuting stacks and the columns within each stack
+fn arrangements() -> Vec<[u8; 9]> {
+    let mut arrangements = Vec::with_capacity(1296);
+
+    for stacks in &PERMUTATIONS {
+        for first in &PERMUTATIONS {
+            for second in &PERMUTATIONS {
+                for third in &PERMUTATIONS {
+                    let mut columns = [0; 9];
+
+                    for (i, within) in [first, second, third].iter().enumerate() {
+                        for j in 0..3 {
+                            columns[3 * i + j] = 3 * stacks[i] + within[j];
+                        }
+                    }
+
+                    arrangements.push(columns);
+                }
+            }
+        }
+    }
+
+    arrangements
+}
+
+// Memoizes solutions by canonical form, so that a puzzle isomorphic to one already seen is answered
+// by transforming the earlier solution
+#[derive(Clone, Debug, Default)]
+pub struct SolverCache {
+    solutions: HashMap<Sudoku, Option<Sudoku>>,
+    hits: usize,
+    misses: usize,
+}
+
+impl SolverCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn solve(&mut self, sudoku: &Sudoku) -> Option<Sudoku> {
+        let transform = sudoku.canonical_transform();
+        let canonical = transform.apply(sudoku);
+
+        let solution = match self.solutions.get(&canonical) {
+            Some(solution) => {
+                self.hits += 1;
+                solution.clone()
+            }
+            None => {
+                self.misses += 1;
+                let solution = canonical.solve();
+                self.solutions.insert(canonical, solution.clone());
+                solution
+            }
+        };
+
+        solution.map(|solution| transform.invert(&solution))
+    }
+
+    pub fn len(&self) -> usize {
+        self.solutions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.solutions.is_empty()
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn clear(&mut self) {
+        self.solutions.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rng::{Rng, SplitMix64};
+    use crate::sudoku::canonical::{arrangements, Transform, PERMUTATIONS};
+    use crate::sudoku::{SolverCache, Sudoku};
+    use core::str::FromStr;
+
+    fn random_transform(rng: &mut SplitMix64) -> Transform {
+        let arrangements = arrangements();
+        let mut digits = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        rng.shuffle(&mut digits[1..]);
+
+        Transform {
+            transpose: rng.below(2) == 0,
+            rows: arrangements[rng.below(1296)],
+            columns: arrangements[rng.below(1296)],
+            digits,
+        }
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        assert_eq!(arrangements().len(), 1296);
+        assert_eq!(PERMUTATIONS.len(), 6);
+
+        let mut rng = SplitMix64::new(3);
+        let puzzles = [
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+            "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+            ".................1.....2.3......3.2...1.4......5....6..3......4.7..8...962...7...",
+        ];
+
+        let mut forms = vec![];
+
+        for puzzle in puzzles {
+            let sudoku = Sudoku::from_str(puzzle).unwrap();
+            let canonical = sudoku.canonical_form();
+            assert_eq!(canonical.clue_count(), sudoku.clue_count());
+
+            for _ in 0..5 {
+                let transform = random_transform(&mut rng);
+                let transformed = transform.apply(&sudoku);
+                assert_eq!(transform.invert(&transformed), sudoku);
+                assert_eq!(transformed.canonical_form(), canonical);
+            }
+
+            forms.push(canonical);
+        }
+
+        assert!(forms[0] != forms[1] && forms[1] != forms[2] && forms[0] != forms[2]);
+
+        let solution = Sudoku::from_str(puzzles[0]).unwrap().solve().unwrap();
+        assert_eq!(
+            solution.canonical_form(),
+            random_transform(&mut rng).apply(&solution).canonical_form()
+        );
+        assert_eq!(Sudoku::new().canonical_form(), Sudoku::new());
+    }
+
+    #[test]
+    fn test_solver_cache() {
+        let mut rng = SplitMix64::new(5);
+        let mut cache = SolverCache::new();
+        let sudoku = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+
+        assert_eq!(cache.solve(&sudoku), sudoku.solve());
+
+        for _ in 0..3 {
+            let transformed = random_transform(&mut rng).apply(&sudoku);
+            assert_eq!(cache.solve(&transformed), transformed.solve());
+        }
+
+        assert_eq!((cache.len(), cache.hits(), cache.misses()), (1, 3, 1));
+
+        let mut invalid = Sudoku::new();
+        invalid.set(0, 0, 1);
+        invalid.set(1, 0, 1);
+        assert!(cache.solve(&invalid).is_none());
+        assert!(cache.solve(&invalid).is_none());
+        assert_eq!((cache.len(), cache.hits()), (2, 4));
+
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,210 @@
+use crate::sudoku::{ParseSudokuError, PuzzleMeta, Sudoku};
+
+use core::fmt;
+use core::str::FromStr;
+use std::fs;
+use std::io;
+use std::io::BufRead;
+use std::path::Path;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Format {
+    // One puzzle per line, optionally followed by whitespace and a comment
+    Line,
+    // One puzzle per line and nothing else
+    Sdm,
+    // The puzzle in the first column, with any further columns kept as the comment; a header row is
+    // skipped, as is a byte order mark
+    Csv,
+}
+
+// Blank lines and lines starting with '#' are skipped in every format
+pub struct Dataset<R> {
+    lines: io::Lines<R>,
+    format: Format,
+    line: usize,
+    started: bool,
+}
+
+impl<R: BufRead> Dataset<R> {
+    pub fn new(reader: R, format: Format) -> Self {
+        Self {
+            lines: reader.lines(),
+            format,
+            line: 0,
+            started: false,
+        }
+    }
+
+    fn parse(&self, line: &str) -> Result<(PuzzleMeta, Sudoku), ParseSudokuError> {
+        let mut meta = PuzzleMeta::default();
+
+        let puzzle = match self.format {
+            Format::Line => {
+                let (puzzle, comment) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                if !comment.trim().is_empty() {
+                    meta.comment = Some(comment.trim().to_string());
+                }
+                puzzle
+            }
+            Format::Sdm => line,
+            Format::Csv => {
+                let (puzzle, rest) = line.split_once(',').unwrap_or((line, ""));
+                if !rest.trim().is_empty() {
+                    meta.comment = Some(rest.trim().to_string());
+                }
+                puzzle.trim().trim_matches('"')
+            }
+        };
+
+        Ok((meta, Sudoku::from_str(puzzle)?))
+    }
+}
+
+impl Dataset<io::BufReader<fs::File>> {
+    pub fn open<P: AsRef<Path>>(path: P, format: Format) -> io::Result<Self> {
+        let file = fs::File::open(path)?;
+        Ok(Self::new(io::BufReader::new(file), format))
+    }
+}
+
+impl<R: BufRead> Iterator for Dataset<R> {
+    type Item = Result<(PuzzleMeta, Sudoku), DatasetError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(error) => return Some(Err(DatasetError::Io { kind: error.kind() })),
+            };
+            self.line += 1;
+
+            let line = match self.line {
+                1 => line.trim_start_matches('\u{feff}'),
+                _ => &line,
+            };
+
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            // The header is only recognised by failing to parse, so files without one still work
+            let first = !self.started;
+            self.started = true;
+
+            match self.parse(trimmed) {
+                Ok(puzzle) => return Some(Ok(puzzle)),
+                Err(_) if self.format == Format::Csv && first => continue,
+                Err(error) => {
+                    return Some(Err(DatasetError::Parse {
+                        line: self.line,
+                        error,
+                    }))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DatasetError {
+    Io {
+        kind: io::ErrorKind,
+    },
+    Parse {
+        line: usize,
+        error: ParseSudokuError,
+    },
+}
+
+impl fmt::Display for DatasetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            DatasetError::Io { kind } => {
+                write!(f, "Error reading dataset: {}", kind)?;
+            }
+            DatasetError::Parse { line, error } => {
+                write!(f, "Invalid puzzle on line {}: {}", line, error)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::dataset::{Dataset, DatasetError, Format};
+    use crate::sudoku::ParseSudokuError;
+    use flate2::bufread::GzDecoder;
+    use std::fs;
+    use std::io;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_dataset() {
+        let lines = format!("# A comment\n\n{}\n  {}  easy one\n", PUZZLE, PUZZLE);
+        let puzzles = Dataset::new(io::Cursor::new(lines), Format::Line)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(puzzles.len(), 2);
+        assert_eq!(puzzles[0].1.to_line(), PUZZLE);
+        assert_eq!(puzzles[0].0.comment, None);
+        assert_eq!(puzzles[1].0.comment.as_deref(), Some("easy one"));
+
+        let csv = format!("quizzes,solutions\n\"{}\",{}\n", PUZZLE, "1".repeat(81));
+        let puzzles = Dataset::new(io::Cursor::new(csv), Format::Csv)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(puzzles.len(), 1);
+        assert_eq!(puzzles[0].1.to_line(), PUZZLE);
+        assert_eq!(puzzles[0].0.comment, Some("1".repeat(81)));
+
+        let csv = format!("\u{feff}\n\nquizzes,solutions\n{}\n{}\n", PUZZLE, PUZZLE);
+        let puzzles = Dataset::new(io::Cursor::new(csv), Format::Csv)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(puzzles.len(), 2);
+
+        let csv = format!("{}\nquizzes\n", PUZZLE);
+        let mut dataset = Dataset::new(io::Cursor::new(csv), Format::Csv);
+        assert!(dataset.next().unwrap().is_ok());
+        assert!(matches!(
+            dataset.next(),
+            Some(Err(DatasetError::Parse { line: 2, .. }))
+        ));
+
+        let sdm = format!("{}\n{} trailing\n", PUZZLE, PUZZLE);
+        let mut dataset = Dataset::new(io::Cursor::new(sdm), Format::Sdm);
+        assert!(dataset.next().unwrap().is_ok());
+        assert_eq!(
+            dataset.next().unwrap(),
+            Err(DatasetError::Parse {
+                line: 2,
+                error: ParseSudokuError::InvalidCharacter {
+                    ch: 't',
+                    offset: 82
+                }
+            })
+        );
+        assert!(dataset.next().is_none());
+
+        assert!(Dataset::open("data/sudoku/missing.list", Format::Line).is_err());
+    }
+
+    #[test]
+    fn test_gzipped_dataset() {
+        let file = fs::File::open("data/sudoku/top1465.list.gz").unwrap();
+        let reader = io::BufReader::new(GzDecoder::new(io::BufReader::new(file)));
+
+        let puzzles = Dataset::new(reader, Format::Line)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(puzzles.len(), 1465);
+        assert!(puzzles.iter().all(|(meta, _)| meta.comment.is_none()));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::sudoku::{parse_cells, ParseSudokuError, Rules, Sudoku};
+
+use core::fmt;
+use core::str;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+impl Parity {
+    pub(super) fn candidates(self) -> u16 {
+        match self {
+            Parity::Even => 0b010101010,
+            Parity::Odd => 0b101010101,
+        }
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Parity::Even => 'e',
+            Parity::Odd => 'o',
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EvenOddSudoku {
+    givens: Sudoku,
+    parities: [[Option<Parity>; 9]; 9],
+}
+
+impl EvenOddSudoku {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn givens(&self) -> &Sudoku {
+        &self.givens
+    }
+
+    pub fn givens_mut(&mut self) -> &mut Sudoku {
+        &mut self.givens
+    }
+
+    pub fn parity(&self, x: usize, y: usize) -> Option<Parity> {
+        self.parities[x][y]
+    }
+
+    pub fn set_parity(&mut self, x: usize, y: usize, parity: Option<Parity>) {
+        self.parities[x][y] = parity;
+    }
+
+    pub fn solve(&self) -> Option<Sudoku> {
+        self.solve_with(&Rules::default())
+    }
+
+    pub fn solve_with(&self, rules: &Rules) -> Option<Sudoku> {
+        let mut constraints = self.givens.constraints(rules).ok()?;
+
+        for (x, y, value) in self.givens.iter() {
+            if let Some(parity) = self.parities[x][y] {
+                // Givens bypass the candidate masks, so they have to be checked here
+                if value != 0 && parity.candidates() & (1 << (value - 1)) == 0 {
+                    return None;
+                }
+
+                constraints.restrict(x, y, parity.candidates());
+            }
+        }
+
+        self.givens
+            .encode(&constraints, rules, 0, |_, _, _, _| ())
+            .solve()
+    }
+}
+
+// The usual 81-character line, with 'e' and 'o' marking empty cells which must be even or odd
+impl str::FromStr for EvenOddSudoku {
+    type Err = ParseSudokuError;
+
+    fn from_str(string: &str) -> Result<Self, ParseSudokuError> {
+        let mut puzzle = Self::new();
+        let mut i = 0;
+
+        // Parity marks are blanked out in place, so parse errors keep their offsets
+        let digits = string
+            .chars()
+            .map(|ch| {
+                let parity = match ch.to_ascii_lowercase() {
+                    'e' => Parity::Even,
+                    'o' => Parity::Odd,
+                    _ => {
+                        if !ch.is_whitespace() {
+                            i += 1;
+                        }
+                        return ch;
+                    }
+                };
+
+                if i < 81 {
+                    puzzle.parities[i % 9][i / 9] = Some(parity);
+                }
+                i += 1;
+                '.'
+            })
+            .collect::<String>();
+
+        let givens = &mut puzzle.givens;
+        parse_cells(&digits, 81, false, |i, value| {
+            givens.set(i % 9, i / 9, value)
+        })?;
+
+        Ok(puzzle)
+    }
+}
+
+impl fmt::Display for EvenOddSudoku {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for y in 0..9 {
+            for x in 0..9 {
+                let ch = match (self.givens.get(x, y), self.parities[x][y]) {
+                    (0, Some(parity)) => parity.symbol(),
+                    (value, _) => Sudoku::symbol(value),
+                };
+                write!(f, "{}", ch)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{EvenOddSudoku, Parity, ParseSudokuError, Sudoku};
+    use core::str::FromStr;
+
+    #[test]
+    fn test_even_odd() {
+        let solution = Sudoku::new().solve().unwrap();
+        let mut puzzle = EvenOddSudoku::new();
+
+        for (x, y, value) in solution.iter() {
+            let parity = match value % 2 {
+                0 => Parity::Even,
+                _ => Parity::Odd,
+            };
+            puzzle.set_parity(x, y, Some(parity));
+        }
+
+        let solved = puzzle.solve().unwrap();
+        assert!(solved.is_solved());
+        assert!(solved
+            .iter()
+            .all(|(x, y, value)| value % 2 == solution.get(x, y) % 2));
+
+        let line = puzzle.to_string();
+        assert_eq!(line.len(), 81);
+        assert!(line.chars().all(|ch| ch == 'e' || ch == 'o'));
+        assert_eq!(EvenOddSudoku::from_str(&line).unwrap(), puzzle);
+
+        let parsed = EvenOddSudoku::from_str(&format!("5E o{}", ".".repeat(78))).unwrap();
+        assert_eq!(parsed.givens().get(0, 0), 5);
+        assert_eq!(parsed.parity(1, 0), Some(Parity::Even));
+        assert_eq!(parsed.parity(2, 0), Some(Parity::Odd));
+        assert_eq!(parsed.to_string(), format!("5eo{}", ".".repeat(78)));
+
+        let mut conflicting = EvenOddSudoku::new();
+        conflicting.givens_mut().set(0, 0, 4);
+        conflicting.set_parity(0, 0, Some(Parity::Odd));
+        assert!(conflicting.solve().is_none());
+
+        // There are only four even digits for the five even cells in the row
+        let mut impossible = EvenOddSudoku::new();
+        for x in 0..5 {
+            impossible.set_parity(x, 0, Some(Parity::Even));
+        }
+        assert!(impossible.solve().is_none());
+
+        assert_eq!(
+            EvenOddSudoku::from_str("ex"),
+            Err(ParseSudokuError::InvalidCharacter { ch: 'x', offset: 1 })
+        );
+    }
+}
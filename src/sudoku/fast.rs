@@ -0,0 +1,257 @@
+use crate::rng::Rng;
+use crate::sudoku::Sudoku;
+
+const ALL: u16 = (1 << 9) - 1;
+
+// A plain 9x9 backtracking solver over per-unit bitmasks. Each node fills in naked and hidden
+// singles before branching on the empty cell with the fewest candidates. It knows nothing about
+// variant rules, but avoids the cost of building an exact cover matrix
+#[derive(Copy, Clone)]
+struct Board {
+    grid: [u8; 81],
+    rows: [u16; 9],
+    columns: [u16; 9],
+    boxes: [u16; 9],
+}
+
+const UNITS: [[u8; 9]; 27] = units();
+
+const fn units() -> [[u8; 9]; 27] {
+    let mut units = [[0; 9]; 27];
+    let mut i = 0;
+
+    while i < 9 {
+        let mut j = 0;
+
+        while j < 9 {
+            units[i][j] = (9 * i + j) as u8;
+            units[9 + i][j] = (9 * j + i) as u8;
+            units[18 + i][j] = (9 * (3 * (i / 3) + j / 3) + 3 * (i % 3) + j % 3) as u8;
+            j += 1;
+        }
+
+        i += 1;
+    }
+
+    units
+}
+
+impl Board {
+    fn new(sudoku: &Sudoku) -> Option<Self> {
+        let mut board = Self {
+            grid: [0; 81],
+            rows: [ALL; 9],
+            columns: [ALL; 9],
+            boxes: [ALL; 9],
+        };
+
+        for (x, y, value) in sudoku.iter().filter(|&(_, _, value)| value != 0) {
+            let cell = 9 * y + x;
+
+            if board.candidates(cell) & (1 << (value - 1)) == 0 {
+                return None;
+            }
+
+            board.place(cell, value);
+        }
+
+        Some(board)
+    }
+
+    fn candidates(&self, cell: usize) -> u16 {
+        let (x, y) = (cell % 9, cell / 9);
+        self.rows[y] & self.columns[x] & self.boxes[3 * (y / 3) + x / 3]
+    }
+
+    fn place(&mut self, cell: usize, value: u8) {
+        let (x, y) = (cell % 9, cell / 9);
+        let bit = !(1 << (value - 1));
+
+        self.grid[cell] = value;
+        self.rows[y] &= bit;
+        self.columns[x] &= bit;
+        self.boxes[3 * (y / 3) + x / 3] &= bit;
+    }
+
+    fn to_sudoku(self) -> Sudoku {
+        let mut sudoku = Sudoku::new();
+
+        for (cell, &value) in self.grid.iter().enumerate() {
+            sudoku.set(cell % 9, cell / 9, value);
+        }
+
+        sudoku
+    }
+
+    // Returns false on a contradiction
+    fn propagate(&mut self) -> bool {
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for cell in 0..81 {
+                if self.grid[cell] != 0 {
+                    continue;
+                }
+
+                let candidates = self.candidates(cell);
+
+                if candidates == 0 {
+                    return false;
+                }
+
+                if candidates & (candidates - 1) == 0 {
+                    self.place(cell, candidates.trailing_zeros() as u8 + 1);
+                    changed = true;
+                }
+            }
+
+            for unit in &UNITS {
+                let mut placed = 0;
+                let mut seen_once = 0;
+                let mut seen_twice = 0;
+
+                for &cell in unit {
+                    match self.grid[cell as usize] {
+                        0 => {
+                            let candidates = self.candidates(cell as usize);
+                            seen_twice |= seen_once & candidates;
+                            seen_once |= candidates;
+                        }
+                        value => placed |= 1 << (value - 1),
+                    }
+                }
+
+                if placed | seen_once != ALL {
+                    return false;
+                }
+
+                let mut singles = seen_once & !seen_twice & !placed;
+
+                while singles != 0 {
+                    let bit = singles & singles.wrapping_neg();
+                    singles ^= bit;
+
+                    let cell = unit
+                        .iter()
+                        .map(|&cell| cell as usize)
+                        .find(|&cell| self.grid[cell] == 0 && self.candidates(cell) & bit != 0);
+
+                    match cell {
+                        Some(cell) => {
+                            self.place(cell, bit.trailing_zeros() as u8 + 1);
+                            changed = true;
+                        }
+                        None => return false,
+                    }
+                }
+            }
+        }
+
+        true
+    }
+
+    // The order callback may permute the candidate values of each branch, such as to pick a random
+    // solution
+    fn search<F: FnMut(&mut [u8])>(mut self, order: &mut F) -> Option<Board> {
+        if !self.propagate() {
+            return None;
+        }
+
+        let mut best = None;
+        let mut best_count = u32::MAX;
+
+        for cell in 0..81 {
+            if self.grid[cell] != 0 {
+                continue;
+            }
+
+            let count = self.candidates(cell).count_ones();
+
+            if count < best_count {
+                best = Some(cell);
+                best_count = count;
+
+                if count == 2 {
+                    break;
+                }
+            }
+        }
+
+        let cell = match best {
+            Some(cell) => cell,
+            None => return Some(self),
+        };
+
+        let mut values = [0; 9];
+        let mut len = 0;
+        let mut candidates = self.candidates(cell);
+
+        while candidates != 0 {
+            values[len] = candidates.trailing_zeros() as u8 + 1;
+            candidates &= candidates - 1;
+            len += 1;
+        }
+
+        order(&mut values[..len]);
+
+        for &value in &values[..len] {
+            let mut child = self;
+            child.place(cell, value);
+
+            if let Some(solved) = child.search(order) {
+                return Some(solved);
+            }
+        }
+
+        None
+    }
+}
+
+impl Sudoku {
+    pub fn solve_fast(&self) -> Option<Sudoku> {
+        let board = Board::new(self)?.search(&mut |_| ())?;
+        Some(board.to_sudoku())
+    }
+
+    pub(super) fn solve_random(&self, rng: &mut impl Rng) -> Option<Sudoku> {
+        let board = Board::new(self)?.search(&mut |values| rng.shuffle(values))?;
+        Some(board.to_sudoku())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::Sudoku;
+    use core::str::FromStr;
+    use flate2::bufread::GzDecoder;
+    use std::fs;
+    use std::io;
+    use std::io::BufRead;
+
+    #[test]
+    fn test_solve_fast() {
+        let file = fs::File::open("data/sudoku/top1465.list.gz").unwrap();
+        let reader = io::BufReader::new(GzDecoder::new(io::BufReader::new(file)));
+
+        for line in reader.lines().take(200) {
+            let sudoku = Sudoku::from_str(line.unwrap().trim()).unwrap();
+            assert_eq!(sudoku.solve_fast(), sudoku.solve());
+        }
+
+        let empty = Sudoku::new().solve_fast().unwrap();
+        assert!(empty.is_solved());
+
+        let mut invalid = Sudoku::new();
+        invalid.set(0, 0, 1);
+        invalid.set(8, 0, 1);
+        assert!(invalid.solve_fast().is_none());
+
+        let unsolvable = Sudoku::from_str(
+            "12345678.........9...............................................................",
+        )
+        .unwrap();
+        assert!(unsolvable.solve_fast().is_none());
+    }
+}
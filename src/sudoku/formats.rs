@@ -0,0 +1,243 @@
+use crate::sudoku::{parse_cells, ParseSudokuError, Sudoku};
+
+use core::fmt::Write;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PuzzleMeta {
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub comment: Option<String>,
+    pub date: Option<String>,
+    pub source: Option<String>,
+    pub difficulty: Option<String>,
+    pub url: Option<String>,
+}
+
+impl PuzzleMeta {
+    fn field(&mut self, code: char) -> Option<&mut Option<String>> {
+        match code {
+            'A' => Some(&mut self.author),
+            'D' => Some(&mut self.description),
+            'C' => Some(&mut self.comment),
+            'B' => Some(&mut self.date),
+            'S' => Some(&mut self.source),
+            'L' => Some(&mut self.difficulty),
+            'U' => Some(&mut self.url),
+            _ => None,
+        }
+    }
+
+    fn fields(&self) -> [(char, &Option<String>); 7] {
+        [
+            ('A', &self.author),
+            ('D', &self.description),
+            ('C', &self.comment),
+            ('B', &self.date),
+            ('S', &self.source),
+            ('L', &self.difficulty),
+            ('U', &self.url),
+        ]
+    }
+}
+
+impl Sudoku {
+    pub fn from_sdk(string: &str) -> Result<(Sudoku, PuzzleMeta), ParseSudokuError> {
+        let mut meta = PuzzleMeta::default();
+
+        // Header and metadata lines are blanked out rather than removed so that error offsets
+        // still point into the original string
+        let grid = blank_lines(string, |line| {
+            let line = line.trim();
+
+            if let Some(rest) = line.strip_prefix('#') {
+                let mut chars = rest.chars();
+                if let Some(field) = chars.next().and_then(|code| meta.field(code)) {
+                    *field = Some(chars.as_str().trim().to_string());
+                }
+                true
+            } else {
+                line.starts_with('[')
+            }
+        });
+
+        let mut sudoku = Sudoku::new();
+        parse_cells(&grid, 81, false, |i, value| sudoku.set(i % 9, i / 9, value))?;
+
+        Ok((sudoku, meta))
+    }
+
+    pub fn to_sdk(&self, meta: &PuzzleMeta) -> String {
+        let mut string = String::new();
+
+        for (code, value) in meta.fields() {
+            if let Some(value) = value {
+                writeln!(&mut string, "#{} {}", code, value).unwrap();
+            }
+        }
+
+        writeln!(&mut string, "{}", self).unwrap();
+        string
+    }
+
+    pub fn from_ss(string: &str) -> Result<Sudoku, ParseSudokuError> {
+        let grid = blank_lines(string, |line| {
+            let line = line.trim();
+            line.starts_with('*') || line.starts_with("|-") || line.starts_with('-')
+        })
+        .replace('|', " ")
+        .replace(['X', 'x'], ".");
+
+        let mut sudoku = Sudoku::new();
+        parse_cells(&grid, 81, false, |i, value| sudoku.set(i % 9, i / 9, value))?;
+
+        Ok(sudoku)
+    }
+
+    pub fn to_ss(&self) -> String {
+        let mut string = String::from("*-----------*\n");
+
+        for y in 0..9 {
+            if y == 3 || y == 6 {
+                string.push_str("|---+---+---|\n");
+            }
+
+            for x in 0..9 {
+                if x % 3 == 0 {
+                    string.push('|');
+                }
+                string.push(Self::symbol(self.grid[x][y]));
+            }
+
+            string.push_str("|\n");
+        }
+
+        string.push_str("*-----------*\n");
+        string
+    }
+
+    pub fn from_sdm(string: &str) -> Result<Vec<Sudoku>, ParseSudokuError> {
+        let mut sudokus = vec![];
+        let mut offset = 0;
+
+        for line in string.split_inclusive('\n') {
+            if !line.trim().is_empty() {
+                let mut sudoku = Sudoku::new();
+
+                parse_cells(line, 81, false, |i, value| sudoku.set(i % 9, i / 9, value)).map_err(
+                    |error| match error {
+                        ParseSudokuError::InvalidCharacter { ch, offset: column } => {
+                            ParseSudokuError::InvalidCharacter {
+                                ch,
+                                offset: offset + column,
+                            }
+                        }
+                        _ => error,
+                    },
+                )?;
+
+                sudokus.push(sudoku);
+            }
+
+            offset += line.len();
+        }
+
+        Ok(sudokus)
+    }
+
+    pub fn to_sdm(sudokus: &[Sudoku]) -> String {
+        let mut string = String::with_capacity(82 * sudokus.len());
+
+        for sudoku in sudokus {
+            string.push_str(&sudoku.to_line());
+            string.push('\n');
+        }
+
+        string
+    }
+}
+
+fn blank_lines<F: FnMut(&str) -> bool>(string: &str, mut blank: F) -> String {
+    let mut blanked = String::with_capacity(string.len());
+
+    for line in string.split_inclusive('\n') {
+        if blank(line) {
+            blanked.extend(line.chars().map(|ch| if ch == '\n' { '\n' } else { ' ' }));
+        } else {
+            blanked.push_str(line);
+        }
+    }
+
+    blanked
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{ParseSudokuError, PuzzleMeta, Sudoku};
+    use core::str::FromStr;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_sdk() {
+        let sudoku = Sudoku::from_str(PUZZLE).unwrap();
+        let meta = PuzzleMeta {
+            author: Some(String::from("Wikipedia")),
+            difficulty: Some(String::from("Easy")),
+            ..PuzzleMeta::default()
+        };
+
+        let sdk = sudoku.to_sdk(&meta);
+        assert!(sdk.starts_with("#A Wikipedia\n#L Easy\n53..7....\n"));
+
+        let (parsed, parsed_meta) = Sudoku::from_sdk(&sdk).unwrap();
+        assert_eq!(parsed.to_line(), PUZZLE);
+        assert_eq!(parsed_meta, meta);
+
+        let (parsed, _) = Sudoku::from_sdk(&format!("[Puzzle]\n{}", sudoku)).unwrap();
+        assert_eq!(parsed.to_line(), PUZZLE);
+
+        assert_eq!(
+            Sudoku::from_sdk(&sdk.replacen('7', "?", 1)).unwrap_err(),
+            ParseSudokuError::InvalidCharacter {
+                ch: '?',
+                offset: 25
+            }
+        );
+    }
+
+    #[test]
+    fn test_ss() {
+        let sudoku = Sudoku::from_str(PUZZLE).unwrap();
+        let ss = sudoku.to_ss();
+        assert_eq!(ss.lines().count(), 13);
+        assert_eq!(ss.lines().nth(1), Some("|53.|.7.|...|"));
+        assert_eq!(Sudoku::from_ss(&ss).unwrap().to_line(), PUZZLE);
+        assert_eq!(
+            Sudoku::from_ss(&ss.replace('.', "X")).unwrap().to_line(),
+            PUZZLE
+        );
+    }
+
+    #[test]
+    fn test_sdm() {
+        let sudoku = Sudoku::from_str(PUZZLE).unwrap();
+        let solution = sudoku.solve().unwrap();
+
+        let sdm = Sudoku::to_sdm(&[sudoku.clone(), solution]);
+        assert_eq!(sdm.lines().count(), 2);
+
+        let parsed = Sudoku::from_sdm(&format!("{}\n", sdm)).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].to_line(), PUZZLE);
+        assert!(parsed[1].is_solved());
+
+        assert_eq!(
+            Sudoku::from_sdm(&format!("{}{}?", sdm, &PUZZLE[1..])).unwrap_err(),
+            ParseSudokuError::InvalidCharacter {
+                ch: '?',
+                offset: 244
+            }
+        );
+    }
+}
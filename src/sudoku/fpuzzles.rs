@@ -0,0 +1,383 @@
+use crate::json::Value;
+use crate::sudoku::{CageError, KillerSudoku, Parity, Regions, RegionsError, Rules, Sudoku};
+
+use core::fmt;
+use core::str;
+
+// Constraints which f-puzzles can express but lynx cannot solve; silently dropping them would
+// produce wrong solutions, so their presence is an error
+const UNSUPPORTED: [&str; 16] = [
+    "arrow",
+    "thermometer",
+    "palindrome",
+    "littlekillersum",
+    "sandwichsum",
+    "difference",
+    "ratio",
+    "xv",
+    "clone",
+    "betweenline",
+    "minimum",
+    "maximum",
+    "quadruple",
+    "disjointgroups",
+    "antixv",
+    "nonconsecutive-diagonal",
+];
+
+const WINDOWS: [(usize, usize); 4] = [(1, 1), (5, 1), (1, 5), (5, 5)];
+
+#[derive(Debug)]
+pub struct FPuzzle {
+    pub puzzle: KillerSudoku,
+    pub rules: Rules,
+    pub title: Option<String>,
+    pub author: Option<String>,
+}
+
+impl FPuzzle {
+    pub fn solve(&self) -> Option<Sudoku> {
+        self.puzzle.solve_with(&self.rules)
+    }
+}
+
+impl str::FromStr for FPuzzle {
+    type Err = FPuzzleError;
+
+    fn from_str(string: &str) -> Result<Self, FPuzzleError> {
+        let json = Value::from_str(string).map_err(|error| FPuzzleError::InvalidJson {
+            offset: error.offset,
+        })?;
+
+        let size = json.get("size").and_then(Value::as_f64).unwrap_or(9.0);
+        if size != 9.0 {
+            return Err(FPuzzleError::UnsupportedSize {
+                size: size as usize,
+            });
+        }
+
+        for (key, value) in json.entries() {
+            if let Some(&constraint) = UNSUPPORTED.iter().find(|&&other| other == key) {
+                if !value.is_empty() {
+                    return Err(FPuzzleError::UnsupportedConstraint { constraint });
+                }
+            }
+        }
+
+        let flag = |key: &str| {
+            json.get(key)
+                .map(|value| !value.is_empty())
+                .unwrap_or(false)
+        };
+
+        let diagonals = match (flag("diagonal+"), flag("diagonal-")) {
+            (true, true) => true,
+            (false, false) => false,
+            _ => {
+                return Err(FPuzzleError::UnsupportedConstraint {
+                    constraint: "single diagonal",
+                })
+            }
+        };
+
+        let rows = json
+            .get("grid")
+            .and_then(Value::as_array)
+            .filter(|rows| rows.len() == 9)
+            .ok_or(FPuzzleError::InvalidGrid)?;
+
+        let mut puzzle = KillerSudoku::new();
+        let mut ids = Regions::boxes().ids;
+        let mut custom_regions = false;
+
+        for (y, row) in rows.iter().enumerate() {
+            let cells = row
+                .as_array()
+                .filter(|cells| cells.len() == 9)
+                .ok_or(FPuzzleError::InvalidGrid)?;
+
+            for (x, cell) in cells.iter().enumerate() {
+                let given = cell.get("given").and_then(Value::as_bool).unwrap_or(false);
+
+                if let (true, Some(value)) = (given, cell.get("value").and_then(Value::as_f64)) {
+                    if !(1.0..=9.0).contains(&value) || value.fract() != 0.0 {
+                        return Err(FPuzzleError::InvalidGrid);
+                    }
+                    puzzle.givens_mut().set(x, y, value as u8);
+                }
+
+                if let Some(region) = cell.get("region").and_then(Value::as_f64) {
+                    if !(0.0..9.0).contains(&region) || region.fract() != 0.0 {
+                        return Err(FPuzzleError::InvalidGrid);
+                    }
+                    custom_regions = true;
+                    ids[x][y] = region as u8;
+                }
+            }
+        }
+
+        let mut rules = Rules::new();
+
+        if custom_regions {
+            let regions =
+                Regions::new(ids).map_err(|error| FPuzzleError::InvalidRegions { error })?;
+            rules = rules.regions(regions);
+        }
+
+        rules = rules
+            .diagonals(diagonals)
+            .anti_knight(flag("antiknight"))
+            .anti_king(flag("antiking"))
+            .non_consecutive(flag("nonconsecutive"));
+
+        // Windoku is the only arrangement of extra regions lynx knows how to solve
+        if let Some(regions) = json.get("extraregion").and_then(Value::as_array) {
+            let mut windows = vec![];
+
+            for region in regions {
+                let mut cells = parse_cells(region)?;
+                cells.sort_unstable();
+                windows.push(cells);
+            }
+            windows.sort_unstable();
+
+            let mut expected = WINDOWS
+                .iter()
+                .map(|&(ox, oy)| {
+                    let mut cells = (0..9).map(|i| (ox + i % 3, oy + i / 3)).collect::<Vec<_>>();
+                    cells.sort_unstable();
+                    cells
+                })
+                .collect::<Vec<_>>();
+            expected.sort_unstable();
+
+            if windows == expected {
+                rules = rules.windoku(true);
+            } else if !windows.is_empty() {
+                return Err(FPuzzleError::UnsupportedConstraint {
+                    constraint: "extraregion",
+                });
+            }
+        }
+
+        if let Some(cages) = json.get("killercage").and_then(Value::as_array) {
+            for cage in cages {
+                let cells = parse_cells(cage)?;
+                let sum = match cage.get("value") {
+                    Some(Value::String(sum)) if !sum.trim().is_empty() => sum
+                        .trim()
+                        .parse::<f64>()
+                        .map_err(|_| FPuzzleError::InvalidGrid)?,
+                    Some(&Value::Number(sum)) => sum,
+                    _ => {
+                        return Err(FPuzzleError::UnsupportedConstraint {
+                            constraint: "killercage without a sum",
+                        })
+                    }
+                };
+
+                // No cage of distinct digits adds up to more than 45
+                if !(1.0..=45.0).contains(&sum) || sum.fract() != 0.0 {
+                    return Err(FPuzzleError::InvalidGrid);
+                }
+
+                puzzle
+                    .add_cage(&cells, sum as u8)
+                    .map_err(|error| FPuzzleError::InvalidCage { error })?;
+            }
+        }
+
+        for (key, parity) in [("odd", Parity::Odd), ("even", Parity::Even)] {
+            if let Some(marks) = json.get(key).and_then(Value::as_array) {
+                for mark in marks {
+                    let (x, y) = parse_cell(mark.get("cell").unwrap_or(&Value::Null))?;
+                    puzzle.set_parity(x, y, Some(parity));
+                }
+            }
+        }
+
+        let text = |key: &str| json.get(key).and_then(Value::as_str).map(String::from);
+
+        Ok(Self {
+            puzzle,
+            rules,
+            title: text("title"),
+            author: text("author"),
+        })
+    }
+}
+
+fn parse_cells(value: &Value) -> Result<Vec<(usize, usize)>, FPuzzleError> {
+    let cells = value
+        .get("cells")
+        .and_then(Value::as_array)
+        .ok_or(FPuzzleError::InvalidGrid)?;
+
+    cells.iter().map(parse_cell).collect()
+}
+
+// Cells are named as in "R1C1", counting rows and then columns from 1
+fn parse_cell(cell: &Value) -> Result<(usize, usize), FPuzzleError> {
+    let name = cell.as_str().unwrap_or("").to_ascii_uppercase();
+    let (row, column) = name
+        .strip_prefix('R')
+        .and_then(|name| name.split_once('C'))
+        .ok_or(FPuzzleError::InvalidGrid)?;
+
+    match (row.parse::<usize>(), column.parse::<usize>()) {
+        (Ok(y @ 1..=9), Ok(x @ 1..=9)) => Ok((x - 1, y - 1)),
+        _ => Err(FPuzzleError::InvalidGrid),
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub enum FPuzzleError {
+    InvalidJson { offset: usize },
+    InvalidGrid,
+    UnsupportedSize { size: usize },
+    UnsupportedConstraint { constraint: &'static str },
+    InvalidRegions { error: RegionsError },
+    InvalidCage { error: CageError },
+}
+
+impl fmt::Display for FPuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            FPuzzleError::InvalidJson { offset } => {
+                write!(f, "Invalid JSON at byte {}", offset)?;
+            }
+            FPuzzleError::InvalidGrid => {
+                write!(f, "Puzzle grid is missing or malformed")?;
+            }
+            FPuzzleError::UnsupportedSize { size } => {
+                write!(f, "Unsupported grid size {} (only 9x9 is supported)", size)?;
+            }
+            FPuzzleError::UnsupportedConstraint { constraint } => {
+                write!(f, "Unsupported constraint: {}", constraint)?;
+            }
+            FPuzzleError::InvalidRegions { error } => {
+                write!(f, "Invalid regions: {}", error)?;
+            }
+            FPuzzleError::InvalidCage { error } => {
+                write!(f, "Invalid killer cage: {}", error)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{FPuzzle, FPuzzleError, Sudoku};
+    use core::fmt::Write;
+    use core::str::FromStr;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    fn grid(sudoku: &Sudoku, region: impl Fn(usize, usize) -> Option<u8>) -> String {
+        let mut json = String::from("[");
+
+        for y in 0..9 {
+            json.push_str(if y == 0 { "[" } else { ",[" });
+            for x in 0..9 {
+                if x > 0 {
+                    json.push(',');
+                }
+                json.push('{');
+                if sudoku.get(x, y) != 0 {
+                    write!(json, r#""value":{},"given":true"#, sudoku.get(x, y)).unwrap();
+                }
+                if let Some(region) = region(x, y) {
+                    if sudoku.get(x, y) != 0 {
+                        json.push(',');
+                    }
+                    write!(json, r#""region":{}"#, region).unwrap();
+                }
+                json.push('}');
+            }
+            json.push(']');
+        }
+
+        json.push(']');
+        json
+    }
+
+    #[test]
+    fn test_fpuzzles() {
+        let sudoku = Sudoku::from_str(PUZZLE).unwrap();
+        let json = format!(
+            r#"{{"size":9,"title":"Classic","author":"Wikipedia","grid":{}}}"#,
+            grid(&sudoku, |_, _| None)
+        );
+
+        let puzzle = FPuzzle::from_str(&json).unwrap();
+        assert_eq!(puzzle.title.as_deref(), Some("Classic"));
+        assert_eq!(puzzle.author.as_deref(), Some("Wikipedia"));
+        assert_eq!(puzzle.puzzle.givens().to_line(), PUZZLE);
+        assert_eq!(
+            puzzle.solve().unwrap().to_line(),
+            sudoku.solve().unwrap().to_line()
+        );
+
+        let empty = Sudoku::new();
+        let json = format!(
+            r#"{{"size":9,"grid":{},"antiking":true,"killercage":[{{"cells":["R1C1","R1C2"],"value":"3"}}]}}"#,
+            grid(&empty, |x, y| Some((3 * (y / 3) + x / 3) as u8))
+        );
+
+        let puzzle = FPuzzle::from_str(&json).unwrap();
+        assert!(puzzle.rules.anti_king && !puzzle.rules.anti_knight);
+
+        let solution = puzzle.solve().unwrap();
+        assert_eq!(solution.get(0, 0) + solution.get(1, 0), 3);
+        assert!(solution.is_solved());
+
+        let json = format!(
+            r#"{{"size":9,"grid":{},"odd":[{{"cell":"R1C1"}}],"even":[{{"cell":"r1c2"}}]}}"#,
+            grid(&empty, |_, _| None)
+        );
+
+        let puzzle = FPuzzle::from_str(&json).unwrap();
+        let solution = puzzle.solve().unwrap();
+        assert_eq!(solution.get(0, 0) % 2, 1);
+        assert_eq!(solution.get(1, 0) % 2, 0);
+
+        for sum in ["12.7", "300", "-5", "0", "\"x\""] {
+            let json = format!(
+                r#"{{"size":9,"grid":{},"killercage":[{{"cells":["R1C1","R1C2"],"value":{}}}]}}"#,
+                grid(&empty, |_, _| None),
+                sum
+            );
+            assert!(matches!(
+                FPuzzle::from_str(&json),
+                Err(FPuzzleError::InvalidGrid)
+            ));
+        }
+
+        for json in [
+            r#"{"size":9,"grid":[],"arrow":[{"lines":[["R1C1","R1C2"]]}]}"#,
+            r#"{"size":9,"grid":[],"diagonal+":true}"#,
+        ] {
+            assert!(matches!(
+                FPuzzle::from_str(json),
+                Err(FPuzzleError::UnsupportedConstraint { .. })
+            ));
+        }
+
+        assert!(matches!(
+            FPuzzle::from_str(r#"{"size":6}"#),
+            Err(FPuzzleError::UnsupportedSize { size: 6 })
+        ));
+        assert!(matches!(
+            FPuzzle::from_str(r#"{"size":9,"grid":[]}"#),
+            Err(FPuzzleError::InvalidGrid)
+        ));
+        assert!(matches!(
+            FPuzzle::from_str("{"),
+            Err(FPuzzleError::InvalidJson { offset: 1 })
+        ));
+    }
+}
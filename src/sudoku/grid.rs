@@ -0,0 +1,372 @@
+use crate::sudoku::{Sudoku, Sudoku16};
+use crate::DLXMatrix;
+
+use core::fmt;
+use core::num::NonZeroU8;
+
+// A digit from 1 to N. Empty cells are None, so an Option<Digit> is still a single byte
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Digit<const N: usize>(NonZeroU8);
+
+impl<const N: usize> Digit<N> {
+    pub fn new(value: u8) -> Option<Self> {
+        if value as usize > N {
+            return None;
+        }
+
+        NonZeroU8::new(value).map(Digit)
+    }
+
+    pub fn get(self) -> u8 {
+        self.0.get()
+    }
+
+    fn bit(self) -> u32 {
+        1 << (self.get() - 1)
+    }
+}
+
+impl<const N: usize> fmt::Display for Digit<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.get())
+    }
+}
+
+// An N by N grid with N boxes, held entirely inline so that checking it and backtracking through it
+// never allocate. N must be no greater than 25; larger sizes fail to compile
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<const N: usize> {
+    cells: [[Option<Digit<N>>; N]; N],
+}
+
+impl<const N: usize> Grid<N> {
+    // The default boxes, as (width, height): as square as the size allows, and wider than they are
+    // tall when they can't be square, so 3x3 for 9x9 grids and 4x3 for 12x12 grids
+    const BOX: (usize, usize) = {
+        assert!(N >= 1 && N <= 25, "grid size must be at most 25");
+
+        let mut height = 1;
+        let mut i = 1;
+        while i * i <= N {
+            if N.is_multiple_of(i) {
+                height = i;
+            }
+            i += 1;
+        }
+        (N / height, height)
+    };
+
+    pub fn new() -> Self {
+        Self {
+            cells: [[None; N]; N],
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, digit: Digit<N>) {
+        self.cells[x][y] = Some(digit);
+    }
+
+    pub fn clear(&mut self, x: usize, y: usize) {
+        self.cells[x][y] = None;
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Digit<N>> {
+        self.cells[x][y]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, Option<Digit<N>>)> + '_ {
+        (0..N).flat_map(move |x| (0..N).map(move |y| (x, y, self.cells[x][y])))
+    }
+
+    pub fn is_valid(&self) -> bool {
+        Search::new(self).is_some()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.iter().all(|(_, _, digit)| digit.is_some()) && self.is_valid()
+    }
+
+    pub fn box_size() -> (usize, usize) {
+        Self::BOX
+    }
+
+    // Backtracks on the empty cell with the fewest candidates, keeping the per-unit masks on the
+    // stack alongside the grid
+    pub fn solve(&self) -> Option<Self> {
+        let mut search = Search::new(self)?;
+
+        if search.search() {
+            Some(search.grid)
+        } else {
+            None
+        }
+    }
+
+    // Solves with boxes of another shape, such as 2x3 rather than 3x2 for a 6x6 grid, which must
+    // tile the grid. This goes through an exact cover encoding rather than backtracking, which
+    // stalls on large, sparse grids where nothing forces the first guesses
+    pub fn solve_with_boxes(&self, width: usize, height: usize) -> Option<Self> {
+        assert!(
+            width * height == N,
+            "{}x{} boxes don't tile a {}x{} grid",
+            width,
+            height,
+            N,
+            N
+        );
+
+        let cells = N * N;
+        let mut matrix = DLXMatrix::new(4 * cells as u32);
+        let mut placements = vec![];
+
+        for (x, y, digit) in self.iter() {
+            let region = height * (y / height) + x / width;
+            let digits = match digit {
+                Some(digit) => digit.get()..=digit.get(),
+                None => 1..=N as u8,
+            };
+
+            for value in digits {
+                let digit = value as usize - 1;
+                matrix.push_row(&[
+                    (N * y + x) as u32,
+                    (cells + N * y + digit) as u32,
+                    (2 * cells + N * x + digit) as u32,
+                    (3 * cells + N * region + digit) as u32,
+                ]);
+                placements.push((x, y, Digit(NonZeroU8::new(value).unwrap())));
+            }
+        }
+
+        let mut solved = *self;
+        for row in matrix.solutions().row_indices().next()? {
+            let (x, y, digit) = placements[row];
+            solved.cells[x][y] = Some(digit);
+        }
+
+        Some(solved)
+    }
+
+    fn region(x: usize, y: usize) -> usize {
+        let (width, height) = Self::BOX;
+        height * (y / height) + x / width
+    }
+}
+
+impl<const N: usize> Default for Grid<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Search<const N: usize> {
+    grid: Grid<N>,
+    rows: [u32; N],
+    columns: [u32; N],
+    regions: [u32; N],
+}
+
+impl<const N: usize> Search<N> {
+    // Returns None if the grid already repeats a digit within a unit
+    fn new(grid: &Grid<N>) -> Option<Self> {
+        let mut search = Self {
+            grid: Grid::new(),
+            rows: [0; N],
+            columns: [0; N],
+            regions: [0; N],
+        };
+
+        for (x, y, digit) in grid.iter() {
+            if let Some(digit) = digit {
+                if search.candidates(x, y) & digit.bit() == 0 {
+                    return None;
+                }
+                search.place(x, y, digit);
+            }
+        }
+
+        Some(search)
+    }
+
+    fn candidates(&self, x: usize, y: usize) -> u32 {
+        let all = (1 << N) - 1;
+        let used = self.rows[y] | self.columns[x] | self.regions[Grid::<N>::region(x, y)];
+        all & !used
+    }
+
+    fn place(&mut self, x: usize, y: usize, digit: Digit<N>) {
+        self.grid.cells[x][y] = Some(digit);
+        self.rows[y] |= digit.bit();
+        self.columns[x] |= digit.bit();
+        self.regions[Grid::<N>::region(x, y)] |= digit.bit();
+    }
+
+    fn remove(&mut self, x: usize, y: usize, digit: Digit<N>) {
+        self.grid.cells[x][y] = None;
+        self.rows[y] &= !digit.bit();
+        self.columns[x] &= !digit.bit();
+        self.regions[Grid::<N>::region(x, y)] &= !digit.bit();
+    }
+
+    fn search(&mut self) -> bool {
+        let mut best = None;
+        let mut best_count = u32::MAX;
+
+        for (x, y, digit) in self.grid.iter() {
+            if digit.is_some() {
+                continue;
+            }
+
+            let count = self.candidates(x, y).count_ones();
+
+            if count < best_count {
+                best = Some((x, y));
+                best_count = count;
+
+                if count <= 1 {
+                    break;
+                }
+            }
+        }
+
+        let (x, y) = match best {
+            Some(cell) => cell,
+            None => return true,
+        };
+
+        let mut candidates = self.candidates(x, y);
+
+        while candidates != 0 {
+            let digit = Digit(NonZeroU8::new(candidates.trailing_zeros() as u8 + 1).unwrap());
+            candidates &= candidates - 1;
+
+            self.place(x, y, digit);
+            if self.search() {
+                return true;
+            }
+            self.remove(x, y, digit);
+        }
+
+        false
+    }
+}
+
+impl From<&Sudoku> for Grid<9> {
+    fn from(sudoku: &Sudoku) -> Self {
+        let mut grid = Self::new();
+        for (x, y, value) in sudoku.iter() {
+            grid.cells[x][y] = Digit::new(value);
+        }
+        grid
+    }
+}
+
+impl From<&Grid<9>> for Sudoku {
+    fn from(grid: &Grid<9>) -> Self {
+        let mut sudoku = Sudoku::new();
+        for (x, y, digit) in grid.iter() {
+            sudoku.set(x, y, digit.map_or(0, Digit::get));
+        }
+        sudoku
+    }
+}
+
+impl From<&Sudoku16> for Grid<16> {
+    fn from(sudoku: &Sudoku16) -> Self {
+        let mut grid = Self::new();
+        for (x, y, value) in sudoku.iter() {
+            grid.cells[x][y] = Digit::new(value);
+        }
+        grid
+    }
+}
+
+impl From<&Grid<16>> for Sudoku16 {
+    fn from(grid: &Grid<16>) -> Self {
+        let mut sudoku = Sudoku16::new();
+        for (x, y, digit) in grid.iter() {
+            sudoku.set(x, y, digit.map_or(0, Digit::get));
+        }
+        sudoku
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{Digit, Grid, Sudoku, Sudoku16};
+    use core::mem;
+    use core::str::FromStr;
+
+    #[test]
+    fn test_grid() {
+        assert_eq!(mem::size_of::<Grid<9>>(), 81);
+        assert_eq!(Digit::<9>::new(0), None);
+        assert_eq!(Digit::<9>::new(10), None);
+        assert_eq!(Digit::<16>::new(16).map(Digit::get), Some(16));
+
+        let sudoku = Sudoku::from_str(
+            "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+        )
+        .unwrap();
+        let grid = Grid::from(&sudoku);
+        assert!(grid.is_valid() && !grid.is_solved());
+
+        let solved = grid.solve().unwrap();
+        assert!(solved.is_solved());
+        assert_eq!(Sudoku::from(&solved), sudoku.solve().unwrap());
+
+        let mut invalid = grid;
+        invalid.set(1, 0, Digit::new(4).unwrap());
+        assert!(!invalid.is_valid());
+        assert_eq!(invalid.solve(), None);
+
+        let mut small = Grid::<4>::new();
+        small.set(0, 0, Digit::new(1).unwrap());
+        small.set(3, 3, Digit::new(2).unwrap());
+        let solved = small.solve().unwrap();
+        assert!(solved.is_solved());
+        assert_eq!(solved.get(3, 3).map(Digit::get), Some(2));
+
+        let mut hex = Sudoku16::new();
+        for i in 0..16 {
+            hex.set(i, i, (i % 4) as u8 + 1);
+        }
+        let solved = Grid::from(&hex).solve().unwrap();
+        assert!(Sudoku16::from(&solved).is_solved());
+        assert_eq!(
+            Grid::<16>::new().solve().map(|grid| grid.is_solved()),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_rectangular_boxes() {
+        assert_eq!(Grid::<6>::box_size(), (3, 2));
+        assert_eq!(Grid::<12>::box_size(), (4, 3));
+        assert_eq!(Grid::<7>::box_size(), (7, 1));
+        assert_eq!(Grid::<9>::box_size(), (3, 3));
+
+        let solved = Grid::<6>::new().solve().unwrap();
+        assert!(solved.is_solved());
+
+        // Columns 0 and 1 of every 2x3 box hold each digit once between them
+        let mut grid = Grid::<6>::new();
+        grid.set(0, 0, Digit::new(1).unwrap());
+        grid.set(1, 2, Digit::new(2).unwrap());
+        let solved = grid.solve_with_boxes(2, 3).unwrap();
+        for (left, top) in [(0, 0), (2, 0), (4, 0), (0, 3), (2, 3), (4, 3)] {
+            let mut digits: Vec<u8> = (0..6)
+                .map(|i| solved.get(left + i % 2, top + i / 2).unwrap().get())
+                .collect();
+            digits.sort_unstable();
+            assert_eq!(digits, vec![1, 2, 3, 4, 5, 6]);
+        }
+
+        // Two 1s in the same 2x3 box, but not in the same 3x2 box
+        let mut grid = Grid::<6>::new();
+        grid.set(0, 0, Digit::new(1).unwrap());
+        grid.set(1, 2, Digit::new(1).unwrap());
+        assert!(grid.solve().is_some());
+        assert_eq!(grid.solve_with_boxes(2, 3), None);
+    }
+}
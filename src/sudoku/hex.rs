@@ -0,0 +1,319 @@
+use crate::sudoku::{ParseSudokuError, Sudoku};
+use crate::DLXMatrix;
+
+use core::fmt;
+use core::str;
+
+// Monster sudoku collections write the sixteen digits either as hexadecimal or as 1 to 9 followed
+// by A to G; blanks are always '.'
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum HexSymbols {
+    ZeroToF,
+    OneToG,
+}
+
+impl HexSymbols {
+    fn symbol(self, value: u8) -> char {
+        let digit = match self {
+            HexSymbols::ZeroToF => value - 1,
+            HexSymbols::OneToG => value,
+        };
+
+        match digit {
+            0..=9 => (b'0' + digit) as char,
+            _ => (b'A' + digit - 10) as char,
+        }
+    }
+
+    fn value(self, ch: char) -> Option<u8> {
+        let digit = match ch.to_ascii_uppercase() {
+            ch @ '0'..='9' => ch as u8 - b'0',
+            ch @ 'A'..='G' => ch as u8 - b'A' + 10,
+            _ => return None,
+        };
+
+        match self {
+            HexSymbols::ZeroToF if digit < 16 => Some(digit + 1),
+            HexSymbols::OneToG if digit > 0 => Some(digit),
+            _ => None,
+        }
+    }
+
+    // A zero can only be ZeroToF and a G only OneToG; puzzles with neither are read as hexadecimal
+    fn detect(string: &str) -> Self {
+        for ch in string.chars() {
+            match ch {
+                '0' => return HexSymbols::ZeroToF,
+                'G' | 'g' => return HexSymbols::OneToG,
+                _ => (),
+            }
+        }
+
+        HexSymbols::ZeroToF
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Sudoku16 {
+    grid: [[u8; 16]; 16],
+}
+
+impl Sudoku16 {
+    pub fn new() -> Self {
+        Self {
+            grid: [[0; 16]; 16],
+        }
+    }
+
+    pub fn from_str_with(string: &str, symbols: HexSymbols) -> Result<Self, ParseSudokuError> {
+        let mut sudoku = Self::new();
+        let mut i = 0;
+
+        for (offset, ch) in string.char_indices() {
+            if ch.is_whitespace() {
+                continue;
+            }
+
+            let value = match (ch, symbols.value(ch)) {
+                ('.', _) => 0,
+                (_, Some(value)) => value,
+                (_, None) => return Err(ParseSudokuError::InvalidCharacter { ch, offset }),
+            };
+
+            if i < 256 {
+                sudoku.set(i % 16, i / 16, value);
+            }
+            i += 1;
+        }
+
+        if i < 256 {
+            return Err(ParseSudokuError::TooShort {
+                len: i,
+                expected: 256,
+            });
+        }
+
+        if i > 256 {
+            return Err(ParseSudokuError::TooLong {
+                len: i,
+                expected: 256,
+            });
+        }
+
+        Ok(sudoku)
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        assert!(value <= 16);
+        self.grid[x][y] = value;
+    }
+
+    pub fn clear(&mut self, x: usize, y: usize) {
+        self.grid[x][y] = 0;
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.grid[x][y]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        (0..16).flat_map(move |x| (0..16).map(move |y| (x, y, self.grid[x][y])))
+    }
+
+    pub fn is_solved(&self) -> bool {
+        let mut seen = [[0u16; 16]; 3];
+
+        for (x, y, value) in self.iter() {
+            if value == 0 {
+                return false;
+            }
+
+            let bit = 1 << (value - 1);
+            let units = [y, x, 4 * (y / 4) + x / 4];
+
+            for (seen, unit) in seen.iter_mut().zip(units) {
+                if seen[unit] & bit != 0 {
+                    return false;
+                }
+                seen[unit] |= bit;
+            }
+        }
+
+        true
+    }
+
+    pub fn solve(&self) -> Option<Sudoku16> {
+        let mut matrix = DLXMatrix::new(4 * 256u16);
+        let mut placements = vec![];
+
+        for (x, y, given) in self.iter() {
+            let values = if given == 0 { 1..=16 } else { given..=given };
+
+            for value in values {
+                let digit = (value - 1) as u16;
+                let region = (4 * (y / 4) + x / 4) as u16;
+
+                matrix.push_row(&[
+                    16 * (y as u16) + (x as u16),
+                    256 + 16 * (y as u16) + digit,
+                    512 + 16 * (x as u16) + digit,
+                    768 + 16 * region + digit,
+                ]);
+                placements.push((x, y, value));
+            }
+        }
+
+        let mut solution = matrix.solve()?;
+        let mut solved = Self::new();
+
+        while let Some(row) = solution.next() {
+            let (x, y, value) = placements[row.index(&solution)];
+            solved.set(x, y, value);
+        }
+
+        Some(solved)
+    }
+
+    pub fn to_line(&self) -> String {
+        self.to_line_with(HexSymbols::ZeroToF)
+    }
+
+    pub fn to_line_with(&self, symbols: HexSymbols) -> String {
+        let mut string = String::with_capacity(256);
+
+        for y in 0..16 {
+            for x in 0..16 {
+                string.push(match self.grid[x][y] {
+                    0 => '.',
+                    value => symbols.symbol(value),
+                });
+            }
+        }
+
+        string
+    }
+}
+
+impl Default for Sudoku16 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl str::FromStr for Sudoku16 {
+    type Err = ParseSudokuError;
+
+    fn from_str(string: &str) -> Result<Self, ParseSudokuError> {
+        Self::from_str_with(string, HexSymbols::detect(string))
+    }
+}
+
+impl fmt::Display for Sudoku16 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let line = self.to_line();
+
+        for y in 0..16 {
+            writeln!(f, "{}", &line[16 * y..16 * (y + 1)])?;
+        }
+
+        Ok(())
+    }
+}
+
+// A puzzle of either size, told apart by the number of cells in the string
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AnySudoku {
+    Classic(Sudoku),
+    Hex(Sudoku16),
+}
+
+impl str::FromStr for AnySudoku {
+    type Err = ParseSudokuError;
+
+    fn from_str(string: &str) -> Result<Self, ParseSudokuError> {
+        let len = string.chars().filter(|ch| !ch.is_whitespace()).count();
+
+        if len <= 81 {
+            Sudoku::from_str(string).map(AnySudoku::Classic)
+        } else {
+            Sudoku16::from_str(string).map(AnySudoku::Hex)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{AnySudoku, HexSymbols, ParseSudokuError, Sudoku16};
+    use core::str::FromStr;
+
+    fn solved() -> Sudoku16 {
+        let mut sudoku = Sudoku16::new();
+
+        for y in 0..16 {
+            for x in 0..16 {
+                let value = (4 * (y % 4) + y / 4 + x) % 16;
+                sudoku.set(x, y, value as u8 + 1);
+            }
+        }
+
+        sudoku
+    }
+
+    #[test]
+    fn test_hex_formats() {
+        let sudoku = solved();
+        assert!(sudoku.is_solved());
+
+        let hex = sudoku.to_line();
+        let one_to_g = sudoku.to_line_with(HexSymbols::OneToG);
+        assert!(hex.starts_with("0123456789ABCDEF4567"));
+        assert!(one_to_g.starts_with("123456789ABCDEFG5678"));
+
+        assert_eq!(Sudoku16::from_str(&hex).unwrap(), sudoku);
+        assert_eq!(Sudoku16::from_str(&one_to_g).unwrap(), sudoku);
+        assert_eq!(
+            Sudoku16::from_str(&one_to_g.to_lowercase()).unwrap(),
+            sudoku
+        );
+        assert_eq!(Sudoku16::from_str(&sudoku.to_string()).unwrap(), sudoku);
+
+        let mut puzzle = sudoku.clone();
+        for i in 0..120 {
+            puzzle.clear((7 * i) % 16, (3 * i + i / 16) % 16);
+        }
+        assert_eq!(puzzle.solve().unwrap(), sudoku);
+
+        // Without a 0 or a G the hexadecimal convention is assumed
+        let line = puzzle.to_line_with(HexSymbols::OneToG).replace('G', ".");
+        let parsed = Sudoku16::from_str(&line).unwrap();
+        assert_eq!(parsed.to_line(), line);
+        assert_ne!(
+            parsed,
+            Sudoku16::from_str_with(&line, HexSymbols::OneToG).unwrap()
+        );
+
+        assert_eq!(
+            Sudoku16::from_str(&hex[..255]),
+            Err(ParseSudokuError::TooShort {
+                len: 255,
+                expected: 256
+            })
+        );
+        assert_eq!(
+            Sudoku16::from_str_with(&hex, HexSymbols::OneToG),
+            Err(ParseSudokuError::InvalidCharacter { ch: '0', offset: 0 })
+        );
+
+        let classic =
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        assert!(matches!(
+            AnySudoku::from_str(classic),
+            Ok(AnySudoku::Classic(_))
+        ));
+        assert_eq!(
+            AnySudoku::from_str(&hex),
+            Ok(AnySudoku::Hex(sudoku.clone()))
+        );
+        assert!(AnySudoku::from_str(&hex[..100]).is_err());
+    }
+}
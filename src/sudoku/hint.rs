@@ -0,0 +1,105 @@
+use crate::sudoku::logic::{LogicalSolver, Technique};
+use crate::sudoku::Sudoku;
+
+use core::fmt;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Reason {
+    Technique(Technique),
+    Search,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Reason::Technique(technique) => write!(f, "{}", technique),
+            Reason::Search => write!(f, "search"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Hint {
+    pub x: usize,
+    pub y: usize,
+    pub value: u8,
+    pub reason: Reason,
+}
+
+impl Sudoku {
+    pub fn hint(&self) -> Option<Hint> {
+        let mut solver = LogicalSolver::new(self)?;
+
+        for step in solver.by_ref() {
+            if let Some(&(x, y, value)) = step.placements().first() {
+                return Some(Hint {
+                    x,
+                    y,
+                    value,
+                    reason: Reason::Technique(step.technique()),
+                });
+            }
+        }
+
+        // Without a logical deduction, a cell is only forced if it takes the same value in every
+        // solution, so fall back to the search and insist on a unique solution
+        let mut solutions = self.solve_all();
+        let solution = solutions.next()?;
+        if solutions.next().is_some() {
+            return None;
+        }
+
+        let (x, y, _) = solver
+            .sudoku()
+            .iter()
+            .filter(|&(_, _, value)| value == 0)
+            .min_by_key(|&(x, y, _)| solver.candidates(x, y).count())?;
+
+        Some(Hint {
+            x,
+            y,
+            value: solution.get(x, y),
+            reason: Reason::Search,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{Reason, Sudoku, Technique};
+    use core::str::FromStr;
+
+    #[test]
+    fn test_hint() {
+        let easy = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let hint = easy.hint().unwrap();
+        assert_eq!((hint.x, hint.y, hint.value), (6, 2, 5));
+        assert_eq!(hint.reason, Reason::Technique(Technique::HiddenSingle));
+
+        let solution = easy.solve().unwrap();
+        assert!(solution.hint().is_none());
+
+        let extreme = Sudoku::from_str(
+            "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+        )
+        .unwrap();
+        let solution = extreme.solve().unwrap();
+
+        let mut sudoku = extreme.clone();
+        let mut searched = false;
+
+        while let Some(hint) = sudoku.hint() {
+            assert_eq!(sudoku.get(hint.x, hint.y), 0);
+            assert_eq!(hint.value, solution.get(hint.x, hint.y));
+            searched |= hint.reason == Reason::Search;
+            sudoku.set(hint.x, hint.y, hint.value);
+        }
+
+        assert!(searched);
+        assert_eq!(sudoku.to_string(), solution.to_string());
+        assert!(Sudoku::new().hint().is_none());
+    }
+}
@@ -0,0 +1,284 @@
+use crate::sudoku::{Parity, Rules, Sudoku};
+
+use core::fmt;
+
+#[derive(Clone, Debug)]
+pub struct Cage {
+    cells: Vec<(usize, usize)>,
+    sum: u8,
+}
+
+impl Cage {
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    pub fn sum(&self) -> u8 {
+        self.sum
+    }
+
+    fn combinations(&self) -> impl Iterator<Item = u16> + '_ {
+        (0u16..(1 << 9)).filter(move |&digits| {
+            let sum = (0..9)
+                .filter(|digit| digits & (1 << digit) != 0)
+                .map(|digit| digit + 1)
+                .sum::<u16>();
+            digits.count_ones() as usize == self.cells.len() && sum == self.sum as u16
+        })
+    }
+}
+
+#[derive(Default, Debug)]
+pub struct KillerSudoku {
+    givens: Sudoku,
+    cages: Vec<Cage>,
+    cage_ids: [[Option<u8>; 9]; 9],
+    parities: [[Option<Parity>; 9]; 9],
+}
+
+impl KillerSudoku {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn givens(&self) -> &Sudoku {
+        &self.givens
+    }
+
+    pub fn givens_mut(&mut self) -> &mut Sudoku {
+        &mut self.givens
+    }
+
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    // Parity marks, as in EvenOddSudoku, which f-puzzles allows alongside cages
+    pub fn parity(&self, x: usize, y: usize) -> Option<Parity> {
+        self.parities[x][y]
+    }
+
+    pub fn set_parity(&mut self, x: usize, y: usize, parity: Option<Parity>) {
+        self.parities[x][y] = parity;
+    }
+
+    pub fn add_cage(&mut self, cells: &[(usize, usize)], sum: u8) -> Result<(), CageError> {
+        if cells.is_empty() || cells.len() > 9 {
+            return Err(CageError::InvalidSize { size: cells.len() });
+        }
+
+        for (i, &(x, y)) in cells.iter().enumerate() {
+            if x >= 9 || y >= 9 {
+                return Err(CageError::OutOfBounds { x, y });
+            }
+
+            if self.cage_ids[x][y].is_some() || cells[..i].contains(&(x, y)) {
+                return Err(CageError::Overlapping { x, y });
+            }
+        }
+
+        let cage = Cage {
+            cells: cells.to_vec(),
+            sum,
+        };
+
+        if cage.combinations().next().is_none() {
+            return Err(CageError::ImpossibleSum {
+                size: cells.len(),
+                sum,
+            });
+        }
+
+        for &(x, y) in cells {
+            self.cage_ids[x][y] = Some(self.cages.len() as u8);
+        }
+        self.cages.push(cage);
+
+        Ok(())
+    }
+
+    pub fn solve(&self) -> Option<Sudoku> {
+        self.solve_with(&Rules::default())
+    }
+
+    pub fn solve_with(&self, rules: &Rules) -> Option<Sudoku> {
+        let mut constraints = self.givens.constraints(rules).ok()?;
+
+        for (x, y, value) in self.givens.iter() {
+            if let Some(parity) = self.parities[x][y] {
+                if value != 0 && parity.candidates() & (1 << (value - 1)) == 0 {
+                    return None;
+                }
+
+                constraints.restrict(x, y, parity.candidates());
+            }
+        }
+
+        // Each cage owns ten columns: one per digit, covered either by the cell holding that digit
+        // or by the cage's chosen combination (when the digit is absent), plus one column selecting
+        // exactly one combination
+        let mut encoding = self.givens.encode(
+            &constraints,
+            rules,
+            10 * self.cages.len() as u16,
+            |x, y, value, row| {
+                if let Some(cage) = self.cage_ids[x][y] {
+                    row.push(324 + 10 * cage as u16 + (value - 1) as u16);
+                }
+            },
+        );
+
+        for (i, cage) in self.cages.iter().enumerate() {
+            let base = 324 + 10 * i as u16;
+
+            for digits in cage.combinations() {
+                let mut row = vec![base + 9];
+                row.extend(
+                    (0..9)
+                        .filter(|digit| digits & (1 << digit) == 0)
+                        .map(|digit| base + digit),
+                );
+                encoding.matrix.push_row(&row);
+            }
+        }
+
+        encoding.solve()
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub enum CageError {
+    InvalidSize { size: usize },
+    OutOfBounds { x: usize, y: usize },
+    Overlapping { x: usize, y: usize },
+    ImpossibleSum { size: usize, sum: u8 },
+}
+
+impl fmt::Display for CageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            CageError::InvalidSize { size } => {
+                write!(f, "Cage has {} cell(s) (must have 1 to 9 cells)", size)?;
+            }
+            CageError::OutOfBounds { x, y } => {
+                write!(f, "Cage cell ({}, {}) is outside the grid", x, y)?;
+            }
+            CageError::Overlapping { x, y } => {
+                write!(f, "Cell ({}, {}) belongs to more than one cage", x, y)?;
+            }
+            CageError::ImpossibleSum { size, sum } => {
+                write!(f, "No set of {} distinct digit(s) adds up to {}", size, sum)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{CageError, KillerSudoku, Parity, Rules, Sudoku};
+
+    fn validate_solution(killer: &KillerSudoku, solution: &Sudoku) {
+        assert!(solution.constraints(&Rules::default()).is_ok());
+        assert!(solution.iter().all(|(_, _, value)| value != 0));
+
+        for cage in killer.cages() {
+            let mut seen = 0u16;
+            let mut sum = 0;
+
+            for &(x, y) in cage.cells() {
+                let value = solution.get(x, y);
+                assert!(seen & (1 << value) == 0);
+                seen |= 1 << value;
+                sum += value;
+            }
+
+            assert_eq!(sum, cage.sum());
+        }
+    }
+
+    #[test]
+    fn test_killer() {
+        let grid = Sudoku::new().solve().unwrap();
+
+        let mut killer = KillerSudoku::new();
+        for y in 0..9 {
+            for x in (0..9).step_by(3) {
+                let cells = [(x, y), (x + 1, y), (x + 2, y)];
+                let sum = cells.iter().map(|&(x, y)| grid.get(x, y)).sum();
+                killer.add_cage(&cells, sum).unwrap();
+            }
+        }
+
+        let solution = killer.solve().unwrap();
+        validate_solution(&killer, &solution);
+    }
+
+    #[test]
+    fn test_killer_with_givens() {
+        let mut killer = KillerSudoku::new();
+        killer.add_cage(&[(0, 0), (1, 0)], 3).unwrap();
+        killer.add_cage(&[(0, 1), (0, 2), (0, 3)], 24).unwrap();
+        killer.add_cage(&[(4, 4)], 5).unwrap();
+        killer.givens_mut().set(1, 0, 2);
+
+        let solution = killer.solve().unwrap();
+        validate_solution(&killer, &solution);
+        assert_eq!(solution.get(0, 0), 1);
+        assert_eq!(solution.get(4, 4), 5);
+    }
+
+    #[test]
+    fn test_killer_with_parities() {
+        let mut killer = KillerSudoku::new();
+        killer.add_cage(&[(0, 0), (1, 0)], 10).unwrap();
+        killer.set_parity(0, 0, Some(Parity::Even));
+        killer.set_parity(1, 0, Some(Parity::Even));
+        killer.set_parity(2, 0, Some(Parity::Odd));
+
+        let solution = killer.solve().unwrap();
+        validate_solution(&killer, &solution);
+        assert_eq!((solution.get(0, 0) % 2, solution.get(1, 0) % 2), (0, 0));
+        assert_eq!(solution.get(2, 0) % 2, 1);
+
+        killer.givens_mut().set(2, 0, 4);
+        assert!(killer.solve().is_none());
+    }
+
+    #[test]
+    fn test_killer_unsolvable() {
+        let mut killer = KillerSudoku::new();
+        killer.add_cage(&[(0, 0), (1, 0)], 3).unwrap();
+        killer.givens_mut().set(0, 0, 3);
+        assert!(killer.solve().is_none());
+    }
+
+    #[test]
+    fn test_cage_errors() {
+        let mut killer = KillerSudoku::new();
+        killer.add_cage(&[(0, 0), (1, 0)], 10).unwrap();
+
+        assert!(matches!(
+            killer.add_cage(&[], 1),
+            Err(CageError::InvalidSize { size: 0 })
+        ));
+        assert!(matches!(
+            killer.add_cage(&[(9, 0)], 1),
+            Err(CageError::OutOfBounds { x: 9, y: 0 })
+        ));
+        assert!(matches!(
+            killer.add_cage(&[(1, 0), (2, 0)], 5),
+            Err(CageError::Overlapping { x: 1, y: 0 })
+        ));
+        assert!(matches!(
+            killer.add_cage(&[(3, 3), (3, 3)], 5),
+            Err(CageError::Overlapping { x: 3, y: 3 })
+        ));
+        assert!(matches!(
+            killer.add_cage(&[(2, 0), (3, 0)], 18),
+            Err(CageError::ImpossibleSum { size: 2, sum: 18 })
+        ));
+    }
+}
@@ -0,0 +1,1011 @@
+use crate::sudoku::{unit_cells, CandidateIterator, Sudoku};
+
+use core::fmt;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    HiddenSingle,
+    NakedSingle,
+    PointingCandidates,
+    ClaimingCandidates,
+    NakedPair,
+    HiddenPair,
+    NakedTriple,
+    HiddenTriple,
+    XWing,
+    Swordfish,
+    XYWing,
+    SimpleColoring,
+}
+
+impl Technique {
+    pub const ALL: [Technique; 12] = [
+        Technique::HiddenSingle,
+        Technique::NakedSingle,
+        Technique::PointingCandidates,
+        Technique::ClaimingCandidates,
+        Technique::NakedPair,
+        Technique::HiddenPair,
+        Technique::NakedTriple,
+        Technique::HiddenTriple,
+        Technique::XWing,
+        Technique::Swordfish,
+        Technique::XYWing,
+        Technique::SimpleColoring,
+    ];
+
+    pub fn weight(self) -> u32 {
+        match self {
+            Technique::HiddenSingle => 10,
+            Technique::NakedSingle => 15,
+            Technique::PointingCandidates => 20,
+            Technique::ClaimingCandidates => 22,
+            Technique::NakedPair => 30,
+            Technique::HiddenPair => 34,
+            Technique::NakedTriple => 36,
+            Technique::HiddenTriple => 40,
+            Technique::XWing => 42,
+            Technique::Swordfish => 50,
+            Technique::XYWing => 55,
+            Technique::SimpleColoring => 60,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Technique::HiddenSingle => "hidden single",
+            Technique::NakedSingle => "naked single",
+            Technique::PointingCandidates => "pointing candidates",
+            Technique::ClaimingCandidates => "claiming candidates",
+            Technique::NakedPair => "naked pair",
+            Technique::HiddenPair => "hidden pair",
+            Technique::NakedTriple => "naked triple",
+            Technique::HiddenTriple => "hidden triple",
+            Technique::XWing => "X-wing",
+            Technique::Swordfish => "swordfish",
+            Technique::XYWing => "XY-wing",
+            Technique::SimpleColoring => "simple coloring",
+        }
+    }
+
+    fn find(self, grid: &Grid) -> Option<Step> {
+        match self {
+            Technique::HiddenSingle => hidden_single(grid),
+            Technique::NakedSingle => naked_single(grid),
+            Technique::PointingCandidates => pointing_candidates(grid),
+            Technique::ClaimingCandidates => claiming_candidates(grid),
+            Technique::NakedPair => naked_subset(grid, 2),
+            Technique::HiddenPair => hidden_subset(grid, 2),
+            Technique::NakedTriple => naked_subset(grid, 3),
+            Technique::HiddenTriple => hidden_subset(grid, 3),
+            Technique::XWing => fish(grid, 2),
+            Technique::Swordfish => fish(grid, 3),
+            Technique::XYWing => xy_wing(grid),
+            Technique::SimpleColoring => simple_coloring(grid),
+        }
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Step {
+    technique: Technique,
+    cells: Vec<(usize, usize)>,
+    digits: Vec<u8>,
+    units: Vec<usize>,
+    placements: Vec<(usize, usize, u8)>,
+    eliminations: Vec<(usize, usize, u8)>,
+}
+
+impl Step {
+    fn new(
+        technique: Technique,
+        cells: Vec<(usize, usize)>,
+        digits: Vec<u8>,
+        units: Vec<usize>,
+    ) -> Self {
+        Self {
+            technique,
+            cells,
+            digits,
+            units,
+            placements: vec![],
+            eliminations: vec![],
+        }
+    }
+
+    fn place(mut self, x: usize, y: usize, value: u8) -> Self {
+        self.placements.push((x, y, value));
+        self
+    }
+
+    fn eliminate(mut self, eliminations: Vec<(usize, usize, u8)>) -> Option<Self> {
+        if eliminations.is_empty() {
+            None
+        } else {
+            self.eliminations = eliminations;
+            Some(self)
+        }
+    }
+
+    pub fn technique(&self) -> Technique {
+        self.technique
+    }
+
+    pub fn cells(&self) -> &[(usize, usize)] {
+        &self.cells
+    }
+
+    pub fn digits(&self) -> &[u8] {
+        &self.digits
+    }
+
+    pub fn placements(&self) -> &[(usize, usize, u8)] {
+        &self.placements
+    }
+
+    pub fn eliminations(&self) -> &[(usize, usize, u8)] {
+        &self.eliminations
+    }
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let cells = List(self.cells.iter().map(|&(x, y)| CellName(x, y)));
+        let digits = List(self.digits.iter());
+        let units = List(self.units.iter().map(|&unit| UnitName(unit)));
+
+        write!(f, "{}: ", self.technique)?;
+
+        match self.technique {
+            Technique::HiddenSingle => {
+                write!(f, "{} can only go in {} within {}", digits, cells, units)?;
+            }
+            Technique::NakedSingle => {
+                write!(f, "{} can only be {}", cells, digits)?;
+            }
+            Technique::PointingCandidates | Technique::ClaimingCandidates => {
+                let (from, to) = (UnitName(self.units[0]), UnitName(self.units[1]));
+                write!(f, "in {}, {} is confined to {}", from, digits, to)?;
+            }
+            Technique::NakedPair | Technique::NakedTriple => {
+                write!(f, "{} can only hold {} within {}", cells, digits, units)?;
+            }
+            Technique::HiddenPair | Technique::HiddenTriple => {
+                write!(f, "{} can only go in {} within {}", digits, cells, units)?;
+            }
+            Technique::XWing | Technique::Swordfish => {
+                let n = self.units.len() / 2;
+                let base = List(self.units[..n].iter().map(|&unit| UnitName(unit)));
+                let cover = List(self.units[n..].iter().map(|&unit| UnitName(unit)));
+                write!(f, "{} in {} is confined to {}", digits, base, cover)?;
+            }
+            Technique::XYWing => {
+                let (pivot, first, second) = (self.cells[0], self.cells[1], self.cells[2]);
+                write!(
+                    f,
+                    "pivot {} with wings {} and {} forces {} into one of the wings",
+                    CellName(pivot.0, pivot.1),
+                    CellName(first.0, first.1),
+                    CellName(second.0, second.1),
+                    digits
+                )?;
+            }
+            Technique::SimpleColoring => {
+                write!(
+                    f,
+                    "{} alternates along the conjugate pairs {}",
+                    digits, cells
+                )?;
+            }
+        }
+
+        if !self.eliminations.is_empty() {
+            let eliminations = List(
+                self.eliminations
+                    .iter()
+                    .map(|&(x, y, value)| Elimination(x, y, value)),
+            );
+            write!(f, ", eliminating {}", eliminations)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct CellName(usize, usize);
+
+impl fmt::Display for CellName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "r{}c{}", self.1 + 1, self.0 + 1)
+    }
+}
+
+struct UnitName(usize);
+
+impl fmt::Display for UnitName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self.0 {
+            0..=8 => write!(f, "row {}", self.0 + 1),
+            9..=17 => write!(f, "column {}", self.0 - 8),
+            _ => write!(f, "box {}", self.0 - 17),
+        }
+    }
+}
+
+struct Elimination(usize, usize, u8);
+
+impl fmt::Display for Elimination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(f, "{} from {}", self.2, CellName(self.0, self.1))
+    }
+}
+
+struct List<I>(I);
+
+impl<I> fmt::Display for List<I>
+where
+    I: Iterator + Clone,
+    I::Item: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (i, item) in self.0.clone().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", item)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct LogicalSolver {
+    grid: Grid,
+}
+
+impl LogicalSolver {
+    pub fn new(sudoku: &Sudoku) -> Option<Self> {
+        Some(Self {
+            grid: Grid::new(sudoku)?,
+        })
+    }
+
+    pub fn solve(&mut self) -> Vec<Step> {
+        self.collect()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.grid.is_solved()
+    }
+
+    pub fn sudoku(&self) -> Sudoku {
+        self.grid.to_sudoku()
+    }
+
+    pub fn candidates(&self, x: usize, y: usize) -> impl Iterator<Item = u8> {
+        CandidateIterator(self.grid.candidates(x, y))
+    }
+}
+
+impl Iterator for LogicalSolver {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Step> {
+        let step = self.grid.next_step()?;
+        self.grid.apply(&step);
+        Some(step)
+    }
+}
+
+struct Grid {
+    values: [[u8; 9]; 9],
+    candidates: [[u16; 9]; 9],
+}
+
+impl Grid {
+    fn new(sudoku: &Sudoku) -> Option<Self> {
+        let mut grid = Self {
+            values: [[0; 9]; 9],
+            candidates: [[(1 << 9) - 1; 9]; 9],
+        };
+
+        for (x, y, value) in sudoku.iter().filter(|&(_, _, value)| value != 0) {
+            if !grid.has_candidate(x, y, value) {
+                return None;
+            }
+            grid.place(x, y, value);
+        }
+
+        Some(grid)
+    }
+
+    fn get(&self, x: usize, y: usize) -> u8 {
+        self.values[x][y]
+    }
+
+    fn candidates(&self, x: usize, y: usize) -> u16 {
+        self.candidates[x][y]
+    }
+
+    fn is_solved(&self) -> bool {
+        self.values.iter().flatten().all(|&value| value != 0)
+    }
+
+    fn to_sudoku(&self) -> Sudoku {
+        Sudoku { grid: self.values }
+    }
+
+    fn next_step(&self) -> Option<Step> {
+        Technique::ALL
+            .iter()
+            .find_map(|technique| technique.find(self))
+    }
+
+    fn apply(&mut self, step: &Step) {
+        for &(x, y, value) in &step.placements {
+            self.place(x, y, value);
+        }
+
+        for &(x, y, value) in &step.eliminations {
+            self.candidates[x][y] &= !(1 << (value - 1));
+        }
+    }
+
+    fn place(&mut self, x: usize, y: usize, value: u8) {
+        self.values[x][y] = value;
+        self.candidates[x][y] = 0;
+
+        for (x2, y2) in peers(x, y) {
+            self.candidates[x2][y2] &= !(1 << (value - 1));
+        }
+    }
+
+    fn has_candidate(&self, x: usize, y: usize, value: u8) -> bool {
+        self.candidates[x][y] & (1 << (value - 1)) != 0
+    }
+
+    fn empty_cells(&self, unit: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        unit_cells(unit)
+            .into_iter()
+            .filter(|&(x, y)| self.values[x][y] == 0)
+    }
+
+    fn positions(&self, unit: usize, value: u8) -> impl Iterator<Item = (usize, usize)> + '_ {
+        unit_cells(unit)
+            .into_iter()
+            .filter(move |&(x, y)| self.has_candidate(x, y, value))
+    }
+
+    fn unit_contains(&self, unit: usize, value: u8) -> bool {
+        unit_cells(unit)
+            .iter()
+            .any(|&(x, y)| self.values[x][y] == value)
+    }
+}
+
+// Units 0 to 8 are rows, 9 to 17 are columns, and 18 to 26 are boxes
+fn box_id(x: usize, y: usize) -> usize {
+    3 * (y / 3) + x / 3
+}
+
+fn sees((x1, y1): (usize, usize), (x2, y2): (usize, usize)) -> bool {
+    (x1, y1) != (x2, y2) && (x1 == x2 || y1 == y2 || box_id(x1, y1) == box_id(x2, y2))
+}
+
+fn peers(x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..9)
+        .flat_map(|x2| (0..9).map(move |y2| (x2, y2)))
+        .filter(move |&cell| sees((x, y), cell))
+}
+
+fn combinations(items: &[usize], n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+
+    let mut result = vec![];
+
+    for (i, &item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], n - 1) {
+            rest.insert(0, item);
+            result.push(rest);
+        }
+    }
+
+    result
+}
+
+fn hidden_single(grid: &Grid) -> Option<Step> {
+    for unit in 0..27 {
+        for value in 1..=9 {
+            if grid.unit_contains(unit, value) {
+                continue;
+            }
+
+            let mut positions = grid.positions(unit, value);
+
+            if let (Some((x, y)), None) = (positions.next(), positions.next()) {
+                let step = Step::new(
+                    Technique::HiddenSingle,
+                    vec![(x, y)],
+                    vec![value],
+                    vec![unit],
+                );
+                return Some(step.place(x, y, value));
+            }
+        }
+    }
+
+    None
+}
+
+fn naked_single(grid: &Grid) -> Option<Step> {
+    for x in 0..9 {
+        for y in 0..9 {
+            let candidates = grid.candidates(x, y);
+
+            if grid.get(x, y) == 0 && candidates.count_ones() == 1 {
+                let value = (candidates.trailing_zeros() + 1) as u8;
+                let step = Step::new(Technique::NakedSingle, vec![(x, y)], vec![value], vec![]);
+                return Some(step.place(x, y, value));
+            }
+        }
+    }
+
+    None
+}
+
+fn pointing_candidates(grid: &Grid) -> Option<Step> {
+    for b in 0..9 {
+        for value in 1..=9 {
+            let positions = grid.positions(18 + b, value).collect::<Vec<_>>();
+
+            if positions.len() < 2 {
+                continue;
+            }
+
+            let (x0, y0) = positions[0];
+
+            let line = if positions.iter().all(|&(_, y)| y == y0) {
+                y0
+            } else if positions.iter().all(|&(x, _)| x == x0) {
+                9 + x0
+            } else {
+                continue;
+            };
+
+            let eliminations = grid
+                .positions(line, value)
+                .filter(|&(x, y)| box_id(x, y) != b)
+                .map(|(x, y)| (x, y, value))
+                .collect();
+
+            let step = Step::new(
+                Technique::PointingCandidates,
+                positions,
+                vec![value],
+                vec![18 + b, line],
+            );
+
+            if let Some(step) = step.eliminate(eliminations) {
+                return Some(step);
+            }
+        }
+    }
+
+    None
+}
+
+fn claiming_candidates(grid: &Grid) -> Option<Step> {
+    for line in 0..18 {
+        for value in 1..=9 {
+            let positions = grid.positions(line, value).collect::<Vec<_>>();
+
+            if positions.len() < 2 {
+                continue;
+            }
+
+            let b = box_id(positions[0].0, positions[0].1);
+
+            if positions.iter().any(|&(x, y)| box_id(x, y) != b) {
+                continue;
+            }
+
+            let eliminations = grid
+                .positions(18 + b, value)
+                .filter(|cell| !positions.contains(cell))
+                .map(|(x, y)| (x, y, value))
+                .collect();
+
+            let step = Step::new(
+                Technique::ClaimingCandidates,
+                positions,
+                vec![value],
+                vec![line, 18 + b],
+            );
+
+            if let Some(step) = step.eliminate(eliminations) {
+                return Some(step);
+            }
+        }
+    }
+
+    None
+}
+
+fn naked_subset(grid: &Grid, n: usize) -> Option<Step> {
+    let technique = if n == 2 {
+        Technique::NakedPair
+    } else {
+        Technique::NakedTriple
+    };
+
+    for unit in 0..27 {
+        let cells = grid.empty_cells(unit).collect::<Vec<_>>();
+        let indices = (0..cells.len()).collect::<Vec<_>>();
+
+        for subset in combinations(&indices, n) {
+            let union = subset.iter().fold(0, |union, &i| {
+                union | grid.candidates(cells[i].0, cells[i].1)
+            });
+
+            if union.count_ones() as usize != n {
+                continue;
+            }
+
+            let eliminations = cells
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !subset.contains(i))
+                .flat_map(|(_, &(x, y))| {
+                    CandidateIterator(grid.candidates(x, y) & union).map(move |value| (x, y, value))
+                })
+                .collect();
+
+            let step = Step::new(
+                technique,
+                subset.iter().map(|&i| cells[i]).collect(),
+                CandidateIterator(union).collect(),
+                vec![unit],
+            );
+
+            if let Some(step) = step.eliminate(eliminations) {
+                return Some(step);
+            }
+        }
+    }
+
+    None
+}
+
+fn hidden_subset(grid: &Grid, n: usize) -> Option<Step> {
+    let technique = if n == 2 {
+        Technique::HiddenPair
+    } else {
+        Technique::HiddenTriple
+    };
+
+    for unit in 0..27 {
+        let cells = unit_cells(unit);
+        let values = (1..=9)
+            .filter(|&value| !grid.unit_contains(unit, value as u8))
+            .collect::<Vec<_>>();
+
+        for subset in combinations(&values, n) {
+            let mut positions = 0u16;
+            let mut digits = 0u16;
+            let mut missing = false;
+
+            for &value in &subset {
+                let value_positions = cells
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &(x, y))| grid.has_candidate(x, y, value as u8))
+                    .fold(0u16, |mask, (i, _)| mask | 1 << i);
+
+                missing |= value_positions == 0;
+                positions |= value_positions;
+                digits |= 1 << (value - 1);
+            }
+
+            if missing || positions.count_ones() as usize != n {
+                continue;
+            }
+
+            let eliminations = CandidateIterator(positions)
+                .map(|i| cells[(i - 1) as usize])
+                .flat_map(|(x, y)| {
+                    CandidateIterator(grid.candidates(x, y) & !digits)
+                        .map(move |value| (x, y, value))
+                })
+                .collect();
+
+            let step = Step::new(
+                technique,
+                CandidateIterator(positions)
+                    .map(|i| cells[(i - 1) as usize])
+                    .collect(),
+                subset.iter().map(|&value| value as u8).collect(),
+                vec![unit],
+            );
+
+            if let Some(step) = step.eliminate(eliminations) {
+                return Some(step);
+            }
+        }
+    }
+
+    None
+}
+
+fn fish(grid: &Grid, n: usize) -> Option<Step> {
+    let technique = if n == 2 {
+        Technique::XWing
+    } else {
+        Technique::Swordfish
+    };
+
+    // Base lines are rows and cover lines are columns, then the reverse
+    for base in [0, 9] {
+        let cover = 9 - base;
+
+        for value in 1..=9 {
+            let masks = (0..9)
+                .map(|line| {
+                    grid.positions(base + line, value)
+                        .fold(0u16, |mask, (x, y)| {
+                            mask | 1 << if base == 0 { x } else { y }
+                        })
+                })
+                .collect::<Vec<_>>();
+
+            let lines = (0..9)
+                .filter(|&line| (2..=n).contains(&(masks[line].count_ones() as usize)))
+                .collect::<Vec<_>>();
+
+            for subset in combinations(&lines, n) {
+                let union = subset.iter().fold(0, |union, &line| union | masks[line]);
+
+                if union.count_ones() as usize != n {
+                    continue;
+                }
+
+                let eliminations = CandidateIterator(union)
+                    .flat_map(|i| grid.positions(cover + (i - 1) as usize, value))
+                    .filter(|&(x, y)| !subset.contains(if base == 0 { &y } else { &x }))
+                    .map(|(x, y)| (x, y, value))
+                    .collect();
+
+                let step = Step::new(
+                    technique,
+                    subset
+                        .iter()
+                        .flat_map(|&line| grid.positions(base + line, value))
+                        .collect(),
+                    vec![value],
+                    subset
+                        .iter()
+                        .map(|&line| base + line)
+                        .chain(CandidateIterator(union).map(|i| cover + (i - 1) as usize))
+                        .collect(),
+                );
+
+                if let Some(step) = step.eliminate(eliminations) {
+                    return Some(step);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+fn xy_wing(grid: &Grid) -> Option<Step> {
+    let bivalue = (0..9)
+        .flat_map(|x| (0..9).map(move |y| (x, y)))
+        .filter(|&(x, y)| grid.get(x, y) == 0 && grid.candidates(x, y).count_ones() == 2)
+        .collect::<Vec<_>>();
+
+    for &pivot in &bivalue {
+        let pivot_candidates = grid.candidates(pivot.0, pivot.1);
+
+        let wings = bivalue
+            .iter()
+            .copied()
+            .filter(|&cell| sees(pivot, cell))
+            .collect::<Vec<_>>();
+
+        for (i, &first) in wings.iter().enumerate() {
+            for &second in &wings[i + 1..] {
+                let first_candidates = grid.candidates(first.0, first.1);
+                let second_candidates = grid.candidates(second.0, second.1);
+
+                let shared = first_candidates & second_candidates;
+                let first_pivot = first_candidates & pivot_candidates;
+                let second_pivot = second_candidates & pivot_candidates;
+
+                if shared.count_ones() != 1
+                    || shared & pivot_candidates != 0
+                    || first_pivot.count_ones() != 1
+                    || second_pivot.count_ones() != 1
+                    || first_pivot == second_pivot
+                {
+                    continue;
+                }
+
+                let value = (shared.trailing_zeros() + 1) as u8;
+
+                let eliminations = (0..9)
+                    .flat_map(|x| (0..9).map(move |y| (x, y)))
+                    .filter(|&cell| cell != pivot && sees(first, cell) && sees(second, cell))
+                    .filter(|&(x, y)| grid.has_candidate(x, y, value))
+                    .map(|(x, y)| (x, y, value))
+                    .collect();
+
+                let step = Step::new(
+                    Technique::XYWing,
+                    vec![pivot, first, second],
+                    vec![value],
+                    vec![],
+                );
+
+                if let Some(step) = step.eliminate(eliminations) {
+                    return Some(step);
+                }
+            }
+        }
+    }
+
+    None
+}
+
+// Single-digit chains: cells joined by units holding only two candidates for the digit take
+// alternating colors, exactly one of which is true. If two cells of one color see each other, that
+// color is false everywhere; otherwise any cell seeing both colors can't hold the digit. Chains
+// through more than one digit, such as X-cycles and AICs, are out of scope
+fn simple_coloring(grid: &Grid) -> Option<Step> {
+    for value in 1..=9 {
+        let mut links = vec![];
+        for unit in 0..27 {
+            let positions = grid.positions(unit, value).collect::<Vec<_>>();
+            if positions.len() == 2 && !grid.unit_contains(unit, value) {
+                links.push((positions[0], positions[1]));
+            }
+        }
+
+        let mut colors = [[None; 9]; 9];
+
+        for &(start, _) in &links {
+            if colors[start.0][start.1].is_some() {
+                continue;
+            }
+
+            // Colors the chain breadth-first, so that cells are listed in chain order
+            colors[start.0][start.1] = Some(false);
+            let mut chain = vec![start];
+            let mut i = 0;
+
+            while i < chain.len() {
+                let cell = chain[i];
+                let color = colors[cell.0][cell.1];
+
+                for &(a, b) in &links {
+                    let other = match (a == cell, b == cell) {
+                        (true, _) => b,
+                        (_, true) => a,
+                        _ => continue,
+                    };
+
+                    if colors[other.0][other.1].is_none() {
+                        colors[other.0][other.1] = color.map(|color| !color);
+                        chain.push(other);
+                    }
+                }
+
+                i += 1;
+            }
+
+            if chain.len() < 3 {
+                continue;
+            }
+
+            let colored = |color: bool| {
+                chain
+                    .iter()
+                    .copied()
+                    .filter(move |&(x, y)| colors[x][y] == Some(color))
+            };
+
+            let wrap = [false, true].into_iter().find(|&color| {
+                colored(color).any(|first| colored(color).any(|second| sees(first, second)))
+            });
+
+            let eliminations = match wrap {
+                Some(color) => colored(color).map(|(x, y)| (x, y, value)).collect(),
+                None => (0..9)
+                    .flat_map(|x| (0..9).map(move |y| (x, y)))
+                    .filter(|&(x, y)| colors[x][y].is_none() && grid.has_candidate(x, y, value))
+                    .filter(|&cell| {
+                        colored(false).any(|other| sees(cell, other))
+                            && colored(true).any(|other| sees(cell, other))
+                    })
+                    .map(|(x, y)| (x, y, value))
+                    .collect(),
+            };
+
+            let step = Step::new(
+                Technique::SimpleColoring,
+                chain.clone(),
+                vec![value],
+                vec![],
+            );
+
+            if let Some(step) = step.eliminate(eliminations) {
+                return Some(step);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::logic::{simple_coloring, Grid, LogicalSolver, Technique};
+    use crate::sudoku::Sudoku;
+    use core::str::FromStr;
+    use flate2::bufread::GzDecoder;
+    use std::fs;
+    use std::io;
+    use std::io::BufRead;
+
+    fn solve_logically(sudoku: &Sudoku) -> (Grid, Vec<Technique>) {
+        let mut grid = Grid::new(sudoku).unwrap();
+        let mut techniques = vec![];
+
+        while let Some(step) = grid.next_step() {
+            grid.apply(&step);
+            techniques.push(step.technique);
+        }
+
+        (grid, techniques)
+    }
+
+    #[test]
+    fn test_singles() {
+        let sudoku = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+
+        let (grid, techniques) = solve_logically(&sudoku);
+        assert!(grid.is_solved());
+        assert!(techniques
+            .iter()
+            .all(|&technique| technique == Technique::HiddenSingle));
+        assert_eq!(
+            grid.to_sudoku().to_string(),
+            sudoku.solve().unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn test_logical_solver() {
+        let sudoku = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+
+        let mut solver = LogicalSolver::new(&sudoku).unwrap();
+        let steps = solver.solve();
+        assert!(solver.is_solved());
+        assert_eq!(steps.len(), 51);
+
+        assert_eq!(
+            steps[0].to_string(),
+            "hidden single: 5 can only go in r3c7 within row 3"
+        );
+        assert_eq!(steps[0].cells(), &[(6, 2)]);
+        assert_eq!(steps[0].digits(), &[5]);
+        assert_eq!(steps[0].placements(), &[(6, 2, 5)]);
+        assert!(steps[0].eliminations().is_empty());
+
+        let mut replayed = sudoku.clone();
+        for step in &steps {
+            for &(x, y, value) in step.placements() {
+                assert_eq!(replayed.get(x, y), 0);
+                replayed.set(x, y, value);
+            }
+        }
+
+        let solution = sudoku.solve().unwrap().to_string();
+        assert_eq!(replayed.to_string(), solution);
+        assert_eq!(solver.sudoku().to_string(), solution);
+        assert!(solver.next().is_none());
+    }
+
+    #[test]
+    fn test_soundness() {
+        let file = fs::File::open("data/sudoku/kaggle.list.gz").unwrap();
+        let reader = io::BufReader::new(GzDecoder::new(io::BufReader::new(file)));
+
+        for line in reader.lines().take(500) {
+            let sudoku = Sudoku::from_str(line.unwrap().trim()).unwrap();
+            let solution = sudoku.solve().unwrap();
+            let (grid, _) = solve_logically(&sudoku);
+
+            assert!(grid.is_solved());
+            assert_eq!(grid.to_sudoku().to_string(), solution.to_string());
+        }
+    }
+
+    #[test]
+    fn test_simple_coloring() {
+        // Only the cells listed can hold a 1; r1c1, r1c5, r5c5 and r4c4 form a chain, and r4c1
+        // sees both of its colors
+        let mut grid = Grid::new(&Sudoku::new()).unwrap();
+        let ones = [(0, 0), (4, 0), (4, 4), (3, 3), (0, 3), (0, 8), (7, 3)];
+        for x in 0..9 {
+            for y in 0..9 {
+                if !ones.contains(&(x, y)) {
+                    grid.candidates[x][y] &= !1;
+                }
+            }
+        }
+
+        let step = simple_coloring(&grid).unwrap();
+        assert_eq!(step.technique, Technique::SimpleColoring);
+        assert_eq!(step.cells(), &[(0, 0), (4, 0), (4, 4), (3, 3)]);
+        assert_eq!(step.eliminations(), &[(0, 3, 1)]);
+        assert_eq!(
+            step.to_string(),
+            "simple coloring: 1 alternates along the conjugate pairs r1c1, r1c5, r5c5, r4c4, \
+             eliminating 1 from r4c1"
+        );
+
+        // Here r1c1 and r2c2 share a color and a box, so every cell of that color is false
+        let mut grid = Grid::new(&Sudoku::new()).unwrap();
+        let ones = [(0, 0), (4, 0), (4, 4), (1, 4), (1, 1), (2, 2)];
+        for x in 0..9 {
+            for y in 0..9 {
+                if !ones.contains(&(x, y)) {
+                    grid.candidates[x][y] &= !1;
+                }
+            }
+        }
+
+        let step = simple_coloring(&grid).unwrap();
+        assert_eq!(step.eliminations(), &[(0, 0, 1), (4, 4, 1), (1, 1, 1)]);
+    }
+
+    #[test]
+    fn test_fish_and_wings() {
+        let file = fs::File::open("data/sudoku/top1465.list.gz").unwrap();
+        let reader = io::BufReader::new(GzDecoder::new(io::BufReader::new(file)));
+
+        let mut used = vec![];
+
+        for line in reader.lines().take(300) {
+            let sudoku = Sudoku::from_str(line.unwrap().trim()).unwrap();
+            let solution = sudoku.solve().unwrap();
+            let (grid, techniques) = solve_logically(&sudoku);
+
+            for (x, y, value) in solution.iter() {
+                assert!(grid.get(x, y) == 0 || grid.get(x, y) == value);
+                assert!(grid.get(x, y) != 0 || grid.has_candidate(x, y, value));
+            }
+
+            used.extend(techniques);
+        }
+
+        for technique in Technique::ALL {
+            assert!(used.contains(&technique), "{} never used", technique);
+        }
+    }
+}
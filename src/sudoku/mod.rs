@@ -0,0 +1,2886 @@
+use crate::rng::Rng;
+use crate::{DLXMatrix, SearchEvent, Solution, Statistics, TimedOut};
+
+use core::fmt;
+use core::fmt::Write;
+use core::ops::Index;
+use core::str;
+use core::time::Duration;
+use std::time::Instant;
+
+mod canonical;
+pub mod dataset;
+mod even_odd;
+mod fast;
+mod formats;
+mod fpuzzles;
+mod grid;
+mod hex;
+mod hint;
+mod killer;
+mod logic;
+mod rating;
+mod render;
+mod samurai;
+mod scan;
+#[cfg(feature = "serde")]
+mod serialization;
+mod session;
+mod sukaku;
+
+pub use canonical::SolverCache;
+pub use even_odd::{EvenOddSudoku, Parity};
+pub use formats::PuzzleMeta;
+pub use fpuzzles::{FPuzzle, FPuzzleError};
+pub use grid::{Digit, Grid};
+pub use hex::{AnySudoku, HexSymbols, Sudoku16};
+pub use hint::{Hint, Reason};
+pub use killer::{Cage, CageError, KillerSudoku};
+pub use logic::{LogicalSolver, Step, Technique};
+pub use rating::{Difficulty, Rating};
+pub use render::RenderOptions;
+pub use samurai::Samurai;
+pub use scan::Confidence;
+#[cfg(feature = "serde")]
+pub use serialization::array as serde_array;
+pub use session::SudokuSession;
+pub use sukaku::Sukaku;
+
+#[derive(Clone, Default, Debug, PartialEq, Eq, Hash)]
+pub struct Sudoku {
+    grid: [[u8; 9]; 9],
+}
+
+impl Sudoku {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Rows are listed top to bottom, so that an array literal reads like the grid
+    pub fn from_grid(rows: [[u8; 9]; 9]) -> Result<Self, GridError> {
+        let mut sudoku = Self::new();
+
+        for (y, row) in rows.iter().enumerate() {
+            for (x, &value) in row.iter().enumerate() {
+                if value > 9 {
+                    return Err(GridError::InvalidValue { x, y, value });
+                }
+                sudoku.grid[x][y] = value;
+            }
+        }
+
+        Ok(sudoku)
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        assert!(value <= 9);
+        self.grid[x][y] = value;
+    }
+
+    pub fn try_set(&mut self, x: usize, y: usize, value: u8) -> Result<(), PlaceError> {
+        if x >= 9 || y >= 9 {
+            return Err(PlaceError::OutOfBounds { x, y });
+        }
+
+        if value > 9 {
+            return Err(PlaceError::InvalidValue { value });
+        }
+
+        self.grid[x][y] = value;
+        Ok(())
+    }
+
+    pub fn try_set_with(
+        &mut self,
+        x: usize,
+        y: usize,
+        value: u8,
+        rules: &Rules,
+    ) -> Result<(), PlaceError> {
+        let previous = *self
+            .grid
+            .get(x)
+            .and_then(|column| column.get(y))
+            .unwrap_or(&0);
+        self.try_set(x, y, value)?;
+
+        let conflict = self
+            .conflicts_with(rules)
+            .into_iter()
+            .find(|conflict| conflict.a == (x, y) || conflict.b == (x, y));
+
+        match conflict {
+            Some(conflict) => {
+                self.grid[x][y] = previous;
+                Err(PlaceError::Conflict { conflict })
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn clear(&mut self, x: usize, y: usize) {
+        self.grid[x][y] = 0;
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.grid[x][y]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        (0..9).flat_map(move |x| (0..9).map(move |y| (x, y, self.grid[x][y])))
+    }
+
+    pub fn row(&self, y: usize) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        assert!(y < 9);
+        self.unit(y)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        assert!(x < 9);
+        self.unit(9 + x)
+    }
+
+    pub fn box_(&self, b: usize) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        assert!(b < 9);
+        self.unit(18 + b)
+    }
+
+    pub fn units(
+        &self,
+    ) -> impl Iterator<Item = impl Iterator<Item = (usize, usize, u8)> + '_> + '_ {
+        (0..27).map(move |unit| self.unit(unit))
+    }
+
+    fn unit(&self, unit: usize) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        unit_cells(unit)
+            .into_iter()
+            .map(move |(x, y)| (x, y, self.grid[x][y]))
+    }
+
+    pub fn solve(&self) -> Option<Sudoku> {
+        self.solve_with(&Rules::default())
+    }
+
+    pub fn solve_with(&self, rules: &Rules) -> Option<Sudoku> {
+        let (propagated, constraints) = self.propagate(rules)?;
+
+        if propagated.is_complete() && !rules.has_conflicts() {
+            return Some(propagated);
+        }
+
+        propagated
+            .encode(&constraints, rules, 0, |_, _, _, _| ())
+            .solve()
+    }
+
+    pub fn solve_with_stats(&self) -> (Option<Sudoku>, SolveStats) {
+        match self.solve_until(&Rules::default(), None) {
+            Ok(result) => result,
+            Err(TimedOut) => unreachable!(),
+        }
+    }
+
+    // Like solve_with_stats, but gives up once the deadline has passed
+    pub fn solve_with_deadline(
+        &self,
+        deadline: Instant,
+    ) -> Result<(Option<Sudoku>, SolveStats), TimedOut> {
+        self.solve_until(&Rules::default(), Some(deadline))
+    }
+
+    // Like solve_with_stats under the given rules, giving up once the deadline, if any, has passed
+    pub fn solve_until(
+        &self,
+        rules: &Rules,
+        deadline: Option<Instant>,
+    ) -> Result<(Option<Sudoku>, SolveStats), TimedOut> {
+        // There's no clock on wasm32-unknown-unknown, where Instant::now panics, so solves there
+        // aren't timed
+        let start = (!cfg!(all(target_arch = "wasm32", target_os = "unknown"))).then(Instant::now);
+        let mut stats = SolveStats::default();
+
+        let solution = match self.propagate(rules) {
+            Some((propagated, constraints)) => {
+                stats.propagated = propagated
+                    .iter()
+                    .filter(|&(x, y, value)| value != 0 && self.get(x, y) == 0)
+                    .count();
+
+                if propagated.is_complete() && !rules.has_conflicts() {
+                    Some(propagated)
+                } else {
+                    let (solution, statistics) = propagated
+                        .encode(&constraints, rules, 0, |_, _, _, _| ())
+                        .solve_until(deadline)?;
+
+                    stats.nodes = statistics.nodes;
+                    stats.backtracks = statistics.backtracks;
+                    solution
+                }
+            }
+            None => None,
+        };
+
+        stats.elapsed = start.map_or(Duration::ZERO, |start| start.elapsed());
+        Ok((solution, stats))
+    }
+
+    // The filter is asked whether value may go in (x, y) given the cells placed so far, which lets
+    // callers layer on constraints such as greater-than signs between cells. Singles aren't
+    // propagated first, since those placements would bypass the filter
+    pub fn solve_with_filter<F>(&self, rules: &Rules, mut filter: F) -> Option<Sudoku>
+    where
+        F: FnMut(&Sudoku, usize, usize, u8) -> bool,
+    {
+        let constraints = self.constraints(rules).ok()?;
+        let encoding = self.encode(&constraints, rules, 0, |_, _, _, _| ());
+        let placements = &encoding.placements;
+
+        let solution = encoding.matrix.solve_with_filter(|selected, row| {
+            let (x, y, value) = match placements.get(row) {
+                Some(&placement) => placement,
+                None => return true,
+            };
+
+            let mut partial = self.clone();
+            for &(x, y, value) in selected.iter().filter_map(|&row| placements.get(row)) {
+                partial.grid[x][y] = value;
+            }
+
+            partial.grid[x][y] == value || filter(&partial, x, y, value)
+        })?;
+
+        Some(Encoding::decode_solution(placements, solution))
+    }
+
+    // Records every candidate the exact cover search places or takes back, in order, for
+    // visualizing the search. Givens are left out, since the search has no choice about them
+    pub fn solve_traced(&self) -> (Option<Sudoku>, Vec<TraceStep>) {
+        let rules = Rules::default();
+        let constraints = match self.constraints(&rules) {
+            Ok(constraints) => constraints,
+            Err(_) => return (None, vec![]),
+        };
+
+        let encoding = self.encode(&constraints, &rules, 0, |_, _, _, _| ());
+        let placements = &encoding.placements;
+        let mut trace = vec![];
+
+        let solution = encoding.matrix.solve_traced(|event| {
+            let (row, backtrack) = match event {
+                SearchEvent::Select { row } => (row, false),
+                SearchEvent::Backtrack { row } => (row, true),
+            };
+
+            let (x, y, value) = placements[row];
+            if self.grid[x][y] != 0 {
+                return;
+            }
+
+            trace.push(if backtrack {
+                TraceStep::Backtrack { x, y, value }
+            } else {
+                TraceStep::Try { x, y, value }
+            });
+        });
+
+        let solution = solution.map(|solution| Encoding::decode_solution(placements, solution));
+        (solution, trace)
+    }
+
+    // Fills in naked and hidden singles until none remain, so that easy puzzles never need an
+    // exact cover matrix and hard ones get a smaller one. Deductions only use the units, which
+    // every rule set includes, so they hold under the conflict rules too. Returns None if the
+    // grid turns out to be contradictory
+    fn propagate(&self, rules: &Rules) -> Option<(Sudoku, SudokuConstraints)> {
+        let mut propagated = self.clone();
+        let mut constraints = self.constraints(rules).ok()?;
+
+        let units = (0..9)
+            .map(|y| (0..9).map(|x| (x, y)).collect())
+            .chain((0..9).map(|x| (0..9).map(|y| (x, y)).collect()))
+            .chain((0..9).map(|region| {
+                (0..81)
+                    .map(|i| (i % 9, i / 9))
+                    .filter(|&(x, y)| rules.regions.get(x, y) == region)
+                    .collect()
+            }))
+            .chain(rules.units().map(|contains| {
+                (0..81)
+                    .map(|i| (i % 9, i / 9))
+                    .filter(|&(x, y)| contains(x, y))
+                    .collect()
+            }))
+            .collect::<Vec<Vec<_>>>();
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for x in 0..9 {
+                for y in 0..9 {
+                    if propagated.grid[x][y] != 0 {
+                        continue;
+                    }
+
+                    let mask = constraints.get_mask(x, y);
+
+                    if mask == 0 {
+                        return None;
+                    }
+
+                    if mask.count_ones() == 1 {
+                        let value = mask.trailing_zeros() as u8 + 1;
+                        propagated.grid[x][y] = value;
+                        constraints.add(x, y, value).ok()?;
+                        changed = true;
+                    }
+                }
+            }
+
+            for unit in &units {
+                let mut placed = 0;
+                let mut seen_once = 0;
+                let mut seen_twice = 0;
+
+                for &(x, y) in unit {
+                    match propagated.grid[x][y] {
+                        0 => {
+                            let mask = constraints.get_mask(x, y);
+                            seen_twice |= seen_once & mask;
+                            seen_once |= mask;
+                        }
+                        value => placed |= 1 << (value - 1),
+                    }
+                }
+
+                if placed | seen_once != (1 << 9) - 1 {
+                    return None;
+                }
+
+                let singles = seen_once & !seen_twice & !placed;
+
+                for &(x, y) in unit {
+                    let mask = constraints.get_mask(x, y) & singles;
+
+                    if propagated.grid[x][y] == 0 && mask != 0 {
+                        if mask.count_ones() > 1 {
+                            return None;
+                        }
+
+                        let value = mask.trailing_zeros() as u8 + 1;
+                        propagated.grid[x][y] = value;
+                        constraints.add(x, y, value).ok()?;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        Some((propagated, constraints))
+    }
+
+    pub fn minimize(&self, rng: &mut impl Rng) -> Option<Sudoku> {
+        let mut cells = self
+            .iter()
+            .filter(|&(_, _, value)| value != 0)
+            .map(|(x, y, _)| (x, y))
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut cells);
+        self.minimize_in_order(cells)
+    }
+
+    pub fn minimize_in_order<I>(&self, cells: I) -> Option<Sudoku>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        if !self.has_unique_solution() {
+            return None;
+        }
+
+        let mut minimized = self.clone();
+
+        for (x, y) in cells {
+            let value = minimized.get(x, y);
+            if value == 0 {
+                continue;
+            }
+
+            minimized.clear(x, y);
+            if !minimized.has_unique_solution() {
+                minimized.set(x, y, value);
+            }
+        }
+
+        Some(minimized)
+    }
+
+    pub fn add_clue_until_unique(&self, rng: &mut impl Rng) -> Option<Sudoku> {
+        let mut solutions = self.solve_all();
+        let solution = solutions.next()?;
+        let mut puzzle = self.clone();
+        let mut other = solutions.next();
+
+        // Each added clue comes from the target solution and rules out at least the other
+        // solution found, since it is chosen among the cells where the two disagree
+        while let Some(alternative) = other {
+            let cells = solution
+                .iter()
+                .filter(|&(x, y, value)| alternative.get(x, y) != value)
+                .collect::<Vec<_>>();
+
+            let (x, y, value) = cells[rng.below(cells.len())];
+            puzzle.set(x, y, value);
+
+            other = puzzle.solve_all().find(|candidate| *candidate != solution);
+        }
+
+        Some(puzzle)
+    }
+
+    // A random filled grid, found by trying each cell's candidates in shuffled order
+    pub fn random_filled(rng: &mut impl Rng) -> Sudoku {
+        Self::random_filled_with(&Rules::default(), rng)
+            .expect("Standard rules always admit a filled grid")
+    }
+
+    pub fn random_filled_with(rules: &Rules, rng: &mut impl Rng) -> Option<Sudoku> {
+        let empty = Sudoku::new();
+        let constraints = empty.constraints(rules).ok()?;
+
+        empty
+            .encode_ordered(
+                &constraints,
+                rules,
+                0,
+                |_, _, _, _| (),
+                |candidates| rng.shuffle(candidates),
+            )
+            .solve()
+    }
+
+    // Fills each pattern with a fresh random grid until the givens it selects have a unique
+    // solution, giving up after a bounded number of attempts
+    pub fn generate_with_pattern(pattern: &[[bool; 9]; 9], rng: &mut impl Rng) -> Option<Sudoku> {
+        const ATTEMPTS: usize = 1000;
+
+        for _ in 0..ATTEMPTS {
+            let filled = Sudoku::new().solve_random(rng)?;
+            let mut puzzle = Sudoku::new();
+
+            for (x, y, value) in filled.iter().filter(|&(x, y, _)| pattern[x][y]) {
+                puzzle.set(x, y, value);
+            }
+
+            if puzzle.has_unique_solution() {
+                return Some(puzzle);
+            }
+        }
+
+        None
+    }
+
+    pub fn solve_all(&self) -> impl Iterator<Item = Sudoku> {
+        self.solve_all_with(&Rules::default())
+    }
+
+    pub fn solve_all_with(&self, rules: &Rules) -> impl Iterator<Item = Sudoku> {
+        self.propagate(rules)
+            .map(|(propagated, constraints)| {
+                propagated
+                    .encode(&constraints, rules, 0, |_, _, _, _| ())
+                    .solutions()
+            })
+            .into_iter()
+            .flatten()
+    }
+
+    pub fn count_solutions(&self, limit: usize) -> usize {
+        self.count_solutions_with(&Rules::default(), limit)
+    }
+
+    pub fn count_solutions_with(&self, rules: &Rules, limit: usize) -> usize {
+        match self.propagate(rules) {
+            Some((propagated, constraints)) => propagated
+                .encode(&constraints, rules, 0, |_, _, _, _| ())
+                .matrix
+                .count_solutions(limit),
+            None => 0,
+        }
+    }
+
+    // The grid of cells whose value is the same in every solution, givens included. Each cell of a
+    // first solution is tested by solving again with its value excluded; any solution found that
+    // way also rules out every other cell it disagrees on. Returns None if there is no solution
+    pub fn solve_forced(&self) -> Option<Sudoku> {
+        self.solve_forced_with(&Rules::default())
+    }
+
+    pub fn solve_forced_with(&self, rules: &Rules) -> Option<Sudoku> {
+        let (propagated, constraints) = self.propagate(rules)?;
+        let mut forced = propagated
+            .encode(&constraints, rules, 0, |_, _, _, _| ())
+            .solve()?;
+
+        for (x, y, value) in propagated.iter() {
+            let candidate = forced.grid[x][y];
+            if value != 0 || candidate == 0 {
+                continue;
+            }
+
+            let mut excluded = constraints.clone();
+            excluded.restrict(x, y, !(1 << (candidate - 1)));
+
+            let other = propagated
+                .encode(&excluded, rules, 0, |_, _, _, _| ())
+                .solve();
+
+            if let Some(other) = other {
+                for (x, y, value) in other.iter() {
+                    if forced.grid[x][y] != value {
+                        forced.grid[x][y] = 0;
+                    }
+                }
+            }
+        }
+
+        Some(forced)
+    }
+
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2) == 1
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.constraints(&Rules::default()).is_ok()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.iter().all(|(_, _, value)| value != 0)
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.is_complete() && self.is_valid()
+    }
+
+    pub fn clue_count(&self) -> usize {
+        self.iter().filter(|&(_, _, value)| value != 0).count()
+    }
+
+    // Index i holds the number of cells containing digit i + 1
+    pub fn digit_histogram(&self) -> [usize; 9] {
+        let mut histogram = [0; 9];
+
+        for (_, _, value) in self.iter().filter(|&(_, _, value)| value != 0) {
+            histogram[value as usize - 1] += 1;
+        }
+
+        histogram
+    }
+
+    pub fn empty_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.iter()
+            .filter(|&(_, _, value)| value == 0)
+            .map(|(x, y, _)| (x, y))
+    }
+
+    // Indexed as [x][y], like the patterns taken by generate_with_pattern
+    pub fn givens_mask(&self) -> [[bool; 9]; 9] {
+        let mut mask = [[false; 9]; 9];
+
+        for (x, y, value) in self.iter() {
+            mask[x][y] = value != 0;
+        }
+
+        mask
+    }
+
+    // The cells filled in this grid which are empty or different in the other, so that a solution
+    // less its puzzle gives the cells the solver filled in
+    pub fn difference(&self, other: &Sudoku) -> Sudoku {
+        let mut difference = Sudoku::new();
+
+        for (x, y, value) in self.iter() {
+            if value != other.grid[x][y] {
+                difference.grid[x][y] = value;
+            }
+        }
+
+        difference
+    }
+
+    // Fills the empty cells of this grid from the other. Returns None if the grids disagree on a
+    // cell which both have filled
+    pub fn merge(&self, other: &Sudoku) -> Option<Sudoku> {
+        let mut merged = self.clone();
+
+        for (x, y, value) in other.iter().filter(|&(_, _, value)| value != 0) {
+            match merged.grid[x][y] {
+                0 => merged.grid[x][y] = value,
+                existing if existing != value => return None,
+                _ => (),
+            }
+        }
+
+        Some(merged)
+    }
+
+    pub fn violations(&self) -> Vec<(usize, usize)> {
+        let mut cells = self
+            .conflicts()
+            .iter()
+            .flat_map(|conflict| [conflict.a, conflict.b])
+            .collect::<Vec<_>>();
+        cells.sort_unstable();
+        cells.dedup();
+        cells
+    }
+
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        self.conflicts_with(&Rules::default())
+    }
+
+    pub fn conflicts_with(&self, rules: &Rules) -> Vec<Conflict> {
+        let givens = self
+            .iter()
+            .filter(|&(_, _, value)| value != 0)
+            .collect::<Vec<_>>();
+
+        let mut conflicts = vec![];
+
+        for (i, &(x, y, value)) in givens.iter().enumerate() {
+            for &(other_x, other_y, other_value) in &givens[i + 1..] {
+                if value != other_value {
+                    continue;
+                }
+
+                let units = rules.cell_units(other_x, other_y).collect::<Vec<_>>();
+
+                conflicts.extend(
+                    rules
+                        .cell_units(x, y)
+                        .filter(|unit| units.contains(unit))
+                        .chain(rules.cell_moves(x, y, other_x, other_y))
+                        .map(|unit| Conflict {
+                            a: (x, y),
+                            b: (other_x, other_y),
+                            value,
+                            unit,
+                        }),
+                );
+            }
+        }
+
+        // Unlike every other rule, non-consecutive is broken by differing digits
+        if rules.non_consecutive {
+            for (i, &(x, y, value)) in givens.iter().enumerate() {
+                for &(other_x, other_y, other_value) in &givens[i + 1..] {
+                    if x.abs_diff(other_x) + y.abs_diff(other_y) == 1
+                        && value.abs_diff(other_value) == 1
+                    {
+                        conflicts.push(Conflict {
+                            a: (x, y),
+                            b: (other_x, other_y),
+                            value,
+                            unit: Unit::Consecutive,
+                        });
+                    }
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    fn encode<F>(
+        &self,
+        constraints: &SudokuConstraints,
+        rules: &Rules,
+        extra_columns: u16,
+        extend: F,
+    ) -> Encoding
+    where
+        F: FnMut(usize, usize, u8, &mut Vec<u16>),
+    {
+        self.encode_ordered(constraints, rules, extra_columns, extend, |_| ())
+    }
+
+    // The search tries rows in the order they were pushed, so permuting the candidate placements
+    // here changes which solution is found first
+    fn encode_ordered<F, O>(
+        &self,
+        constraints: &SudokuConstraints,
+        rules: &Rules,
+        extra_columns: u16,
+        mut extend: F,
+        order: O,
+    ) -> Encoding
+    where
+        F: FnMut(usize, usize, u8, &mut Vec<u16>),
+        O: FnOnce(&mut [(usize, usize, u8)]),
+    {
+        let conflicts = Conflicts::new(rules);
+        let units_base = 324 + extra_columns;
+        let primary_columns = units_base + 9 * rules.units().count() as u16;
+        let mut matrix = DLXMatrix::with_secondary_columns(primary_columns, conflicts.columns);
+        let mut placements = vec![];
+
+        let mut row = Vec::with_capacity(4);
+
+        let mut push_row = |x: usize, y: usize, value: u8| {
+            debug_assert!((1..=9).contains(&value));
+
+            let digit = (value - 1) as u16;
+            let region = rules.regions.get(x, y);
+
+            row.clear();
+            row.extend_from_slice(&[
+                9 * (y as u16) + (x as u16),
+                81 + 9 * (y as u16) + digit,
+                162 + 9 * (x as u16) + digit,
+                243 + 9 * (region as u16) + digit,
+            ]);
+            extend(x, y, value, &mut row);
+            row.extend(
+                rules
+                    .units()
+                    .enumerate()
+                    .filter(|&(_, contains)| contains(x, y))
+                    .map(|(unit, _)| units_base + 9 * unit as u16 + digit),
+            );
+            row.extend(
+                conflicts
+                    .get(x, y, value)
+                    .iter()
+                    .map(|&column| primary_columns + column),
+            );
+
+            matrix.push_row(&row);
+            placements.push((x, y, value));
+        };
+
+        let mut candidates = self
+            .iter()
+            .flat_map(|(x, y, value)| {
+                let values = if value == 0 {
+                    constraints.get_mask(x, y)
+                } else {
+                    1 << (value - 1)
+                };
+                CandidateIterator(values).map(move |value| (x, y, value))
+            })
+            .collect::<Vec<_>>();
+        order(&mut candidates);
+
+        for (x, y, value) in candidates {
+            push_row(x, y, value);
+        }
+
+        Encoding { matrix, placements }
+    }
+
+    // Strict parsing rejects whitespace, so that only the canonical 81-character line is accepted
+    pub fn from_str_strict(string: &str) -> Result<Self, ParseSudokuError> {
+        let mut sudoku = Self::new();
+        parse_cells(string, 81, true, |i, value| sudoku.set(i % 9, i / 9, value))?;
+        Ok(sudoku)
+    }
+
+    // Always exactly 81 characters, which both from_str and from_str_strict read back to an equal
+    // grid
+    pub fn to_line(&self) -> String {
+        let mut string = String::with_capacity(81);
+
+        for y in 0..9 {
+            for x in 0..9 {
+                string.push(Self::symbol(self.grid[x][y]));
+            }
+        }
+
+        string
+    }
+
+    pub fn to_grid(&self) -> String {
+        self.to_string()
+    }
+
+    pub fn to_pretty(&self) -> String {
+        let mut string = String::new();
+
+        for y in 0..9 {
+            string.push_str(match y {
+                0 => "┌───────┬───────┬───────┐\n",
+                3 | 6 => "├───────┼───────┼───────┤\n",
+                _ => "",
+            });
+
+            for x in 0..9 {
+                string.push_str(if x % 3 == 0 { "│ " } else { " " });
+                string.push(Self::symbol(self.grid[x][y]));
+                if x % 3 == 2 {
+                    string.push(' ');
+                }
+            }
+
+            string.push_str("│\n");
+        }
+
+        string.push_str("└───────┴───────┴───────┘");
+        string
+    }
+
+    pub fn to_pencilmark_string(&self) -> String {
+        let candidates = self.candidate_grid();
+
+        let marks = |x: usize, y: usize| -> String {
+            match self.grid[x][y] {
+                0 => CandidateIterator(candidates[x][y])
+                    .map(|value| (value + b'0') as char)
+                    .collect(),
+                value => Self::symbol(value).to_string(),
+            }
+        };
+
+        let width = (0..9)
+            .flat_map(|x| (0..9).map(move |y| (x, y)))
+            .map(|(x, y)| marks(x, y).len())
+            .max()
+            .unwrap_or(1)
+            .max(1);
+
+        let mut string = String::new();
+
+        for y in 0..9 {
+            if y == 3 || y == 6 {
+                let band = "-".repeat(3 * (width + 1) + 1);
+                writeln!(&mut string, "{}+{}+{}", band, band, band).unwrap();
+            }
+
+            for x in 0..9 {
+                if x == 3 || x == 6 {
+                    string.push_str(" |");
+                }
+                write!(&mut string, " {:<width$}", marks(x, y), width = width).unwrap();
+            }
+
+            string.truncate(string.trim_end().len());
+            if y < 8 {
+                string.push('\n');
+            }
+        }
+
+        string
+    }
+
+    fn symbol(value: u8) -> char {
+        if value == 0 {
+            '.'
+        } else {
+            (value + b'0') as char
+        }
+    }
+
+    pub fn candidates(&self, x: usize, y: usize) -> impl Iterator<Item = u8> {
+        CandidateIterator(self.candidate_grid()[x][y])
+    }
+
+    pub fn candidate_grid(&self) -> [[u16; 9]; 9] {
+        let mut constraints = SudokuConstraints::new(&Rules::default());
+
+        for (x, y, value) in self.iter().filter(|&(_, _, value)| value != 0) {
+            constraints.remove(x, y, value);
+        }
+
+        let mut candidates = [[0; 9]; 9];
+
+        for (x, y, value) in self.iter() {
+            if value == 0 {
+                candidates[x][y] = constraints.get_mask(x, y);
+            }
+        }
+
+        candidates
+    }
+
+    // One channel per digit, each a row-major 9x9 plane, so index 81 * (value - 1) + 9 * y + x is 1.0
+    // if value is given at or still a candidate for (x, y)
+    pub fn to_feature_tensor(&self) -> Vec<f32> {
+        let candidates = self.candidate_grid();
+        let mut tensor = vec![0.0; 729];
+
+        for (x, y, value) in self.iter() {
+            let mask = if value == 0 {
+                candidates[x][y]
+            } else {
+                1 << (value - 1)
+            };
+
+            for value in CandidateIterator(mask) {
+                tensor[81 * (value as usize - 1) + 9 * y + x] = 1.0;
+            }
+        }
+
+        tensor
+    }
+
+    fn constraints(&self, rules: &Rules) -> Result<SudokuConstraints, Option<Conflict>> {
+        let mut constraints = SudokuConstraints::new(rules);
+
+        for (x, y, value) in self.iter().filter(|&(_, _, value)| value != 0) {
+            if constraints.add(x, y, value).is_err() {
+                // The bitmasks only know that some unit already holds the value, so go back and
+                // look for the given responsible
+                return Err(self.conflicts_with(rules).into_iter().next());
+            }
+        }
+
+        Ok(constraints)
+    }
+}
+
+impl TryFrom<[[u8; 9]; 9]> for Sudoku {
+    type Error = GridError;
+
+    fn try_from(rows: [[u8; 9]; 9]) -> Result<Self, GridError> {
+        Self::from_grid(rows)
+    }
+}
+
+impl TryFrom<&[u8; 81]> for Sudoku {
+    type Error = GridError;
+
+    fn try_from(cells: &[u8; 81]) -> Result<Self, GridError> {
+        let mut rows = [[0; 9]; 9];
+
+        for (i, &value) in cells.iter().enumerate() {
+            rows[i / 9][i % 9] = value;
+        }
+
+        Self::from_grid(rows)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TraceStep {
+    Try { x: usize, y: usize, value: u8 },
+    Backtrack { x: usize, y: usize, value: u8 },
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    pub propagated: usize,
+    pub nodes: u64,
+    pub backtracks: u64,
+    pub elapsed: Duration,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GridError {
+    InvalidValue { x: usize, y: usize, value: u8 },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            GridError::InvalidValue { x, y, value } => {
+                write!(
+                    f,
+                    "Invalid value {} at ({}, {}) (must be 0 to 9)",
+                    value, x, y
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Index<(usize, usize)> for Sudoku {
+    type Output = u8;
+
+    fn index(&self, (x, y): (usize, usize)) -> &u8 {
+        &self.grid[x][y]
+    }
+}
+
+impl fmt::Display for Sudoku {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        for y in 0..9 {
+            for x in 0..9 {
+                write!(f, "{}", Self::symbol(self.grid[x][y]))?;
+            }
+
+            if y < 8 {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl str::FromStr for Sudoku {
+    type Err = ParseSudokuError;
+
+    fn from_str(string: &str) -> Result<Self, ParseSudokuError> {
+        let mut sudoku = Self::new();
+        parse_cells(string, 81, false, |i, value| {
+            sudoku.set(i % 9, i / 9, value)
+        })?;
+        Ok(sudoku)
+    }
+}
+
+fn parse_cells<F>(
+    string: &str,
+    expected: usize,
+    strict: bool,
+    mut set: F,
+) -> Result<(), ParseSudokuError>
+where
+    F: FnMut(usize, u8),
+{
+    let mut i = 0;
+
+    for (offset, ch) in string.char_indices() {
+        match ch {
+            '0' | '.' => i += 1,
+            '1'..='9' => {
+                if i < expected {
+                    set(i, (ch as u8) - b'0');
+                }
+                i += 1;
+            }
+            _ if ch.is_whitespace() && !strict => (),
+            _ => return Err(ParseSudokuError::InvalidCharacter { ch, offset }),
+        }
+    }
+
+    if i < expected {
+        return Err(ParseSudokuError::TooShort { len: i, expected });
+    }
+
+    if i > expected {
+        return Err(ParseSudokuError::TooLong { len: i, expected });
+    }
+
+    Ok(())
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseSudokuError {
+    InvalidCharacter { ch: char, offset: usize },
+    TooShort { len: usize, expected: usize },
+    TooLong { len: usize, expected: usize },
+}
+
+impl fmt::Display for ParseSudokuError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            ParseSudokuError::InvalidCharacter { ch, offset } => {
+                write!(
+                    f,
+                    "Invalid character in Sudoku grid {:?} at byte {} (valid characters are 1 to 9, '.', and 0)",
+                    ch, offset
+                )?;
+            }
+            ParseSudokuError::TooShort { len, expected } => {
+                write!(
+                    f,
+                    "Sudoku grid is too short at {} cell(s) (must be exactly {} cells)",
+                    len, expected
+                )?;
+            }
+            ParseSudokuError::TooLong { len, expected } => {
+                write!(
+                    f,
+                    "Sudoku grid is too long at {} cell(s) (must be exactly {} cells)",
+                    len, expected
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PlaceError {
+    OutOfBounds { x: usize, y: usize },
+    InvalidValue { value: u8 },
+    Conflict { conflict: Conflict },
+}
+
+impl fmt::Display for PlaceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            PlaceError::OutOfBounds { x, y } => {
+                write!(f, "Cell ({}, {}) is outside the grid", x, y)?;
+            }
+            PlaceError::InvalidValue { value } => {
+                write!(f, "Invalid value {} (must be 0 to 9)", value)?;
+            }
+            PlaceError::Conflict { conflict } => {
+                write!(f, "Placement breaks the rules: {}", conflict)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Rules {
+    regions: Regions,
+    diagonals: bool,
+    windoku: bool,
+    anti_knight: bool,
+    anti_king: bool,
+    non_consecutive: bool,
+}
+
+impl Rules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn regions(mut self, regions: Regions) -> Self {
+        self.regions = regions;
+        self
+    }
+
+    pub fn diagonals(mut self, diagonals: bool) -> Self {
+        self.diagonals = diagonals;
+        self
+    }
+
+    pub fn windoku(mut self, windoku: bool) -> Self {
+        self.windoku = windoku;
+        self
+    }
+
+    pub fn anti_knight(mut self, anti_knight: bool) -> Self {
+        self.anti_knight = anti_knight;
+        self
+    }
+
+    pub fn anti_king(mut self, anti_king: bool) -> Self {
+        self.anti_king = anti_king;
+        self
+    }
+
+    pub fn non_consecutive(mut self, non_consecutive: bool) -> Self {
+        self.non_consecutive = non_consecutive;
+        self
+    }
+
+    // Extra houses which, like rows, columns, and regions, must contain each digit exactly once
+    fn units(&self) -> impl Iterator<Item = fn(usize, usize) -> bool> {
+        const DIAGONALS: [fn(usize, usize) -> bool; 2] = [|x, y| x == y, |x, y| x + y == 8];
+
+        const WINDOWS: [fn(usize, usize) -> bool; 4] = [
+            |x, y| (1..4).contains(&x) && (1..4).contains(&y),
+            |x, y| (5..8).contains(&x) && (1..4).contains(&y),
+            |x, y| (1..4).contains(&x) && (5..8).contains(&y),
+            |x, y| (5..8).contains(&x) && (5..8).contains(&y),
+        ];
+
+        let diagonals = if self.diagonals { &DIAGONALS[..] } else { &[] };
+        let windows = if self.windoku { &WINDOWS[..] } else { &[] };
+        diagonals.iter().chain(windows).copied()
+    }
+
+    fn cell_units(&self, x: usize, y: usize) -> impl Iterator<Item = Unit> + '_ {
+        let extras = self
+            .units()
+            .enumerate()
+            .filter(move |&(_, contains)| contains(x, y))
+            .map(move |(unit, _)| match (self.diagonals, unit) {
+                (true, 0..=1) => Unit::Diagonal(unit),
+                (true, _) => Unit::Window(unit - 2),
+                (false, _) => Unit::Window(unit),
+            });
+
+        [
+            Unit::Row(y),
+            Unit::Column(x),
+            Unit::Region(self.regions.get(x, y) as usize),
+        ]
+        .into_iter()
+        .chain(extras)
+    }
+
+    // The anti-knight and anti-king rules which keep the same digit out of two cells
+    fn cell_moves(&self, x: usize, y: usize, other_x: usize, other_y: usize) -> Option<Unit> {
+        match (x.abs_diff(other_x), y.abs_diff(other_y)) {
+            (1, 2) | (2, 1) if self.anti_knight => Some(Unit::Knight),
+            (0, 1) | (1, 0) | (1, 1) if self.anti_king => Some(Unit::King),
+            _ => None,
+        }
+    }
+
+    fn has_conflicts(&self) -> bool {
+        self.anti_knight || self.anti_king || self.non_consecutive
+    }
+
+    fn offsets(&self) -> impl Iterator<Item = (isize, isize)> {
+        const KNIGHT: [(isize, isize); 4] = [(1, 2), (2, 1), (2, -1), (1, -2)];
+        const KING: [(isize, isize); 4] = [(1, 0), (1, 1), (0, 1), (1, -1)];
+
+        let knight = if self.anti_knight { &KNIGHT[..] } else { &[] };
+        let king = if self.anti_king { &KING[..] } else { &[] };
+        knight.iter().chain(king).copied()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Row(usize),
+    Column(usize),
+    Region(usize),
+    Diagonal(usize),
+    Window(usize),
+    // Not houses, but pairs of cells which the anti-knight, anti-king, and non-consecutive rules
+    // keep apart
+    Knight,
+    King,
+    Consecutive,
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Unit::Row(y) => write!(f, "row {}", y + 1),
+            Unit::Column(x) => write!(f, "column {}", x + 1),
+            Unit::Region(region) => write!(f, "region {}", region + 1),
+            Unit::Diagonal(0) => write!(f, "main diagonal"),
+            Unit::Diagonal(_) => write!(f, "anti-diagonal"),
+            Unit::Window(window) => write!(f, "window {}", window + 1),
+            Unit::Knight => write!(f, "a knight's move"),
+            Unit::King => write!(f, "a king's move"),
+            Unit::Consecutive => write!(f, "adjacent cells"),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Conflict {
+    pub a: (usize, usize),
+    pub b: (usize, usize),
+    pub value: u8,
+    pub unit: Unit,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "r{}c{} and r{}c{} ",
+            self.a.1 + 1,
+            self.a.0 + 1,
+            self.b.1 + 1,
+            self.b.0 + 1
+        )?;
+
+        match self.unit {
+            Unit::Knight | Unit::King => {
+                write!(f, "both contain {}, {} apart", self.value, self.unit)
+            }
+            Unit::Consecutive => write!(f, "contain consecutive digits in {}", self.unit),
+            _ => write!(f, "both contain {} in {}", self.value, self.unit),
+        }
+    }
+}
+
+// Builds the exact cover encoding of a grid for variants which need constraints of their own. The
+// columns are laid out as:
+//
+//   0..81     each cell holds a digit (9 * y + x)
+//   81..162   each row holds each digit (81 + 9 * y + digit - 1)
+//   162..243  each column holds each digit (162 + 9 * x + digit - 1)
+//   243..324  each region holds each digit (243 + 9 * region + digit - 1)
+//   324..     the caller's extra columns, numbered by extra_column
+//
+// followed by columns for any extra units in the rules (diagonals, windows), and then secondary
+// columns for conflict rules such as anti-knight
+#[derive(Clone, Debug, Default)]
+pub struct SudokuEncoder {
+    rules: Rules,
+    extra_columns: u16,
+}
+
+impl SudokuEncoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn extra_columns(mut self, extra_columns: u16) -> Self {
+        self.extra_columns = extra_columns;
+        self
+    }
+
+    pub fn extra_column(&self, i: u16) -> u16 {
+        assert!(i < self.extra_columns);
+        324 + i
+    }
+
+    // The callback may append columns to the row for each candidate placement. Returns None if the
+    // givens already conflict
+    pub fn encode<F>(&self, sudoku: &Sudoku, extend: F) -> Option<Encoding>
+    where
+        F: FnMut(usize, usize, u8, &mut Vec<u16>),
+    {
+        let constraints = sudoku.constraints(&self.rules).ok()?;
+        Some(sudoku.encode(&constraints, &self.rules, self.extra_columns, extend))
+    }
+}
+
+// An exact cover matrix for a grid, along with the placement represented by each of its leading
+// rows; callers may push further rows (such as killer cage combinations) which decode to nothing
+pub struct Encoding {
+    matrix: DLXMatrix<u16>,
+    placements: Vec<(usize, usize, u8)>,
+}
+
+impl Encoding {
+    pub fn matrix(&self) -> &DLXMatrix<u16> {
+        &self.matrix
+    }
+
+    pub fn push_row(&mut self, columns: &[u16]) -> usize {
+        self.matrix.push_row(columns)
+    }
+
+    pub fn placement(&self, row: usize) -> Option<(usize, usize, u8)> {
+        self.placements.get(row).copied()
+    }
+
+    pub fn decode(&self, rows: &[usize]) -> Sudoku {
+        Self::decode_rows(&self.placements, rows.iter().copied())
+    }
+
+    pub fn into_matrix(self) -> DLXMatrix<u16> {
+        self.matrix
+    }
+
+    pub fn solve(self) -> Option<Sudoku> {
+        match self.solve_until(None) {
+            Ok((solution, _)) => solution,
+            Err(TimedOut) => unreachable!(),
+        }
+    }
+
+    fn solve_until(
+        self,
+        deadline: Option<Instant>,
+    ) -> Result<(Option<Sudoku>, Statistics), TimedOut> {
+        let (solution, statistics) = self.matrix.solve_until(deadline)?;
+        let placements = &self.placements;
+
+        Ok((
+            solution.map(|solution| Self::decode_solution(placements, solution)),
+            statistics,
+        ))
+    }
+
+    pub fn solutions(self) -> impl Iterator<Item = Sudoku> {
+        let placements = self.placements;
+
+        self.matrix
+            .solutions()
+            .row_indices()
+            .map(move |rows| Self::decode_rows(&placements, rows))
+    }
+
+    fn decode_solution(placements: &[(usize, usize, u8)], mut solution: Solution<u16>) -> Sudoku {
+        let mut rows = vec![];
+
+        while let Some(row) = solution.next() {
+            rows.push(row.index(&solution));
+        }
+
+        Self::decode_rows(placements, rows)
+    }
+
+    fn decode_rows<I>(placements: &[(usize, usize, u8)], rows: I) -> Sudoku
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        let mut solved = Sudoku::new();
+
+        for row in rows {
+            if let Some(&(x, y, value)) = placements.get(row) {
+                debug_assert!(solved.get(x, y) == 0);
+                solved.set(x, y, value);
+            }
+        }
+
+        solved
+    }
+}
+
+// Pairs of candidates that may not both be placed, each sharing a secondary column
+struct Conflicts {
+    columns: u16,
+    candidates: Vec<Vec<u16>>,
+}
+
+impl Conflicts {
+    fn new(rules: &Rules) -> Self {
+        let mut conflicts = Self {
+            columns: 0,
+            candidates: vec![vec![]; 729],
+        };
+
+        for (x, y) in (0..9).flat_map(|x| (0..9).map(move |y| (x, y))) {
+            for (dx, dy) in rules.offsets() {
+                let (x2, y2) = (x as isize + dx, y as isize + dy);
+
+                if !(0..9).contains(&x2) || !(0..9).contains(&y2) {
+                    continue;
+                }
+
+                for value in 1..=9 {
+                    conflicts.add((x, y, value), (x2 as usize, y2 as usize, value));
+                }
+            }
+
+            if !rules.non_consecutive {
+                continue;
+            }
+
+            for (x2, y2) in [(x + 1, y), (x, y + 1)] {
+                if x2 >= 9 || y2 >= 9 {
+                    continue;
+                }
+
+                for value in 1..9 {
+                    conflicts.add((x, y, value), (x2, y2, value + 1));
+                    conflicts.add((x, y, value + 1), (x2, y2, value));
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    fn add(&mut self, (x1, y1, value1): (usize, usize, u8), (x2, y2, value2): (usize, usize, u8)) {
+        let column = self.columns;
+        self.columns += 1;
+
+        self.candidates[Self::index(x1, y1, value1)].push(column);
+        self.candidates[Self::index(x2, y2, value2)].push(column);
+    }
+
+    fn get(&self, x: usize, y: usize, value: u8) -> &[u16] {
+        &self.candidates[Self::index(x, y, value)]
+    }
+
+    fn index(x: usize, y: usize, value: u8) -> usize {
+        9 * (9 * y + x) + (value - 1) as usize
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Regions {
+    ids: [[u8; 9]; 9],
+}
+
+impl Regions {
+    pub fn boxes() -> Self {
+        let mut ids = [[0; 9]; 9];
+
+        for (x, column) in ids.iter_mut().enumerate() {
+            for (y, id) in column.iter_mut().enumerate() {
+                *id = (3 * (y / 3) + (x / 3)) as u8;
+            }
+        }
+
+        Self { ids }
+    }
+
+    pub fn new(ids: [[u8; 9]; 9]) -> Result<Self, RegionsError> {
+        let mut sizes = [0; 9];
+
+        for &id in ids.iter().flatten() {
+            if id >= 9 {
+                return Err(RegionsError::InvalidRegionId { id });
+            }
+            sizes[id as usize] += 1;
+        }
+
+        for (region, &size) in sizes.iter().enumerate() {
+            if size != 9 {
+                return Err(RegionsError::WrongRegionSize {
+                    region: region as u8,
+                    size,
+                });
+            }
+        }
+
+        Ok(Self { ids })
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.ids[x][y]
+    }
+}
+
+impl Default for Regions {
+    fn default() -> Self {
+        Self::boxes()
+    }
+}
+
+impl str::FromStr for Regions {
+    type Err = RegionsError;
+
+    fn from_str(string: &str) -> Result<Self, RegionsError> {
+        let mut ids = [[0; 9]; 9];
+        let mut symbols = Vec::<char>::with_capacity(9);
+        let mut i = 0;
+
+        for ch in string.chars().filter(|ch| !ch.is_whitespace()) {
+            let id = match symbols.iter().position(|&symbol| symbol == ch) {
+                Some(id) => id,
+                None if symbols.len() < 9 => {
+                    symbols.push(ch);
+                    symbols.len() - 1
+                }
+                None => return Err(RegionsError::TooManyRegions),
+            };
+
+            if i < 81 {
+                ids[i % 9][i / 9] = id as u8;
+            }
+            i += 1;
+        }
+
+        if i < 81 {
+            return Err(RegionsError::TooShort { len: i });
+        }
+
+        if i > 81 {
+            return Err(RegionsError::TooLong { len: i });
+        }
+
+        Self::new(ids)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
+pub enum RegionsError {
+    InvalidRegionId { id: u8 },
+    WrongRegionSize { region: u8, size: usize },
+    TooManyRegions,
+    TooShort { len: usize },
+    TooLong { len: usize },
+}
+
+impl fmt::Display for RegionsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            RegionsError::InvalidRegionId { id } => {
+                write!(f, "Invalid region id {} (must be in the range 0..9)", id)?;
+            }
+            RegionsError::WrongRegionSize { region, size } => {
+                write!(
+                    f,
+                    "Region {} has {} cell(s) (every region must have exactly 9 cells)",
+                    region, size
+                )?;
+            }
+            RegionsError::TooManyRegions => {
+                write!(f, "Region map has more than 9 distinct regions")?;
+            }
+            RegionsError::TooShort { len } => {
+                write!(
+                    f,
+                    "Region map is too short at {} cell(s) (must be exactly 81 cells)",
+                    len
+                )?;
+            }
+            RegionsError::TooLong { len } => {
+                write!(
+                    f,
+                    "Region map is too long at {} cell(s) (must be exactly 81 cells)",
+                    len
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct SudokuConstraints {
+    regions: Regions,
+    cell_units: [[u8; 9]; 9],
+    cells: [[u16; 9]; 9],
+    rows: [u16; 9],
+    columns: [u16; 9],
+    boxes: [u16; 9],
+    units: [u16; 6],
+}
+
+impl SudokuConstraints {
+    fn new(rules: &Rules) -> Self {
+        let mut cell_units = [[0; 9]; 9];
+
+        for (unit, contains) in rules.units().enumerate() {
+            for (x, column) in cell_units.iter_mut().enumerate() {
+                for (y, flags) in column.iter_mut().enumerate() {
+                    if contains(x, y) {
+                        *flags |= 1 << unit;
+                    }
+                }
+            }
+        }
+
+        Self {
+            regions: rules.regions,
+            cell_units,
+            cells: [[((1 << 9) - 1); 9]; 9],
+            rows: [((1 << 9) - 1); 9],
+            columns: [((1 << 9) - 1); 9],
+            boxes: [((1 << 9) - 1); 9],
+            units: [((1 << 9) - 1); 6],
+        }
+    }
+
+    fn add(&mut self, x: usize, y: usize, value: u8) -> Result<(), ()> {
+        debug_assert!((1..=9).contains(&value));
+        let value = value - 1;
+
+        let cell_units = self.cell_units[x][y];
+
+        let flags_refs = [
+            &mut self.rows[y],
+            &mut self.columns[x],
+            &mut self.boxes[self.regions.get(x, y) as usize],
+        ];
+
+        let unit_refs = self
+            .units
+            .iter_mut()
+            .enumerate()
+            .filter(|&(unit, _)| cell_units & (1 << unit) != 0)
+            .map(|(_, flags)| flags);
+
+        for flags in flags_refs.into_iter().chain(unit_refs) {
+            if *flags & (1 << value) == 0 {
+                return Err(());
+            }
+            *flags ^= 1 << value;
+        }
+
+        Ok(())
+    }
+
+    // Unlike add, tolerates givens that already conflict, which is what an editor needs when
+    // displaying pencil marks for a grid that is still being filled in
+    fn remove(&mut self, x: usize, y: usize, value: u8) {
+        debug_assert!((1..=9).contains(&value));
+        let mask = !(1 << (value - 1));
+        let cell_units = self.cell_units[x][y];
+
+        self.rows[y] &= mask;
+        self.columns[x] &= mask;
+        self.boxes[self.regions.get(x, y) as usize] &= mask;
+
+        for (unit, flags) in self.units.iter_mut().enumerate() {
+            if cell_units & (1 << unit) != 0 {
+                *flags &= mask;
+            }
+        }
+    }
+
+    fn get_mask(&self, x: usize, y: usize) -> u16 {
+        debug_assert!((0..9).contains(&x) && (0..9).contains(&y));
+
+        let cell_units = self.cell_units[x][y];
+
+        let units = self
+            .units
+            .iter()
+            .enumerate()
+            .filter(|&(unit, _)| cell_units & (1 << unit) != 0)
+            .fold((1 << 9) - 1, |candidates, (_, &flags)| candidates & flags);
+
+        self.cells[x][y]
+            & self.rows[y]
+            & self.columns[x]
+            & self.boxes[self.regions.get(x, y) as usize]
+            & units
+    }
+
+    fn restrict(&mut self, x: usize, y: usize, candidates: u16) {
+        self.cells[x][y] &= candidates;
+    }
+}
+
+// Units 0 to 8 are rows, 9 to 17 are columns, and 18 to 26 are the standard boxes
+fn unit_cells(unit: usize) -> [(usize, usize); 9] {
+    let mut cells = [(0, 0); 9];
+
+    for (i, cell) in cells.iter_mut().enumerate() {
+        *cell = match unit {
+            0..=8 => (i, unit),
+            9..=17 => (unit - 9, i),
+            _ => {
+                let b = unit - 18;
+                (3 * (b % 3) + i % 3, 3 * (b / 3) + i / 3)
+            }
+        };
+    }
+
+    cells
+}
+
+struct CandidateIterator(u16);
+
+impl Iterator for CandidateIterator {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<u8> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let value = (self.0.trailing_zeros() + 1) as u8;
+        self.0 ^= 1 << (value - 1);
+
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rng::{Rng, SplitMix64};
+    use crate::sudoku::{
+        Conflict, GridError, ParseSudokuError, PlaceError, Regions, Rules, Sudoku, SudokuEncoder,
+        TraceStep, Unit,
+    };
+    use core::str::FromStr;
+    use flate2::bufread::GzDecoder;
+    use std::collections::HashSet;
+    use std::fs;
+    use std::io;
+    use std::io::BufRead;
+    use std::path;
+
+    fn validate_solution(sudoku: Sudoku) {
+        let solution = sudoku.solve().unwrap();
+
+        assert!(solution.constraints(&Rules::default()).is_ok());
+
+        for (x, y, value) in sudoku.iter() {
+            if value == 0 {
+                continue;
+            }
+            assert!(solution.get(x, y) == value);
+        }
+    }
+
+    fn test_gzipped_data_file(filename: &'static str) {
+        let path = path::Path::new("data/sudoku").join(filename);
+        let file = fs::File::open(path).unwrap();
+        let decoder = GzDecoder::new(io::BufReader::new(file));
+        let reader = io::BufReader::new(decoder);
+
+        for line in reader.lines() {
+            let line = line.unwrap();
+            let trimmed = line.trim();
+
+            if trimmed.is_empty() {
+                continue;
+            }
+
+            let sudoku = Sudoku::from_str(trimmed).unwrap();
+            validate_solution(sudoku);
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        let sudoku = Sudoku::new();
+        validate_solution(sudoku);
+    }
+
+    #[test]
+    fn test_jigsaw() {
+        let regions = Regions::from_str(
+            "111222333
+             111222333
+             114222333
+             144555666
+             444555666
+             444555666
+             777888999
+             777888999
+             777888999",
+        )
+        .unwrap();
+        let rules = Rules::new().regions(regions);
+
+        let solution = Sudoku::new().solve_with(&rules).unwrap();
+        assert!(solution.constraints(&rules).is_ok());
+        assert!(solution.iter().all(|(_, _, value)| value != 0));
+
+        let mut puzzle = Sudoku::new();
+        for (x, y, value) in solution.iter().filter(|&(x, y, _)| (x + y) % 3 == 0) {
+            puzzle.set(x, y, value);
+        }
+
+        let solved = puzzle.solve_with(&rules).unwrap();
+        assert!(solved.constraints(&rules).is_ok());
+        for (x, y, value) in puzzle.iter().filter(|&(_, _, value)| value != 0) {
+            assert_eq!(solved.get(x, y), value);
+        }
+    }
+
+    fn validate_offsets(solution: &Sudoku, offsets: &[(isize, isize)]) {
+        assert!(solution.iter().all(|(_, _, value)| value != 0));
+
+        for (x, y, value) in solution.iter() {
+            for &(dx, dy) in offsets {
+                let (x2, y2) = (x as isize + dx, y as isize + dy);
+                if (0..9).contains(&x2) && (0..9).contains(&y2) {
+                    assert_ne!(solution.get(x2 as usize, y2 as usize), value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_anti_knight() {
+        let rules = Rules::new().anti_knight(true);
+        let grid = Sudoku::from_str(
+            "123456789456789123789123456234567891567891234891234567345678912678912345912345678",
+        )
+        .unwrap();
+
+        let mut sudoku = Sudoku::new();
+        for (x, y, value) in grid.iter().filter(|&(x, y, _)| (x + 2 * y) % 3 == 0) {
+            sudoku.set(x, y, value);
+        }
+
+        let solution = sudoku.solve_with(&rules).unwrap();
+        assert!(solution.constraints(&rules).is_ok());
+        validate_offsets(&solution, &[(1, 2), (2, 1), (2, -1), (1, -2)]);
+
+        let mut sudoku = Sudoku::new();
+        sudoku.set(2, 0, 1);
+        sudoku.set(3, 2, 1);
+        assert!(sudoku.solve().is_some());
+        assert!(sudoku.solve_with(&rules).is_none());
+        assert_eq!(sudoku.solve_until(&rules, None).unwrap().0, None);
+    }
+
+    #[test]
+    fn test_anti_king() {
+        let rules = Rules::new().anti_king(true);
+        let solution = Sudoku::new().solve_with(&rules).unwrap();
+        assert!(solution.constraints(&rules).is_ok());
+        validate_offsets(&solution, &[(1, 0), (1, 1), (0, 1), (1, -1)]);
+
+        let mut sudoku = Sudoku::new();
+        sudoku.set(2, 2, 1);
+        sudoku.set(3, 3, 1);
+        assert!(sudoku.solve().is_some());
+        assert!(sudoku.solve_with(&rules).is_none());
+    }
+
+    #[test]
+    fn test_non_consecutive() {
+        let rules = Rules::new().non_consecutive(true);
+        let solution = Sudoku::new().solve_with(&rules).unwrap();
+        assert!(solution.constraints(&rules).is_ok());
+        assert!(solution.iter().all(|(_, _, value)| value != 0));
+
+        for (x, y, value) in solution.iter() {
+            for (x2, y2) in [(x + 1, y), (x, y + 1)] {
+                if x2 < 9 && y2 < 9 {
+                    assert_ne!((solution.get(x2, y2) as i8 - value as i8).abs(), 1);
+                }
+            }
+        }
+
+        let mut sudoku = Sudoku::new();
+        sudoku.set(4, 4, 5);
+        sudoku.set(4, 5, 6);
+        assert!(sudoku.solve().is_some());
+        assert!(sudoku.solve_with(&rules).is_none());
+    }
+
+    #[test]
+    fn test_diagonals_and_windoku() {
+        let rules = Rules::new().diagonals(true).windoku(true);
+        let solution = Sudoku::new().solve_with(&rules).unwrap();
+        assert!(solution.constraints(&rules).is_ok());
+        assert!(solution.iter().all(|(_, _, value)| value != 0));
+
+        for contains in rules.units() {
+            let mut seen = 0u16;
+            for (_, _, value) in solution.iter().filter(|&(x, y, _)| contains(x, y)) {
+                seen |= 1 << value;
+            }
+            assert_eq!(seen, ((1 << 9) - 1) << 1);
+        }
+
+        let mut sudoku = Sudoku::new();
+        sudoku.set(0, 0, 1);
+        sudoku.set(8, 8, 1);
+        assert!(sudoku.solve().is_some());
+        assert!(sudoku.constraints(&rules).is_err());
+        assert!(sudoku.solve_with(&rules).is_none());
+    }
+
+    #[test]
+    fn test_count_solutions() {
+        let mut sudoku = Sudoku::from_str(
+            "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+        )
+        .unwrap();
+        assert_eq!(sudoku.count_solutions(usize::MAX), 1);
+        assert!(sudoku.has_unique_solution());
+
+        sudoku.clear(0, 0);
+        assert_eq!(sudoku.count_solutions(2), 2);
+        assert_eq!(sudoku.count_solutions(usize::MAX), 7465);
+        assert!(!sudoku.has_unique_solution());
+
+        sudoku.set(0, 0, 3);
+        sudoku.set(1, 0, 3);
+        assert_eq!(sudoku.count_solutions(usize::MAX), 0);
+
+        assert_eq!(Sudoku::new().count_solutions(1000), 1000);
+        assert_eq!(
+            Sudoku::new().count_solutions_with(&Rules::new().diagonals(true), 10),
+            10
+        );
+    }
+
+    #[test]
+    fn test_solve_all() {
+        let mut sudoku = Sudoku::from_str(
+            "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+        )
+        .unwrap();
+        let solution = sudoku.solve().unwrap();
+
+        let all = sudoku
+            .solve_all()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(all, vec![solution.to_string()]);
+
+        sudoku.clear(0, 0);
+        let mut all = sudoku
+            .solve_all()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(all.len(), 7465);
+        assert!(all.contains(&solution.to_string()));
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(all.len(), 7465);
+
+        for solved in sudoku.solve_all().take(50) {
+            assert!(solved.constraints(&Rules::default()).is_ok());
+            assert!(solved.iter().all(|(_, _, value)| value != 0));
+        }
+
+        assert_eq!(Sudoku::new().solve_all().take(3).count(), 3);
+
+        sudoku.set(0, 0, 3);
+        sudoku.set(1, 0, 3);
+        assert_eq!(sudoku.solve_all().count(), 0);
+    }
+
+    #[test]
+    fn test_minimize() {
+        let grid = Sudoku::from_str(
+            "123456789456789123789123456234567891567891234891234567345678912678912345912345678",
+        )
+        .unwrap();
+
+        let minimized = grid.minimize(&mut SplitMix64::new(7)).unwrap();
+        assert!(minimized.has_unique_solution());
+        assert_eq!(minimized.solve().unwrap().to_string(), grid.to_string());
+
+        for (x, y, value) in minimized.iter().filter(|&(_, _, value)| value != 0) {
+            let mut reduced = Sudoku {
+                grid: minimized.grid,
+            };
+            reduced.clear(x, y);
+            assert_eq!(reduced.count_solutions(2), 2);
+            assert_eq!(grid.get(x, y), value);
+        }
+
+        let in_order = grid
+            .minimize_in_order((0..9).flat_map(|y| (0..9).map(move |x| (x, y))))
+            .unwrap();
+        assert!(in_order.has_unique_solution());
+        assert_eq!(in_order.get(0, 0), 0);
+
+        assert!(Sudoku::new().minimize(&mut SplitMix64::new(7)).is_none());
+    }
+
+    #[test]
+    fn test_candidates() {
+        let mut sudoku = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+
+        assert_eq!(sudoku.candidates(2, 0).collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert_eq!(sudoku.candidates(0, 0).count(), 0);
+
+        let grid = sudoku.candidate_grid();
+        assert_eq!(grid[2][0], 0b1011);
+        assert_eq!(grid[0][0], 0);
+
+        let solution = sudoku.solve().unwrap();
+        for (x, y, value) in solution.iter() {
+            assert!(sudoku.get(x, y) != 0 || grid[x][y] & (1 << (value - 1)) != 0);
+        }
+
+        // Conflicting givens still produce pencil marks rather than failing outright
+        sudoku.set(3, 0, 5);
+        assert!(sudoku.solve().is_none());
+        assert_eq!(sudoku.candidates(2, 0).collect::<Vec<_>>(), vec![1, 2, 4]);
+        assert!(Sudoku::new().candidate_grid() == [[(1 << 9) - 1; 9]; 9]);
+    }
+
+    #[test]
+    fn test_masks() {
+        let puzzle = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let solution = puzzle.solve().unwrap();
+
+        let mask = puzzle.givens_mask();
+        assert!(mask[0][0] && mask[4][0] && !mask[2][0]);
+        assert_eq!(mask.iter().flatten().filter(|&&given| given).count(), 30);
+
+        let filled = solution.difference(&puzzle);
+        assert_eq!(filled.clue_count(), 51);
+        assert!(filled
+            .iter()
+            .all(|(x, y, value)| (value == 0) == mask[x][y]));
+        assert_eq!(puzzle.difference(&solution), Sudoku::new());
+
+        assert_eq!(puzzle.merge(&filled), Some(solution.clone()));
+        assert_eq!(filled.merge(&puzzle), Some(solution.clone()));
+        assert_eq!(puzzle.merge(&solution), Some(solution.clone()));
+
+        let mut wrong = Sudoku::new();
+        wrong.set(0, 0, 1);
+        assert_eq!(puzzle.merge(&wrong), None);
+    }
+
+    #[test]
+    fn test_feature_tensor() {
+        let sudoku = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let tensor = sudoku.to_feature_tensor();
+        assert_eq!(tensor.len(), 729);
+
+        let at = |x: usize, y: usize, value: usize| tensor[81 * (value - 1) + 9 * y + x];
+        assert_eq!((1..=9).map(|value| at(0, 0, value)).sum::<f32>(), 1.0);
+        assert_eq!(at(0, 0, 5), 1.0);
+        assert_eq!(
+            (1..=9)
+                .filter(|&value| at(2, 0, value) == 1.0)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 4]
+        );
+
+        let solution = sudoku.solve().unwrap().to_feature_tensor();
+        assert_eq!(solution.iter().sum::<f32>(), 81.0);
+        assert!(Sudoku::new()
+            .to_feature_tensor()
+            .iter()
+            .all(|&value| value == 1.0));
+    }
+
+    #[test]
+    fn test_metrics() {
+        let sudoku = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        assert_eq!(sudoku.clue_count(), 30);
+        assert_eq!(sudoku.digit_histogram(), [3, 2, 3, 2, 3, 5, 3, 5, 4]);
+        assert_eq!(sudoku.empty_cells().count(), 51);
+        assert_eq!(sudoku.empty_cells().next(), Some((0, 2)));
+        assert!(sudoku.empty_cells().all(|(x, y)| sudoku.get(x, y) == 0));
+
+        let solution = sudoku.solve().unwrap();
+        assert_eq!(solution.clue_count(), 81);
+        assert_eq!(solution.digit_histogram(), [9; 9]);
+        assert_eq!(solution.empty_cells().next(), None);
+        assert_eq!(Sudoku::new().digit_histogram(), [0; 9]);
+    }
+
+    #[test]
+    fn test_validity() {
+        let mut sudoku = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        assert!(sudoku.is_valid());
+        assert!(!sudoku.is_complete());
+        assert!(!sudoku.is_solved());
+        assert!(sudoku.violations().is_empty());
+
+        let solution = sudoku.solve().unwrap();
+        assert!(solution.is_solved());
+
+        sudoku.set(2, 0, 5);
+        assert!(!sudoku.is_valid());
+        assert_eq!(sudoku.violations(), vec![(0, 0), (2, 0)]);
+
+        sudoku.set(2, 0, 6);
+        assert!(!sudoku.is_valid());
+        assert_eq!(sudoku.violations(), vec![(0, 1), (2, 0)]);
+
+        assert!(Sudoku::new().is_valid());
+        assert!(!Sudoku::new().is_solved());
+    }
+
+    #[test]
+    fn test_conflicts() {
+        let mut sudoku = Sudoku::new();
+        sudoku.set(0, 0, 5);
+        sudoku.set(4, 4, 5);
+        sudoku.set(8, 0, 5);
+        sudoku.set(1, 1, 5);
+
+        let conflicts = sudoku.conflicts();
+        assert_eq!(conflicts.len(), 2);
+        assert!(conflicts.contains(&Conflict {
+            a: (0, 0),
+            b: (8, 0),
+            value: 5,
+            unit: Unit::Row(0)
+        }));
+        assert!(conflicts.contains(&Conflict {
+            a: (0, 0),
+            b: (1, 1),
+            value: 5,
+            unit: Unit::Region(0)
+        }));
+        assert_eq!(
+            conflicts[0].to_string(),
+            "r1c1 and r2c2 both contain 5 in region 1"
+        );
+
+        let rules = Rules::new().diagonals(true).windoku(true);
+        let conflicts = sudoku.conflicts_with(&rules);
+        assert_eq!(conflicts.len(), 6);
+        assert!(conflicts
+            .iter()
+            .any(|conflict| conflict.unit == Unit::Diagonal(0)
+                && conflict.a == (0, 0)
+                && conflict.b == (4, 4)));
+        assert!(sudoku.count_solutions_with(&rules, 1) == 0);
+
+        sudoku.clear(0, 0);
+        assert!(sudoku.conflicts().is_empty());
+        assert!(sudoku.is_valid());
+        assert_eq!(sudoku.conflicts_with(&rules).len(), 2);
+    }
+
+    #[test]
+    fn test_formats() {
+        let line =
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let sudoku = Sudoku::from_str(line).unwrap();
+
+        assert_eq!(sudoku.to_line(), line);
+        assert_eq!(sudoku.to_grid(), sudoku.to_string());
+        assert_eq!(sudoku.to_grid().lines().next(), Some("53..7...."));
+
+        let pretty = sudoku.to_pretty();
+        assert_eq!(pretty.lines().count(), 13);
+        assert_eq!(pretty.lines().nth(1), Some("│ 5 3 . │ . 7 . │ . . . │"));
+        assert_eq!(
+            Sudoku::from_str(
+                &pretty.replace(['│', '─', '┌', '┬', '┐', '├', '┼', '┤', '└', '┴', '┘'], "")
+            )
+            .unwrap()
+            .to_line(),
+            line
+        );
+
+        let pencilmarks = sudoku.to_pencilmark_string();
+        assert_eq!(pencilmarks.lines().count(), 11);
+        assert!(pencilmarks
+            .lines()
+            .next()
+            .unwrap()
+            .starts_with(" 5      3      124    | 26"));
+
+        let solution = sudoku.solve().unwrap();
+        assert_eq!(
+            solution.to_pencilmark_string().lines().next(),
+            Some(" 5 3 4 | 6 7 8 | 9 1 2")
+        );
+    }
+
+    #[test]
+    fn test_index_and_equality() {
+        let mut sudoku = Sudoku::new();
+        sudoku.set(3, 5, 7);
+        assert_eq!(sudoku[(3, 5)], 7);
+        assert_eq!(sudoku.get(3, 5), 7);
+
+        let copy = sudoku.clone();
+        assert_eq!(copy, sudoku);
+
+        sudoku.clear(3, 5);
+        assert_ne!(copy, sudoku);
+        assert_eq!(sudoku, Sudoku::new());
+
+        let set = [copy.clone(), sudoku.clone(), copy]
+            .into_iter()
+            .collect::<HashSet<_>>();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&sudoku));
+    }
+
+    #[test]
+    fn test_try_set() {
+        let mut sudoku = Sudoku::new();
+        assert!(sudoku.try_set(0, 0, 5).is_ok());
+        assert_eq!(
+            sudoku.try_set(9, 0, 5),
+            Err(PlaceError::OutOfBounds { x: 9, y: 0 })
+        );
+        assert_eq!(
+            sudoku.try_set(1, 0, 10),
+            Err(PlaceError::InvalidValue { value: 10 })
+        );
+
+        let rules = Rules::new();
+        assert!(sudoku.try_set(1, 1, 5).is_ok());
+        sudoku.clear(1, 1);
+
+        assert_eq!(
+            sudoku.try_set_with(1, 1, 5, &rules),
+            Err(PlaceError::Conflict {
+                conflict: Conflict {
+                    a: (0, 0),
+                    b: (1, 1),
+                    value: 5,
+                    unit: Unit::Region(0)
+                }
+            })
+        );
+        assert_eq!(sudoku.get(1, 1), 0);
+
+        sudoku.set(4, 4, 3);
+        assert!(sudoku.try_set_with(4, 4, 4, &rules).is_ok());
+        assert!(sudoku
+            .try_set_with(4, 4, 5, &rules.clone().diagonals(true))
+            .is_err());
+        assert_eq!(sudoku.get(4, 4), 4);
+        assert!(sudoku.try_set_with(0, 0, 0, &rules).is_ok());
+    }
+
+    #[test]
+    fn test_try_set_with_moves() {
+        let mut sudoku = Sudoku::new();
+        sudoku.set(2, 2, 5);
+
+        let rules = Rules::new().anti_knight(true);
+        assert_eq!(
+            sudoku.try_set_with(3, 4, 5, &rules),
+            Err(PlaceError::Conflict {
+                conflict: Conflict {
+                    a: (2, 2),
+                    b: (3, 4),
+                    value: 5,
+                    unit: Unit::Knight
+                }
+            })
+        );
+        assert_eq!(sudoku.get(3, 4), 0);
+        assert!(sudoku.try_set_with(3, 1, 5, &rules).is_ok());
+
+        let rules = Rules::new().anti_king(true);
+        let mut sudoku = Sudoku::new();
+        sudoku.set(2, 2, 7);
+        assert!(sudoku.try_set_with(3, 3, 7, &rules).is_err());
+        assert!(sudoku.try_set_with(4, 3, 7, &rules).is_ok());
+
+        let rules = Rules::new().non_consecutive(true);
+        let mut sudoku = Sudoku::new();
+        sudoku.set(4, 4, 5);
+        for value in [4, 6] {
+            assert_eq!(
+                sudoku.try_set_with(4, 5, value, &rules),
+                Err(PlaceError::Conflict {
+                    conflict: Conflict {
+                        a: (4, 4),
+                        b: (4, 5),
+                        value: 5,
+                        unit: Unit::Consecutive
+                    }
+                })
+            );
+        }
+        assert!(sudoku.try_set_with(5, 5, 6, &rules).is_ok());
+        assert!(sudoku.try_set_with(4, 5, 8, &rules).is_ok());
+    }
+
+    #[test]
+    fn test_move_conflicts() {
+        let mut sudoku = Sudoku::new();
+        sudoku.set(0, 2, 5);
+        sudoku.set(1, 4, 5);
+        sudoku.set(2, 2, 3);
+        sudoku.set(3, 3, 3);
+        sudoku.set(7, 7, 8);
+        sudoku.set(7, 8, 9);
+        assert!(sudoku.conflicts().is_empty());
+
+        let conflicts = sudoku.conflicts_with(&Rules::new().anti_knight(true));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts[0].to_string(),
+            "r3c1 and r5c2 both contain 5, a knight's move apart"
+        );
+
+        let conflicts = sudoku.conflicts_with(&Rules::new().anti_king(true));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].a, (2, 2));
+        assert_eq!(conflicts[0].unit, Unit::King);
+
+        let conflicts = sudoku.conflicts_with(&Rules::new().non_consecutive(true));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(
+            conflicts[0].to_string(),
+            "r8c8 and r9c8 contain consecutive digits in adjacent cells"
+        );
+    }
+
+    #[test]
+    fn test_unit_iterators() {
+        let sudoku = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+
+        let values = |unit: &mut dyn Iterator<Item = (usize, usize, u8)>| {
+            unit.map(|(_, _, value)| value).collect::<Vec<_>>()
+        };
+
+        assert_eq!(values(&mut sudoku.row(0)), vec![5, 3, 0, 0, 7, 0, 0, 0, 0]);
+        assert_eq!(
+            values(&mut sudoku.column(0)),
+            vec![5, 6, 0, 8, 4, 7, 0, 0, 0]
+        );
+        assert_eq!(values(&mut sudoku.box_(1)), vec![0, 7, 0, 1, 9, 5, 0, 0, 0]);
+        assert!(sudoku.box_(8).all(|(x, y, _)| x >= 6 && y >= 6));
+
+        let solution = sudoku.solve().unwrap();
+        assert_eq!(solution.units().count(), 27);
+        for unit in solution.units() {
+            let mut unit = unit.map(|(_, _, value)| value).collect::<Vec<_>>();
+            unit.sort_unstable();
+            assert_eq!(unit, (1..=9).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_decode() {
+        let sudoku = Sudoku::from_str(
+            "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+        )
+        .unwrap();
+        let rules = Rules::new();
+        let constraints = sudoku.constraints(&rules).unwrap();
+        let expected = sudoku.solve().unwrap();
+
+        // Extra columns and rows that decode to nothing must not disturb the mapping from rows to
+        // placements, whatever order the columns of each row end up in
+        for hooked in [expected.get(1, 0), expected.get(1, 0) % 9 + 1] {
+            let mut encoding = sudoku.encode(&constraints, &rules, 2, |x, y, value, row| {
+                if (x, y, value) == (1, 0, hooked) {
+                    row.insert(0, 325);
+                }
+                row.reverse();
+            });
+            encoding.matrix.push_row(&[324]);
+            encoding.matrix.push_row(&[325, 324]);
+
+            let solutions = encoding.solutions().collect::<Vec<_>>();
+            assert_eq!(solutions, vec![expected.clone()]);
+        }
+    }
+
+    #[test]
+    fn test_sudoku_encoder() {
+        // An X-sudoku with only the main diagonal, built from the public encoder
+        let encoder = SudokuEncoder::new().extra_columns(9);
+        let mut encoding = encoder
+            .encode(&Sudoku::new(), |x, y, value, row| {
+                if x == y {
+                    row.push(encoder.extra_column(value as u16 - 1));
+                }
+            })
+            .unwrap();
+        assert_eq!(encoding.placement(0), Some((0, 0, 1)));
+        assert_eq!(encoding.matrix().columns(), 333);
+
+        // Rows pushed after the placements decode to nothing
+        let extra = encoding.push_row(&[encoder.extra_column(0)]);
+        assert_eq!(encoding.placement(extra), None);
+
+        let mut solution = encoding.into_matrix().solutions().row_indices();
+        let rows = solution.next().unwrap();
+
+        let encoding = encoder.encode(&Sudoku::new(), |_, _, _, _| ()).unwrap();
+        let decoded = encoding.decode(&rows);
+        assert!(decoded.is_solved());
+
+        let mut diagonal = (0..9).map(|i| decoded.get(i, i)).collect::<Vec<_>>();
+        diagonal.sort_unstable();
+        assert_eq!(diagonal, (1..=9).collect::<Vec<_>>());
+
+        let mut invalid = Sudoku::new();
+        invalid.set(0, 0, 1);
+        invalid.set(1, 0, 1);
+        assert!(encoder.encode(&invalid, |_, _, _, _| ()).is_none());
+
+        let windoku = SudokuEncoder::new().rules(Rules::new().windoku(true));
+        let solution = windoku
+            .encode(&Sudoku::new(), |_, _, _, _| ())
+            .unwrap()
+            .solve();
+        assert!(solution
+            .unwrap()
+            .conflicts_with(&Rules::new().windoku(true))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_solve_forced() {
+        let easy = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        assert_eq!(easy.solve_forced(), easy.solve());
+
+        assert_eq!(Sudoku::new().solve_forced(), Some(Sudoku::new()));
+
+        // Emptying two rows leaves several ways to refill them, and the cells which agree across
+        // all of them are forced
+        let mut puzzle = easy.solve().unwrap();
+        for x in 0..9 {
+            puzzle.clear(x, 0);
+            puzzle.clear(x, 3);
+        }
+
+        let solutions = puzzle.solve_all().collect::<Vec<_>>();
+        assert!(solutions.len() > 1);
+
+        let forced = puzzle.solve_forced().unwrap();
+        for (x, y, value) in solutions[0].iter() {
+            let agreed = solutions.iter().all(|solution| solution.get(x, y) == value);
+            assert_eq!(forced.get(x, y), if agreed { value } else { 0 });
+        }
+        assert!(forced.empty_cells().count() > 0);
+
+        let mut contradiction = Sudoku::new();
+        contradiction.set(0, 0, 1);
+        contradiction.set(1, 0, 1);
+        assert!(contradiction.solve_forced().is_none());
+    }
+
+    #[test]
+    fn test_propagate() {
+        let easy = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let (propagated, _) = easy.propagate(&Rules::new()).unwrap();
+        assert!(propagated.is_solved());
+        assert_eq!(easy.solve(), Some(propagated));
+
+        let hard = Sudoku::from_str(
+            "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+        )
+        .unwrap();
+        let (propagated, _) = hard.propagate(&Rules::new()).unwrap();
+        assert!(!propagated.is_complete());
+        assert!(hard
+            .iter()
+            .all(|(x, y, value)| value == 0 || propagated.get(x, y) == value));
+
+        // Row 1 needs a 9, but the only cell that could hold it sees another 9
+        let mut contradiction = Sudoku::from_str(
+            "12345678.........9...............................................................",
+        )
+        .unwrap();
+        assert!(contradiction.propagate(&Rules::new()).is_none());
+        assert!(contradiction.solve().is_none());
+        assert_eq!(contradiction.count_solutions(1), 0);
+
+        contradiction.clear(8, 1);
+        assert!(contradiction.propagate(&Rules::new()).is_some());
+    }
+
+    #[test]
+    fn test_add_clue_until_unique() {
+        let mut rng = SplitMix64::new(7);
+        let mut sudoku = Sudoku::from_str(
+            "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+        )
+        .unwrap();
+
+        for (x, y) in [(0, 0), (4, 0), (3, 1), (6, 1)] {
+            sudoku.clear(x, y);
+        }
+        assert!(!sudoku.has_unique_solution());
+
+        let unique = sudoku.add_clue_until_unique(&mut rng).unwrap();
+        assert!(unique.has_unique_solution());
+        assert!(sudoku
+            .iter()
+            .all(|(x, y, value)| value == 0 || unique.get(x, y) == value));
+        assert_eq!(unique.solve(), sudoku.solve_all().next());
+        assert_ne!(unique.solve(), None);
+
+        let empty = Sudoku::new().add_clue_until_unique(&mut rng).unwrap();
+        assert!(empty.has_unique_solution());
+        assert!(empty.iter().filter(|&(_, _, value)| value != 0).count() >= 17);
+
+        let mut invalid = Sudoku::new();
+        invalid.set(0, 0, 1);
+        invalid.set(1, 0, 1);
+        assert!(invalid.add_clue_until_unique(&mut rng).is_none());
+    }
+
+    #[test]
+    fn test_generate_with_pattern() {
+        let mut rng = SplitMix64::new(3);
+
+        // Rotationally symmetric, with 36 clues
+        let mut pattern = [[false; 9]; 9];
+        for (x, column) in pattern.iter_mut().enumerate() {
+            for (y, clue) in column.iter_mut().enumerate() {
+                *clue = (x + 2 * y) % 9 < 4 || (8 - x + 2 * (8 - y)) % 9 < 4;
+            }
+        }
+        assert!((0..81).all(|i| pattern[i % 9][i / 9] == pattern[8 - i % 9][8 - i / 9]));
+
+        let puzzle = Sudoku::generate_with_pattern(&pattern, &mut rng).unwrap();
+        assert!(puzzle.has_unique_solution());
+        assert!(puzzle
+            .iter()
+            .all(|(x, y, value)| (value != 0) == pattern[x][y]));
+
+        let other = Sudoku::generate_with_pattern(&pattern, &mut rng).unwrap();
+        assert_ne!(puzzle, other);
+
+        assert!(Sudoku::generate_with_pattern(&[[false; 9]; 9], &mut rng).is_none());
+    }
+
+    #[test]
+    fn test_from_grid() {
+        let line =
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let sudoku = Sudoku::from_str(line).unwrap();
+
+        let mut cells = [0; 81];
+        for (i, ch) in line.chars().enumerate() {
+            cells[i] = ch.to_digit(10).unwrap_or(0) as u8;
+        }
+
+        let mut rows = [[0; 9]; 9];
+        for (i, &value) in cells.iter().enumerate() {
+            rows[i / 9][i % 9] = value;
+        }
+
+        assert_eq!(Sudoku::from_grid(rows), Ok(sudoku.clone()));
+        assert_eq!(Sudoku::try_from(rows), Ok(sudoku.clone()));
+        assert_eq!(Sudoku::try_from(&cells), Ok(sudoku));
+
+        rows[2][7] = 10;
+        assert_eq!(
+            Sudoku::from_grid(rows),
+            Err(GridError::InvalidValue {
+                x: 7,
+                y: 2,
+                value: 10
+            })
+        );
+
+        cells[80] = 255;
+        assert!(Sudoku::try_from(&cells).is_err());
+    }
+
+    #[test]
+    fn test_random_filled() {
+        let mut rng = SplitMix64::new(11);
+
+        let grids = (0..20)
+            .map(|_| Sudoku::random_filled(&mut rng))
+            .collect::<HashSet<_>>();
+        assert_eq!(grids.len(), 20);
+        assert!(grids.iter().all(Sudoku::is_solved));
+
+        // Every digit should turn up in the corner over enough grids
+        let corners = grids
+            .iter()
+            .map(|grid| grid.get(0, 0))
+            .collect::<HashSet<_>>();
+        assert!(corners.len() > 5);
+
+        let rules = Rules::new().diagonals(true);
+        let grid = Sudoku::random_filled_with(&rules, &mut rng).unwrap();
+        assert!(grid.is_solved());
+        assert!(grid.conflicts_with(&rules).is_empty());
+    }
+
+    #[test]
+    fn test_solve_with_stats() {
+        let easy = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let (solution, stats) = easy.solve_with_stats();
+        assert_eq!(solution, easy.solve());
+        assert_eq!(stats.propagated, 51);
+        assert_eq!(stats.nodes, 0);
+
+        let hard = Sudoku::from_str(
+            "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+        )
+        .unwrap();
+        let (solution, stats) = hard.solve_with_stats();
+        assert_eq!(solution, hard.solve());
+        assert!(stats.nodes > 0 && stats.backtracks > 0);
+        assert!(stats.propagated < 64);
+
+        let mut invalid = Sudoku::new();
+        invalid.set(0, 0, 1);
+        invalid.set(1, 0, 1);
+        let (solution, stats) = invalid.solve_with_stats();
+        assert!(solution.is_none());
+        assert_eq!((stats.nodes, stats.propagated), (0, 0));
+    }
+
+    #[test]
+    fn test_solve_with_filter() {
+        let rules = Rules::default();
+
+        // Futoshiki-style greater-than signs, as (greater, lesser) pairs
+        let signs = [
+            ((0, 0), (1, 0)),
+            ((1, 0), (2, 0)),
+            ((4, 4), (4, 5)),
+            ((8, 7), (8, 8)),
+            ((8, 8), (7, 8)),
+        ];
+
+        let solution = Sudoku::new()
+            .solve_with_filter(&rules, |partial, x, y, value| {
+                signs.iter().all(|&(greater, lesser)| {
+                    if greater == (x, y) {
+                        let other = partial.get(lesser.0, lesser.1);
+                        other == 0 || value > other
+                    } else if lesser == (x, y) {
+                        let other = partial.get(greater.0, greater.1);
+                        other == 0 || value < other
+                    } else {
+                        true
+                    }
+                })
+            })
+            .unwrap();
+        assert!(solution.is_solved());
+        assert!(signs.iter().all(|&(greater, lesser)| {
+            solution.get(greater.0, greater.1) > solution.get(lesser.0, lesser.1)
+        }));
+
+        let mut sudoku = Sudoku::new();
+        sudoku.set(4, 4, 5);
+        let solution = sudoku
+            .solve_with_filter(&rules, |_, x, y, value| (x + y) % 2 == 0 || value != 5)
+            .unwrap();
+        assert!(solution.is_solved());
+        assert!(solution
+            .iter()
+            .all(|(x, y, value)| (x + y) % 2 == 0 || value != 5));
+
+        assert!(Sudoku::new()
+            .solve_with_filter(&rules, |_, x, y, value| (x, y) != (0, 0) || value > 9)
+            .is_none());
+    }
+
+    #[test]
+    fn test_solve_traced() {
+        let sudoku = Sudoku::from_str(
+            "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+        )
+        .unwrap();
+        let (solution, trace) = sudoku.solve_traced();
+        assert_eq!(solution, sudoku.solve());
+
+        let rules = Rules::default();
+        let constraints = sudoku.constraints(&rules).unwrap();
+        let (_, stats) = sudoku
+            .encode(&constraints, &rules, 0, |_, _, _, _| ())
+            .matrix
+            .solve_with_statistics();
+        let solved = solution.unwrap();
+        let tries = trace
+            .iter()
+            .filter(|step| matches!(step, TraceStep::Try { .. }))
+            .count();
+        assert_eq!(tries + sudoku.clue_count(), stats.nodes as usize);
+        assert_eq!(trace.len() - tries, stats.backtracks as usize);
+
+        // Replaying the trace reconstructs the solution
+        let mut replay = sudoku.clone();
+        for step in &trace {
+            match *step {
+                TraceStep::Try { x, y, value } => {
+                    assert_eq!(replay.get(x, y), 0);
+                    replay.set(x, y, value);
+                }
+                TraceStep::Backtrack { x, y, value } => {
+                    assert_eq!(replay.get(x, y), value);
+                    replay.clear(x, y);
+                }
+            }
+        }
+        assert_eq!(replay, solved);
+
+        let mut invalid = Sudoku::new();
+        invalid.set(0, 0, 1);
+        invalid.set(1, 0, 1);
+        assert_eq!(invalid.solve_traced(), (None, vec![]));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = SplitMix64::new(11);
+        let mut grids = vec![Sudoku::new()];
+
+        for _ in 0..10 {
+            let filled = Sudoku::random_filled(&mut rng);
+            let mut sparse = filled.clone();
+            for (x, y, _) in filled.iter() {
+                if rng.below(3) != 0 {
+                    sparse.clear(x, y);
+                }
+            }
+
+            // Round-tripping doesn't care whether the grid is valid
+            let mut invalid = sparse.clone();
+            invalid.set(0, 0, 1);
+            invalid.set(1, 0, 1);
+
+            grids.extend([filled, sparse, invalid]);
+        }
+
+        for sudoku in grids {
+            let line = sudoku.to_line();
+            assert_eq!(line.len(), 81);
+            assert_eq!(Sudoku::from_str(&line), Ok(sudoku.clone()));
+            assert_eq!(Sudoku::from_str_strict(&line), Ok(sudoku.clone()));
+            assert_eq!(Sudoku::from_str(&sudoku.to_string()), Ok(sudoku));
+        }
+
+        // An empty grid is well-formed; a short or padded line is not
+        assert!(Sudoku::from_str_strict(&".".repeat(81)).is_ok());
+        assert!(Sudoku::from_str_strict(&".".repeat(80)).is_err());
+        assert!(Sudoku::from_str_strict(&format!(" {}", ".".repeat(81))).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let line =
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+        let grid = Sudoku::from_str(line).unwrap().to_string();
+
+        assert!(Sudoku::from_str(&grid).is_ok());
+        assert!(Sudoku::from_str_strict(line).is_ok());
+        assert_eq!(
+            Sudoku::from_str_strict(&grid).unwrap_err(),
+            ParseSudokuError::InvalidCharacter {
+                ch: '\n',
+                offset: 9
+            }
+        );
+
+        assert_eq!(
+            Sudoku::from_str(&line[1..]).unwrap_err(),
+            ParseSudokuError::TooShort {
+                len: 80,
+                expected: 81
+            }
+        );
+        assert_eq!(
+            Sudoku::from_str(&format!("{}.", line)).unwrap_err(),
+            ParseSudokuError::TooLong {
+                len: 82,
+                expected: 81
+            }
+        );
+
+        let error = Sudoku::from_str(&line.replacen('7', "x", 1)).unwrap_err();
+        assert_eq!(
+            error,
+            ParseSudokuError::InvalidCharacter { ch: 'x', offset: 4 }
+        );
+        assert!(error.to_string().contains("at byte 4"));
+    }
+
+    #[test]
+    fn test_regions_errors() {
+        assert!(Regions::from_str("123456789").is_err());
+        assert!(Regions::from_str(&"0".repeat(81)).is_err());
+        assert!(Regions::from_str(&"0123456789".repeat(9)[..81]).is_err());
+
+        let mut ids = [[0; 9]; 9];
+        ids[0][0] = 9;
+        assert!(Regions::new(ids).is_err());
+        assert_eq!(Regions::default(), Regions::boxes());
+    }
+
+    #[test]
+    fn test_top1465() {
+        test_gzipped_data_file("top1465.list.gz");
+    }
+
+    #[test]
+    fn test_kaggle() {
+        test_gzipped_data_file("kaggle.list.gz");
+    }
+
+    #[test]
+    fn test_royle17() {
+        test_gzipped_data_file("royle17.list.gz");
+    }
+}
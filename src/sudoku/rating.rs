@@ -0,0 +1,151 @@
+use crate::rng::Rng;
+use crate::sudoku::logic::{LogicalSolver, Technique};
+use crate::sudoku::{Rules, Sudoku};
+
+use core::fmt;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    Extreme,
+}
+
+impl Difficulty {
+    fn from_technique(technique: Option<Technique>) -> Self {
+        match technique {
+            None | Some(Technique::HiddenSingle) => Difficulty::Easy,
+            Some(Technique::NakedSingle) => Difficulty::Medium,
+            Some(
+                Technique::PointingCandidates
+                | Technique::ClaimingCandidates
+                | Technique::NakedPair
+                | Technique::HiddenPair
+                | Technique::NakedTriple
+                | Technique::HiddenTriple,
+            ) => Difficulty::Hard,
+            Some(
+                Technique::XWing
+                | Technique::Swordfish
+                | Technique::XYWing
+                | Technique::SimpleColoring,
+            ) => Difficulty::Expert,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let name = match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+            Difficulty::Extreme => "extreme",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rating {
+    pub difficulty: Difficulty,
+    pub score: u32,
+    pub hardest: Option<Technique>,
+}
+
+impl Sudoku {
+    pub fn rate(&self) -> Option<Rating> {
+        if !self.has_unique_solution() {
+            return None;
+        }
+
+        let mut solver = LogicalSolver::new(self)?;
+        let hardest = solver.by_ref().map(|step| step.technique()).max();
+
+        if solver.is_solved() {
+            return Some(Rating {
+                difficulty: Difficulty::from_technique(hardest),
+                score: hardest.map(Technique::weight).unwrap_or(0),
+                hardest,
+            });
+        }
+
+        // Puzzles beyond the logical solver are scored on the effort the exact cover search needs,
+        // starting from wherever the logical solver got stuck
+        let rules = Rules::default();
+        let stuck = solver.sudoku();
+        let constraints = stuck.constraints(&rules).ok()?;
+        let (_, statistics) = stuck
+            .encode(&constraints, &rules, 0, |_, _, _, _| ())
+            .matrix
+            .solve_with_statistics();
+
+        Some(Rating {
+            difficulty: Difficulty::Extreme,
+            score: 100 + statistics.nodes.min(u32::MAX as u64 - 100) as u32,
+            hardest,
+        })
+    }
+
+    // Minimal puzzles from random grids are mostly easy to hard; expert and extreme ones can take
+    // many attempts, hence the bound
+    pub fn generate_with_difficulty(target: Difficulty, rng: &mut impl Rng) -> Option<Sudoku> {
+        const ATTEMPTS: usize = 10000;
+
+        for _ in 0..ATTEMPTS {
+            let puzzle = Sudoku::random_filled(rng).minimize(rng)?;
+
+            if puzzle.rate().map(|rating| rating.difficulty) == Some(target) {
+                return Some(puzzle);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rng::SplitMix64;
+    use crate::sudoku::{Difficulty, Sudoku, Technique};
+    use core::str::FromStr;
+
+    #[test]
+    fn test_rate() {
+        let easy = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let rating = easy.rate().unwrap();
+        assert_eq!(rating.difficulty, Difficulty::Easy);
+        assert_eq!(rating.hardest, Some(Technique::HiddenSingle));
+        assert_eq!(rating.score, Technique::HiddenSingle.weight());
+
+        let solved = easy.solve().unwrap().rate().unwrap();
+        assert_eq!(solved.difficulty, Difficulty::Easy);
+        assert_eq!(solved.score, 0);
+        assert_eq!(solved.hardest, None);
+
+        let extreme = Sudoku::from_str(
+            "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+        )
+        .unwrap();
+        let rating = extreme.rate().unwrap();
+        assert_eq!(rating.difficulty, Difficulty::Extreme);
+        assert!(rating.score > 100);
+
+        assert!(Sudoku::new().rate().is_none());
+
+        let mut rng = SplitMix64::new(7);
+        for target in [Difficulty::Easy, Difficulty::Hard] {
+            let puzzle = Sudoku::generate_with_difficulty(target, &mut rng).unwrap();
+            assert_eq!(puzzle.rate().unwrap().difficulty, target);
+        }
+
+        assert!(Difficulty::Easy < Difficulty::Extreme);
+        assert_eq!(Difficulty::Hard.to_string(), "hard");
+    }
+}
@@ -0,0 +1,343 @@
+use crate::sudoku::{CandidateIterator, Sudoku};
+
+use core::fmt::Write;
+
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    cell_size: u32,
+    given_color: String,
+    solved_color: String,
+    pencil_marks: bool,
+    solution: Option<Sudoku>,
+}
+
+impl RenderOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cell_size(mut self, cell_size: u32) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    pub fn given_color(mut self, color: &str) -> Self {
+        self.given_color = color.to_string();
+        self
+    }
+
+    pub fn solved_color(mut self, color: &str) -> Self {
+        self.solved_color = color.to_string();
+        self
+    }
+
+    pub fn pencil_marks(mut self, pencil_marks: bool) -> Self {
+        self.pencil_marks = pencil_marks;
+        self
+    }
+
+    // Cells which are empty in the puzzle are filled from the solution in the solved color
+    pub fn solution(mut self, solution: &Sudoku) -> Self {
+        self.solution = Some(solution.clone());
+        self
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 40,
+            given_color: "#000000".to_string(),
+            solved_color: "#1f5fbf".to_string(),
+            pencil_marks: false,
+            solution: None,
+        }
+    }
+}
+
+enum Cell {
+    Given(u8),
+    Solved(u8),
+    Candidates(u16),
+}
+
+impl Sudoku {
+    pub fn to_svg(&self, options: &RenderOptions) -> String {
+        let size = options.cell_size;
+        let side = 9 * size;
+        let mut svg = String::new();
+
+        write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="-2 -2 {} {}">"#,
+            side + 4,
+            side + 4,
+            side + 4,
+            side + 4
+        )
+        .unwrap();
+        write!(
+            svg,
+            r#"<rect width="{}" height="{}" fill="white"/>"#,
+            side, side
+        )
+        .unwrap();
+
+        for i in 0..=9 {
+            let offset = i * size;
+            let width = if i % 3 == 0 { 3 } else { 1 };
+
+            write!(
+                svg,
+                r#"<line x1="{}" y1="0" x2="{}" y2="{}" stroke="black" stroke-width="{}"/>"#,
+                offset, offset, side, width
+            )
+            .unwrap();
+            write!(
+                svg,
+                r#"<line x1="0" y1="{}" x2="{}" y2="{}" stroke="black" stroke-width="{}"/>"#,
+                offset, side, offset, width
+            )
+            .unwrap();
+        }
+
+        for (x, y, cell) in self.render_cells(options) {
+            let (left, top) = (x as u32 * size, y as u32 * size);
+
+            let (value, color) = match cell {
+                Cell::Given(value) => (value, &options.given_color),
+                Cell::Solved(value) => (value, &options.solved_color),
+                Cell::Candidates(candidates) => {
+                    for value in CandidateIterator(candidates) {
+                        let (column, row) = ((value as u32 - 1) % 3, (value as u32 - 1) / 3);
+                        write!(
+                            svg,
+                            r##"<text x="{}" y="{}" font-size="{}" fill="#808080" text-anchor="middle" dominant-baseline="central">{}</text>"##,
+                            left + (2 * column + 1) * size / 6,
+                            top + (2 * row + 1) * size / 6,
+                            size / 4,
+                            value
+                        )
+                        .unwrap();
+                    }
+                    continue;
+                }
+            };
+
+            write!(
+                svg,
+                r#"<text x="{}" y="{}" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                left + size / 2,
+                top + size / 2,
+                3 * size / 5,
+                escape(color),
+                value
+            )
+            .unwrap();
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+
+    pub fn to_html(&self, options: &RenderOptions) -> String {
+        let size = options.cell_size;
+        let mut html = String::new();
+
+        write!(
+            html,
+            r#"<table style="border-collapse: collapse; border: 3px solid black; font-family: sans-serif; text-align: center">"#
+        )
+        .unwrap();
+
+        let cells = self.render_cells(options).collect::<Vec<_>>();
+
+        for y in 0..9 {
+            html.push_str("<tr>");
+
+            for x in 0..9 {
+                let bottom = if y % 3 == 2 { 3 } else { 1 };
+                let right = if x % 3 == 2 { 3 } else { 1 };
+
+                write!(
+                    html,
+                    r#"<td style="width: {}px; height: {}px; padding: 0; border: 1px solid black; border-bottom-width: {}px; border-right-width: {}px; "#,
+                    size, size, bottom, right
+                )
+                .unwrap();
+
+                // Cells are yielded in column-major order
+                match cells[9 * x + y].2 {
+                    Cell::Given(value) => write!(
+                        html,
+                        r#"font-size: {}px; color: {}">{}"#,
+                        3 * size / 5,
+                        escape(&options.given_color),
+                        value
+                    ),
+                    Cell::Solved(value) => write!(
+                        html,
+                        r#"font-size: {}px; color: {}">{}"#,
+                        3 * size / 5,
+                        escape(&options.solved_color),
+                        value
+                    ),
+                    Cell::Candidates(candidates) => {
+                        let marks = CandidateIterator(candidates)
+                            .map(|value| value.to_string())
+                            .collect::<Vec<_>>();
+                        write!(
+                            html,
+                            r#"font-size: {}px; color: #808080">{}"#,
+                            size / 4,
+                            marks.join(" ")
+                        )
+                    }
+                }
+                .unwrap();
+
+                html.push_str("</td>");
+            }
+
+            html.push_str("</tr>");
+        }
+
+        html.push_str("</table>");
+        html
+    }
+
+    // A TikZ picture in units of one cell, with y running up from the bottom row as TikZ expects
+    pub fn to_latex(&self, candidates: bool) -> String {
+        let mut latex = String::new();
+
+        latex.push_str("\\begin{tikzpicture}[scale=0.6]\n");
+        latex.push_str("  \\draw[thin] (0,0) grid (9,9);\n");
+        latex.push_str("  \\draw[very thick,step=3] (0,0) grid (9,9);\n");
+
+        let options = RenderOptions::new().pencil_marks(candidates);
+
+        for (x, y, cell) in self.render_cells(&options) {
+            let bottom = 8 - y;
+
+            match cell {
+                Cell::Given(value) | Cell::Solved(value) => {
+                    writeln!(
+                        latex,
+                        "  \\node[font=\\Large] at ({}.5,{}.5) {{{}}};",
+                        x, bottom, value
+                    )
+                    .unwrap();
+                }
+                Cell::Candidates(candidates) => {
+                    for value in CandidateIterator(candidates) {
+                        let (column, row) = ((value as usize - 1) % 3, (value as usize - 1) / 3);
+                        writeln!(
+                            latex,
+                            "  \\node[font=\\tiny,gray] at ({:.3},{:.3}) {{{}}};",
+                            x as f64 + (2 * column + 1) as f64 / 6.0,
+                            (bottom + 1) as f64 - (2 * row + 1) as f64 / 6.0,
+                            value
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+        }
+
+        latex.push_str("\\end{tikzpicture}\n");
+        latex
+    }
+
+    fn render_cells<'a>(
+        &'a self,
+        options: &'a RenderOptions,
+    ) -> impl Iterator<Item = (usize, usize, Cell)> + 'a {
+        let candidates = if options.pencil_marks {
+            self.candidate_grid()
+        } else {
+            [[0; 9]; 9]
+        };
+
+        self.iter().map(move |(x, y, value)| {
+            let solved = options
+                .solution
+                .as_ref()
+                .map(|solution| solution.get(x, y))
+                .unwrap_or(0);
+
+            let cell = match (value, solved) {
+                (0, 0) => Cell::Candidates(candidates[x][y]),
+                (0, solved) => Cell::Solved(solved),
+                (value, _) => Cell::Given(value),
+            };
+
+            (x, y, cell)
+        })
+    }
+}
+
+fn escape(string: &str) -> String {
+    string
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{RenderOptions, Sudoku};
+    use core::str::FromStr;
+
+    #[test]
+    fn test_render() {
+        let sudoku = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+
+        let svg = sudoku.to_svg(&RenderOptions::new());
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<line").count(), 20);
+        assert_eq!(svg.matches("<text").count(), 30);
+        assert!(svg.contains(r#"width="364""#));
+
+        let options = RenderOptions::new()
+            .cell_size(60)
+            .given_color("black")
+            .solved_color("red")
+            .solution(&sudoku.solve().unwrap());
+        let svg = sudoku.to_svg(&options);
+        assert_eq!(svg.matches(r#"fill="black""#).count(), 30);
+        assert_eq!(svg.matches(r#"fill="red""#).count(), 51);
+
+        let svg = sudoku.to_svg(&RenderOptions::new().pencil_marks(true));
+        let marks = sudoku
+            .candidate_grid()
+            .iter()
+            .flatten()
+            .map(|mask| mask.count_ones() as usize)
+            .sum::<usize>();
+        assert_eq!(svg.matches("<text").count(), 30 + marks);
+
+        let html = sudoku.to_html(&options);
+        assert!(html.starts_with("<table") && html.ends_with("</table>"));
+        assert_eq!(html.matches("<tr>").count(), 9);
+        assert_eq!(html.matches("<td").count(), 81);
+        assert_eq!(html.matches("color: red").count(), 51);
+        assert!(html.contains(r#"color: black">5</td>"#));
+
+        let latex = sudoku.to_latex(false);
+        assert!(latex.starts_with("\\begin{tikzpicture}"));
+        assert!(latex.ends_with("\\end{tikzpicture}\n"));
+        assert_eq!(latex.matches("\\node").count(), 30);
+        assert!(latex.contains("\\node[font=\\Large] at (0.5,8.5) {5};"));
+
+        let latex = Sudoku::new().to_latex(true);
+        assert_eq!(latex.matches("\\node[font=\\tiny,gray]").count(), 729);
+        assert!(latex.contains("at (0.167,8.833) {1};"));
+
+        let html = Sudoku::new().to_html(&RenderOptions::new().pencil_marks(true));
+        assert!(html.contains(">1 2 3 4 5 6 7 8 9</td>"));
+    }
+}
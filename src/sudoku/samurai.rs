@@ -0,0 +1,274 @@
+use crate::sudoku::{parse_cells, CandidateIterator, ParseSudokuError, Sudoku};
+use crate::DLXMatrix;
+
+use core::fmt;
+use core::str;
+
+const SIZE: usize = 21;
+const CELLS: usize = 369;
+const ORIGINS: [(usize, usize); 5] = [(0, 0), (12, 0), (6, 6), (0, 12), (12, 12)];
+
+#[derive(Debug)]
+pub struct Samurai {
+    grid: [[u8; SIZE]; SIZE],
+}
+
+impl Samurai {
+    pub fn new() -> Self {
+        Self {
+            grid: [[0; SIZE]; SIZE],
+        }
+    }
+
+    pub fn contains(x: usize, y: usize) -> bool {
+        ORIGINS
+            .iter()
+            .any(|&(ox, oy)| (ox..ox + 9).contains(&x) && (oy..oy + 9).contains(&y))
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        assert!(Self::contains(x, y));
+        assert!(value <= 9);
+        self.grid[x][y] = value;
+    }
+
+    pub fn clear(&mut self, x: usize, y: usize) {
+        assert!(Self::contains(x, y));
+        self.grid[x][y] = 0;
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        assert!(Self::contains(x, y));
+        self.grid[x][y]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, usize, u8)> + '_ {
+        (0..SIZE)
+            .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+            .filter(|&(x, y)| Self::contains(x, y))
+            .map(move |(x, y)| (x, y, self.grid[x][y]))
+    }
+
+    pub fn grid(&self, i: usize) -> Sudoku {
+        let (ox, oy) = ORIGINS[i];
+        let mut sudoku = Sudoku::new();
+
+        for x in 0..9 {
+            for y in 0..9 {
+                sudoku.set(x, y, self.grid[ox + x][oy + y]);
+            }
+        }
+
+        sudoku
+    }
+
+    pub fn solve(&self) -> Option<Samurai> {
+        let layout = Layout::new();
+        let mut units = vec![(1u16 << 9) - 1; layout.units];
+
+        for (cell, (x, y, value)) in self.iter().enumerate() {
+            if value == 0 {
+                continue;
+            }
+
+            for &unit in &layout.cell_units[cell] {
+                if units[unit] & (1 << (value - 1)) == 0 {
+                    return None;
+                }
+                units[unit] ^= 1 << (value - 1);
+            }
+
+            debug_assert!(layout.cells[cell] == (x, y));
+        }
+
+        let mut matrix = DLXMatrix::<u16>::new((CELLS + 9 * layout.units) as u16);
+        let mut row = Vec::with_capacity(4);
+        let mut placements = vec![];
+
+        for (cell, (x, y, value)) in self.iter().enumerate() {
+            let candidates = if value == 0 {
+                layout.cell_units[cell]
+                    .iter()
+                    .fold((1 << 9) - 1, |candidates, &unit| candidates & units[unit])
+            } else {
+                1 << (value - 1)
+            };
+
+            for value in CandidateIterator(candidates) {
+                let digit = (value - 1) as usize;
+
+                row.clear();
+                row.push(cell as u16);
+                row.extend(
+                    layout.cell_units[cell]
+                        .iter()
+                        .map(|&unit| (CELLS + 9 * unit + digit) as u16),
+                );
+                matrix.push_row(&row);
+                placements.push((x, y, value));
+            }
+        }
+
+        let mut solution = matrix.solve()?;
+        let mut solved = Samurai::new();
+
+        while let Some(row) = solution.next() {
+            let (x, y, value) = placements[row.index(&solution)];
+            solved.set(x, y, value);
+        }
+
+        Some(solved)
+    }
+}
+
+impl Default for Samurai {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Samurai {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        for y in 0..SIZE {
+            let width = if (9..12).contains(&y) { 15 } else { SIZE };
+
+            for x in 0..width {
+                let ch = if !Self::contains(x, y) {
+                    b' '
+                } else if self.grid[x][y] == 0 {
+                    b'.'
+                } else {
+                    self.grid[x][y] + b'0'
+                };
+                write!(f, "{}", ch as char)?;
+            }
+
+            if y < SIZE - 1 {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl str::FromStr for Samurai {
+    type Err = ParseSudokuError;
+
+    fn from_str(string: &str) -> Result<Self, ParseSudokuError> {
+        let cells = Layout::new().cells;
+        let mut samurai = Self::new();
+
+        parse_cells(string, CELLS, false, |i, value| {
+            let (x, y) = cells[i];
+            samurai.set(x, y, value);
+        })?;
+
+        Ok(samurai)
+    }
+}
+
+// Rows and columns belong to a single grid, but the corner boxes of the central grid are shared
+// with the outer grids, so each box is a single unit regardless of how many grids contain it
+struct Layout {
+    cells: Vec<(usize, usize)>,
+    cell_units: Vec<Vec<usize>>,
+    units: usize,
+}
+
+impl Layout {
+    fn new() -> Self {
+        let cells = (0..SIZE)
+            .flat_map(|y| (0..SIZE).map(move |x| (x, y)))
+            .filter(|&(x, y)| Samurai::contains(x, y))
+            .collect::<Vec<_>>();
+        debug_assert!(cells.len() == CELLS);
+
+        let mut boxes = vec![];
+        let mut cell_units = vec![vec![]; CELLS];
+
+        for (cell, &(x, y)) in cells.iter().enumerate() {
+            for (grid, &(ox, oy)) in ORIGINS.iter().enumerate() {
+                if (ox..ox + 9).contains(&x) && (oy..oy + 9).contains(&y) {
+                    cell_units[cell].push(18 * grid + (y - oy));
+                    cell_units[cell].push(18 * grid + 9 + (x - ox));
+                }
+            }
+
+            let box_id = (x / 3, y / 3);
+            let unit = match boxes.iter().position(|&other| other == box_id) {
+                Some(unit) => unit,
+                None => {
+                    boxes.push(box_id);
+                    boxes.len() - 1
+                }
+            };
+            cell_units[cell].push(18 * ORIGINS.len() + unit);
+        }
+
+        Self {
+            cells,
+            cell_units,
+            units: 18 * ORIGINS.len() + boxes.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{Rules, Samurai};
+    use core::str::FromStr;
+
+    fn validate_solution(samurai: &Samurai, solution: &Samurai) {
+        for i in 0..5 {
+            let grid = solution.grid(i);
+            assert!(grid.constraints(&Rules::default()).is_ok());
+            assert!(grid.iter().all(|(_, _, value)| value != 0));
+        }
+
+        for (x, y, value) in samurai.iter().filter(|&(_, _, value)| value != 0) {
+            assert_eq!(solution.get(x, y), value);
+        }
+    }
+
+    #[test]
+    fn test_samurai() {
+        let empty = Samurai::new();
+        let solution = empty.solve().unwrap();
+        validate_solution(&empty, &solution);
+
+        let mut samurai = Samurai::new();
+        for (x, y, value) in solution.iter().filter(|&(x, y, _)| (x + 2 * y) % 4 == 0) {
+            samurai.set(x, y, value);
+        }
+
+        let solved = samurai.solve().unwrap();
+        validate_solution(&samurai, &solved);
+
+        let parsed = Samurai::from_str(&solution.to_string()).unwrap();
+        assert!(parsed.iter().eq(solution.iter()));
+    }
+
+    #[test]
+    fn test_samurai_shared_boxes() {
+        let mut samurai = Samurai::new();
+        samurai.set(6, 6, 1);
+        samurai.set(6, 0, 1);
+        assert!(samurai.grid(0).solve().is_none());
+        assert!(samurai.solve().is_none());
+
+        let mut samurai = Samurai::new();
+        samurai.set(6, 6, 1);
+        samurai.set(14, 6, 1);
+        assert!(samurai.grid(0).solve().is_some());
+        assert!(samurai.grid(1).solve().is_some());
+        assert!(samurai.solve().is_none());
+    }
+
+    #[test]
+    fn test_samurai_parse_errors() {
+        assert!(Samurai::from_str(&".".repeat(368)).is_err());
+        assert!(Samurai::from_str(&".".repeat(370)).is_err());
+        assert!(Samurai::from_str(&".".repeat(369)).is_ok());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::sudoku::Sudoku;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Confidence {
+    // The grid has conflicting givens, or the text held more than one grid
+    Low,
+    // Row labels or a column header had to be stripped, or stray rows were skipped
+    Medium,
+    High,
+}
+
+enum Line {
+    // Borders and blank lines, which may separate rows of a grid
+    Border,
+    Row { cells: Vec<u8>, labelled: bool },
+    Other,
+}
+
+impl Sudoku {
+    // Finds a grid in free-form text such as a forum post: either 81 cells on a single line, or
+    // nine rows which may be decorated with pipes, plus signs, dashes, spaces, and row labels. Lines
+    // of prose are ignored rather than read as cells. Returns the first grid found, or None if
+    // there is none
+    pub fn scan(text: &str) -> Option<(Sudoku, Confidence)> {
+        let mut grids = vec![];
+        let mut stray = false;
+
+        let mut run: Vec<(Vec<u8>, bool)> = vec![];
+
+        for line in text.lines() {
+            for token in line.split_whitespace() {
+                if let Some(cells) = cells(token).filter(|cells| cells.len() == 81) {
+                    grids.push((cells, false));
+                }
+            }
+
+            match classify(line) {
+                Line::Border => continue,
+                Line::Row { cells, labelled } => {
+                    // A column header reads 1 to 9 above labelled rows
+                    if labelled && run.len() == 1 && !run[0].1 && run[0].0.iter().copied().eq(1..=9)
+                    {
+                        run.clear();
+                    }
+
+                    run.push((cells, labelled));
+
+                    if run.len() == 9 {
+                        let labelled = run.iter().any(|&(_, labelled)| labelled);
+                        let cells = run.drain(..).flat_map(|(cells, _)| cells).collect();
+                        grids.push((cells, labelled));
+                    }
+                }
+                Line::Other => {
+                    stray |= !run.is_empty();
+                    run.clear();
+                }
+            }
+        }
+
+        stray |= !run.is_empty();
+
+        let count = grids.len();
+        let (cells, labelled) = grids.into_iter().next()?;
+
+        let mut sudoku = Sudoku::new();
+        for (i, &value) in cells.iter().enumerate() {
+            sudoku.set(i % 9, i / 9, value);
+        }
+
+        let confidence = if count > 1 || !sudoku.is_valid() {
+            Confidence::Low
+        } else if labelled || stray {
+            Confidence::Medium
+        } else {
+            Confidence::High
+        };
+
+        Some((sudoku, confidence))
+    }
+}
+
+fn classify(line: &str) -> Line {
+    let line = line.trim();
+
+    match cells(line) {
+        Some(cells) if cells.is_empty() => return Line::Border,
+        Some(cells) if cells.len() == 9 => {
+            return Line::Row {
+                cells,
+                labelled: false,
+            }
+        }
+        _ => (),
+    }
+
+    // A short label such as "3", "C", or "r3" may precede the row, set off by a space or punctuation
+    let end = match line.find(|ch: char| ch.is_whitespace() || ":)|".contains(ch)) {
+        Some(end) => end,
+        None => return Line::Other,
+    };
+
+    let (label, rest) = line.split_at(end);
+    if label.is_empty() || label.len() > 2 || !label.chars().all(|ch| ch.is_ascii_alphanumeric()) {
+        return Line::Other;
+    }
+
+    match cells(rest.trim_start_matches(':').trim_start_matches(')')) {
+        Some(cells) if cells.len() == 9 => Line::Row {
+            cells,
+            labelled: true,
+        },
+        _ => Line::Other,
+    }
+}
+
+// The cells in a piece of text made only of digits, blanks, whitespace, and border characters
+fn cells(text: &str) -> Option<Vec<u8>> {
+    let mut cells = vec![];
+
+    for ch in text.chars() {
+        match ch {
+            '1'..='9' => cells.push(ch as u8 - b'0'),
+            '0' | '.' | '_' => cells.push(0),
+            '|' | '+' | '-' | '=' | '!' | '*' => (),
+            _ if ch.is_whitespace() => (),
+            _ => return None,
+        }
+    }
+
+    Some(cells)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{Confidence, Sudoku};
+    use core::str::FromStr;
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[test]
+    fn test_scan() {
+        let expected = Sudoku::from_str(PUZZLE).unwrap();
+
+        let post = "Here is puzzle 12 from the 2024 collection, rated 3 stars:\n\
+                    \n\
+                    +-------+-------+-------+\n\
+                    | 5 3 . | . 7 . | . . . |\n\
+                    | 6 . . | 1 9 5 | . . . |\n\
+                    | . 9 8 | . . . | . 6 . |\n\
+                    +-------+-------+-------+\n\
+                    | 8 . . | . 6 . | . . 3 |\n\
+                    | 4 . . | 8 . 3 | . . 1 |\n\
+                    | 7 . . | . 2 . | . . 6 |\n\
+                    +-------+-------+-------+\n\
+                    | . 6 . | . . . | 2 8 . |\n\
+                    | . . . | 4 1 9 | . . 5 |\n\
+                    | . . . | . 8 . | . 7 9 |\n\
+                    +-------+-------+-------+\n\
+                    \n\
+                    Took me 45 minutes.";
+        assert_eq!(
+            Sudoku::scan(post),
+            Some((expected.clone(), Confidence::High))
+        );
+
+        let inline = format!("Try this one: {} (from 2005)", PUZZLE);
+        assert_eq!(
+            Sudoku::scan(&inline),
+            Some((expected.clone(), Confidence::High))
+        );
+
+        let labelled = "   1 2 3   4 5 6   7 8 9\n\
+                        A  5 3 0 | 0 7 0 | 0 0 0\n\
+                        B  6 0 0 | 1 9 5 | 0 0 0\n\
+                        C  0 9 8 | 0 0 0 | 0 6 0\n\
+                        ---------+-------+------\n\
+                        D  8 0 0 | 0 6 0 | 0 0 3\n\
+                        E  4 0 0 | 8 0 3 | 0 0 1\n\
+                        F  7 0 0 | 0 2 0 | 0 0 6\n\
+                        ---------+-------+------\n\
+                        G  0 6 0 | 0 0 0 | 2 8 0\n\
+                        H  0 0 0 | 4 1 9 | 0 0 5\n\
+                        I  0 0 0 | 0 8 0 | 0 7 9";
+        assert_eq!(
+            Sudoku::scan(labelled),
+            Some((expected.clone(), Confidence::Medium))
+        );
+
+        let numbered = PUZZLE
+            .as_bytes()
+            .chunks(9)
+            .enumerate()
+            .map(|(i, row)| format!("{}: {}", i + 1, core::str::from_utf8(row).unwrap()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(
+            Sudoku::scan(&numbered),
+            Some((expected.clone(), Confidence::Medium))
+        );
+
+        let twice = format!("{}\n{}", PUZZLE, PUZZLE);
+        assert_eq!(Sudoku::scan(&twice), Some((expected, Confidence::Low)));
+
+        let conflicting = format!("11{}", &PUZZLE[2..]);
+        assert_eq!(Sudoku::scan(&conflicting).unwrap().1, Confidence::Low);
+
+        assert_eq!(
+            Sudoku::scan("No grid here, just 81 words and 3 numbers."),
+            None
+        );
+        assert_eq!(Sudoku::scan(""), None);
+    }
+}
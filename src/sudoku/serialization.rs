@@ -0,0 +1,163 @@
+use crate::sudoku::Sudoku;
+
+use core::fmt;
+use core::str::FromStr;
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+// Sudokus serialize as an 81-character line by default; the array module can be used with
+// #[serde(with = "...")] to store a 9x9 array of rows instead. Either form is accepted when
+// deserializing from self-describing formats; others, such as bincode, have to be told which to
+// expect
+impl Serialize for Sudoku {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_line())
+    }
+}
+
+impl<'de> Deserialize<'de> for Sudoku {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(SudokuVisitor)
+        } else {
+            deserializer.deserialize_str(SudokuVisitor)
+        }
+    }
+}
+
+struct SudokuVisitor;
+
+impl<'de> Visitor<'de> for SudokuVisitor {
+    type Value = Sudoku;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an 81-character Sudoku string or a 9x9 array of digits")
+    }
+
+    fn visit_str<E: de::Error>(self, string: &str) -> Result<Sudoku, E> {
+        Sudoku::from_str(string).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Sudoku, A::Error> {
+        let mut sudoku = Sudoku::new();
+        let mut y = 0;
+
+        while let Some(row) = seq.next_element::<[u8; 9]>()? {
+            if y == 9 {
+                return Err(de::Error::invalid_length(10, &self));
+            }
+
+            for (x, &value) in row.iter().enumerate() {
+                if value > 9 {
+                    return Err(de::Error::invalid_value(
+                        de::Unexpected::Unsigned(value as u64),
+                        &"a digit from 0 to 9",
+                    ));
+                }
+                sudoku.set(x, y, value);
+            }
+
+            y += 1;
+        }
+
+        if y < 9 {
+            return Err(de::Error::invalid_length(y, &self));
+        }
+
+        Ok(sudoku)
+    }
+}
+
+pub mod array {
+    use crate::sudoku::serialization::SudokuVisitor;
+    use crate::sudoku::Sudoku;
+
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{SerializeSeq, Serializer};
+
+    pub fn serialize<S: Serializer>(sudoku: &Sudoku, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(9))?;
+
+        for y in 0..9 {
+            let row: [u8; 9] = core::array::from_fn(|x| sudoku.get(x, y));
+            seq.serialize_element(&row)?;
+        }
+
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Sudoku, D::Error> {
+        if deserializer.is_human_readable() {
+            Sudoku::deserialize(deserializer)
+        } else {
+            deserializer.deserialize_seq(SudokuVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{serde_array, Sudoku};
+    use core::str::FromStr;
+    use serde::{Deserialize, Serialize};
+
+    const PUZZLE: &str =
+        "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79";
+
+    #[derive(Serialize, Deserialize)]
+    struct Document {
+        line: Sudoku,
+        #[serde(with = "serde_array")]
+        array: Sudoku,
+    }
+
+    #[test]
+    fn test_serde() {
+        let sudoku = Sudoku::from_str(PUZZLE).unwrap();
+
+        let json = serde_json::to_string(&sudoku).unwrap();
+        assert_eq!(json, format!("\"{}\"", PUZZLE));
+        assert_eq!(
+            serde_json::from_str::<Sudoku>(&json).unwrap().to_line(),
+            PUZZLE
+        );
+
+        let document = Document {
+            line: sudoku.clone(),
+            array: sudoku,
+        };
+        let json = serde_json::to_string(&document).unwrap();
+        assert!(json.contains("\"array\":[[5,3,0,0,7,0,0,0,0],[6,"));
+
+        let parsed = serde_json::from_str::<Document>(&json).unwrap();
+        assert_eq!(parsed.line.to_line(), PUZZLE);
+        assert_eq!(parsed.array.to_line(), PUZZLE);
+
+        let array = serde_json::to_string(&[[0u8; 9]; 9]).unwrap();
+        assert!(serde_json::from_str::<Sudoku>(&array).is_ok());
+        assert!(serde_json::from_str::<Sudoku>(&array.replacen('0', "10", 1)).is_err());
+        assert!(serde_json::from_str::<Sudoku>("[[0,0,0,0,0,0,0,0,0]]").is_err());
+        assert!(serde_json::from_str::<Sudoku>("\"123\"").is_err());
+    }
+
+    #[test]
+    fn test_serde_bincode() {
+        let sudoku = Sudoku::from_str(PUZZLE).unwrap();
+
+        let bytes = bincode::serialize(&sudoku).unwrap();
+        let parsed = bincode::deserialize::<Sudoku>(&bytes).unwrap();
+        assert_eq!(parsed.to_line(), PUZZLE);
+
+        let document = Document {
+            line: sudoku.clone(),
+            array: sudoku,
+        };
+        let bytes = bincode::serialize(&document).unwrap();
+        let parsed = bincode::deserialize::<Document>(&bytes).unwrap();
+        assert_eq!(parsed.line.to_line(), PUZZLE);
+        assert_eq!(parsed.array.to_line(), PUZZLE);
+
+        let bytes = bincode::serialize("123").unwrap();
+        assert!(bincode::deserialize::<Sudoku>(&bytes).is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use crate::sudoku::{Hint, Sudoku};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+struct Edit {
+    x: usize,
+    y: usize,
+    before: u8,
+    after: u8,
+}
+
+// Every undoable action is a batch of cell edits, so that restoring a snapshot undoes in one step
+#[derive(Clone, Debug, Default)]
+pub struct SudokuSession {
+    sudoku: Sudoku,
+    undo: Vec<Vec<Edit>>,
+    redo: Vec<Vec<Edit>>,
+}
+
+impl SudokuSession {
+    pub fn new(sudoku: Sudoku) -> Self {
+        Self {
+            sudoku,
+            undo: vec![],
+            redo: vec![],
+        }
+    }
+
+    pub fn sudoku(&self) -> &Sudoku {
+        &self.sudoku
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: u8) {
+        assert!(value <= 9);
+
+        let before = self.sudoku.get(x, y);
+        self.record(vec![Edit {
+            x,
+            y,
+            before,
+            after: value,
+        }]);
+    }
+
+    pub fn clear(&mut self, x: usize, y: usize) {
+        self.set(x, y, 0);
+    }
+
+    pub fn apply_hint(&mut self) -> Option<Hint> {
+        let hint = self.sudoku.hint()?;
+        self.set(hint.x, hint.y, hint.value);
+        Some(hint)
+    }
+
+    pub fn snapshot(&self) -> Sudoku {
+        self.sudoku.clone()
+    }
+
+    pub fn restore(&mut self, snapshot: &Sudoku) {
+        let edits = self
+            .sudoku
+            .iter()
+            .zip(snapshot.iter())
+            .filter(|&((_, _, before), (_, _, after))| before != after)
+            .map(|((x, y, before), (_, _, after))| Edit {
+                x,
+                y,
+                before,
+                after,
+            })
+            .collect();
+
+        self.record(edits);
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let edits = match self.undo.pop() {
+            Some(edits) => edits,
+            None => return false,
+        };
+
+        for edit in edits.iter().rev() {
+            self.sudoku.set(edit.x, edit.y, edit.before);
+        }
+
+        self.redo.push(edits);
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let edits = match self.redo.pop() {
+            Some(edits) => edits,
+            None => return false,
+        };
+
+        for edit in &edits {
+            self.sudoku.set(edit.x, edit.y, edit.after);
+        }
+
+        self.undo.push(edits);
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // No-op edits are dropped so they don't clutter the history or discard the redo stack
+    fn record(&mut self, edits: Vec<Edit>) {
+        let edits = edits
+            .into_iter()
+            .filter(|edit| edit.before != edit.after)
+            .collect::<Vec<_>>();
+
+        if edits.is_empty() {
+            return;
+        }
+
+        for edit in &edits {
+            self.sudoku.set(edit.x, edit.y, edit.after);
+        }
+
+        self.undo.push(edits);
+        self.redo.clear();
+    }
+}
+
+impl From<Sudoku> for SudokuSession {
+    fn from(sudoku: Sudoku) -> Self {
+        Self::new(sudoku)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{Sudoku, SudokuSession};
+    use core::str::FromStr;
+
+    #[test]
+    fn test_session() {
+        let puzzle = Sudoku::from_str(
+            "53..7....6..195....98....6.8...6...34..8.3..17...2...6.6....28....419..5....8..79",
+        )
+        .unwrap();
+        let mut session = SudokuSession::new(puzzle.clone());
+        assert!(!session.can_undo() && !session.can_redo());
+
+        session.set(2, 0, 4);
+        session.set(3, 0, 6);
+        session.set(3, 0, 6);
+        assert_eq!(session.sudoku().get(3, 0), 6);
+
+        assert!(session.undo());
+        assert_eq!(session.sudoku().get(3, 0), 0);
+        assert_eq!(session.sudoku().get(2, 0), 4);
+        assert!(session.undo());
+        assert_eq!(session.sudoku(), &puzzle);
+        assert!(!session.undo());
+
+        assert!(session.redo());
+        assert!(session.redo());
+        assert!(!session.redo());
+        assert_eq!(session.sudoku().get(3, 0), 6);
+
+        let snapshot = session.snapshot();
+        session.clear(2, 0);
+        session.undo();
+        session.clear(3, 0);
+        assert!(!session.can_redo());
+
+        let hint = session.apply_hint().unwrap();
+        assert_eq!(session.sudoku().get(hint.x, hint.y), hint.value);
+
+        session.restore(&snapshot);
+        assert_eq!(session.sudoku(), &snapshot);
+        assert!(session.undo());
+        assert_eq!(session.sudoku().get(hint.x, hint.y), hint.value);
+        assert_eq!(session.sudoku().get(3, 0), 0);
+    }
+}
@@ -0,0 +1,168 @@
+use crate::sudoku::{parse_cells, CandidateIterator, ParseSudokuError, Rules, Sudoku};
+
+use core::fmt;
+use core::str;
+
+#[derive(Debug)]
+pub struct Sukaku {
+    candidates: [[u16; 9]; 9],
+}
+
+impl Sukaku {
+    pub fn new() -> Self {
+        Self {
+            candidates: [[(1 << 9) - 1; 9]; 9],
+        }
+    }
+
+    pub fn candidates(&self, x: usize, y: usize) -> impl Iterator<Item = u8> {
+        CandidateIterator(self.candidates[x][y])
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u16 {
+        self.candidates[x][y]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, candidates: u16) {
+        assert!(candidates < (1 << 9));
+        self.candidates[x][y] = candidates;
+    }
+
+    pub fn allow(&mut self, x: usize, y: usize, value: u8) {
+        assert!((1..=9).contains(&value));
+        self.candidates[x][y] |= 1 << (value - 1);
+    }
+
+    pub fn forbid(&mut self, x: usize, y: usize, value: u8) {
+        assert!((1..=9).contains(&value));
+        self.candidates[x][y] &= !(1 << (value - 1));
+    }
+
+    pub fn solve(&self) -> Option<Sudoku> {
+        self.solve_with(&Rules::default())
+    }
+
+    pub fn solve_with(&self, rules: &Rules) -> Option<Sudoku> {
+        let empty = Sudoku::new();
+        let mut constraints = empty.constraints(rules).ok()?;
+
+        for x in 0..9 {
+            for y in 0..9 {
+                constraints.restrict(x, y, self.candidates[x][y]);
+            }
+        }
+
+        empty
+            .encode(&constraints, rules, 0, |_, _, _, _| ())
+            .solve()
+    }
+}
+
+impl Default for Sukaku {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&Sudoku> for Sukaku {
+    fn from(sudoku: &Sudoku) -> Self {
+        let mut sukaku = Self::new();
+
+        for (x, y, value) in sudoku.iter().filter(|&(_, _, value)| value != 0) {
+            sukaku.set(x, y, 1 << (value - 1));
+        }
+
+        sukaku
+    }
+}
+
+impl fmt::Display for Sukaku {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        for y in 0..9 {
+            for x in 0..9 {
+                for value in 1..=9 {
+                    let ch = if self.candidates[x][y] & (1 << (value - 1)) == 0 {
+                        b'.'
+                    } else {
+                        value + b'0'
+                    };
+                    write!(f, "{}", ch as char)?;
+                }
+            }
+
+            if y < 8 {
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl str::FromStr for Sukaku {
+    type Err = ParseSudokuError;
+
+    fn from_str(string: &str) -> Result<Self, ParseSudokuError> {
+        let mut candidates = [[0; 9]; 9];
+
+        parse_cells(string, 729, false, |i, value| {
+            let cell = i / 9;
+            candidates[cell % 9][cell / 9] |= 1 << (value - 1);
+        })?;
+
+        Ok(Self { candidates })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::{Sudoku, Sukaku};
+    use core::str::FromStr;
+
+    const PUZZLE: &str =
+        "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........";
+
+    #[test]
+    fn test_sukaku() {
+        let sudoku = Sudoku::from_str(PUZZLE).unwrap();
+        let solution = sudoku.solve().unwrap();
+
+        let mut sukaku = Sukaku::from(&sudoku);
+        assert_eq!(sukaku.solve().unwrap().to_string(), solution.to_string());
+
+        for (x, y, value) in solution.iter().filter(|&(x, y, _)| (x + y) % 2 == 0) {
+            sukaku.set(x, y, 0);
+            sukaku.allow(x, y, value);
+            sukaku.allow(x, y, value % 9 + 1);
+        }
+        assert_eq!(sukaku.solve().unwrap().to_string(), solution.to_string());
+
+        let (x, y) = (1, 0);
+        sukaku.forbid(x, y, solution.get(x, y));
+        assert!(sukaku.solve().is_none());
+    }
+
+    #[test]
+    fn test_sukaku_parse() {
+        let sukaku = Sukaku::from(&Sudoku::from_str(PUZZLE).unwrap());
+        let string = sukaku.to_string();
+        assert_eq!(string.lines().map(str::len).sum::<usize>(), 729);
+
+        let parsed = Sukaku::from_str(&string).unwrap();
+        for x in 0..9 {
+            for y in 0..9 {
+                assert_eq!(parsed.get(x, y), sukaku.get(x, y));
+            }
+        }
+
+        assert!(Sukaku::from_str(&"123456789".repeat(80)).is_err());
+        assert!(Sukaku::from_str(&"123456789".repeat(81))
+            .unwrap()
+            .solve()
+            .is_some());
+        assert!(Sukaku::from_str(&".".repeat(729))
+            .unwrap()
+            .solve()
+            .is_none());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::cover::Problem;
+use crate::rng::Rng;
+
+// Random exact cover problems, for testing and benchmarking encoders and solvers. Every option
+// outside a planted solution includes each primary item with probability given by the density,
+// and likewise each secondary item
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Generator {
+    primary_items: usize,
+    secondary_items: usize,
+    options: usize,
+    density: f64,
+}
+
+impl Generator {
+    pub fn new(primary_items: usize, options: usize) -> Self {
+        assert!(primary_items > 0, "There must be at least one primary item");
+        Self {
+            primary_items,
+            secondary_items: 0,
+            options,
+            density: 0.25,
+        }
+    }
+
+    pub fn secondary_items(mut self, secondary_items: usize) -> Self {
+        self.secondary_items = secondary_items;
+        self
+    }
+
+    pub fn density(mut self, density: f64) -> Self {
+        assert!(
+            density > 0.0 && density <= 1.0,
+            "Density must be in the range (0, 1] (got {})",
+            density
+        );
+        self.density = density;
+        self
+    }
+
+    // A problem with at least one solution, along with the indices of the options in a solution
+    // that was planted among them, in increasing order. The planted options split the primary
+    // items into pieces of about the size the density gives, and the rest are filled in at random
+    // up to the number of options asked for. There may be more options than that when the planted
+    // solution needs them
+    pub fn satisfiable(&self, rng: &mut impl Rng) -> (Problem, Vec<usize>) {
+        let mut items: Vec<usize> = (0..self.primary_items).collect();
+        rng.shuffle(&mut items);
+
+        let mut planted = vec![];
+        let mut rest = &items[..];
+        while !rest.is_empty() {
+            let size = (1 + self.binomial(rng, self.primary_items - 1)).min(rest.len());
+            planted.push(rest[..size].to_vec());
+            rest = &rest[size..];
+        }
+
+        // Each secondary item is in at most one planted option
+        for item in self.secondary_range() {
+            if self.chance(rng) {
+                let option = rng.below(planted.len());
+                planted[option].push(item);
+            }
+        }
+
+        let mut options: Vec<(Vec<usize>, bool)> =
+            planted.into_iter().map(|option| (option, true)).collect();
+        while options.len() < self.options {
+            options.push((self.option(rng, false), false));
+        }
+        rng.shuffle(&mut options);
+
+        let solution = (0..options.len()).filter(|&i| options[i].1).collect();
+        let options = options.into_iter().map(|(option, _)| option).collect();
+        (self.problem(options), solution)
+    }
+
+    // A problem with no solutions. There are an odd number of primary items but every option has
+    // an even number of them, so no choice of options can cover them all exactly once, though a
+    // solver has no easy way of telling
+    pub fn unsatisfiable(&self, rng: &mut impl Rng) -> Problem {
+        assert!(
+            self.primary_items % 2 == 1 && self.primary_items > 1,
+            "Unsatisfiable problems need an odd number of primary items, and at least three"
+        );
+
+        let options = (0..self.options).map(|_| self.option(rng, true)).collect();
+        self.problem(options)
+    }
+
+    fn option(&self, rng: &mut impl Rng, even: bool) -> Vec<usize> {
+        let n = self.primary_items;
+        let mut option: Vec<usize> = (0..n).filter(|_| self.chance(rng)).collect();
+
+        // Adding or removing any one item evens up the count
+        if even && option.len() % 2 == 1 {
+            let item = rng.below(n);
+            match option.iter().position(|&other| other == item) {
+                Some(i) => {
+                    option.remove(i);
+                }
+                None => option.push(item),
+            }
+        }
+
+        if option.is_empty() {
+            let item = rng.below(n);
+            option.push(item);
+            if even {
+                option.push((item + 1 + rng.below(n - 1)) % n);
+            }
+        }
+
+        option.extend(self.secondary_range().filter(|_| self.chance(rng)));
+        option
+    }
+
+    fn secondary_range(&self) -> core::ops::Range<usize> {
+        self.primary_items..self.primary_items + self.secondary_items
+    }
+
+    fn chance(&self, rng: &mut impl Rng) -> bool {
+        ((rng.next_u64() >> 11) as f64) < self.density * (1u64 << 53) as f64
+    }
+
+    fn binomial(&self, rng: &mut impl Rng, trials: usize) -> usize {
+        (0..trials).filter(|_| self.chance(rng)).count()
+    }
+
+    fn problem(&self, options: Vec<Vec<usize>>) -> Problem {
+        Problem::new(
+            self.primary_items + self.secondary_items,
+            self.primary_items,
+            options,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rng::SplitMix64;
+    use crate::testgen::Generator;
+
+    #[test]
+    fn test_satisfiable() {
+        let mut rng = SplitMix64::new(0);
+
+        for i in 0..20 {
+            let generator = Generator::new(10 + i, 40)
+                .secondary_items(i % 4)
+                .density(0.2);
+            let (problem, solution) = generator.satisfiable(&mut rng);
+
+            assert_eq!(problem.items().len(), 10 + i + i % 4);
+            assert!(problem.options().len() >= 40);
+
+            let mut covered = vec![0; problem.items().len()];
+            for &option in &solution {
+                for &item in &problem.options()[option] {
+                    covered[item] += 1;
+                }
+            }
+            assert!(covered[..problem.primary_items()]
+                .iter()
+                .all(|&count| count == 1));
+            assert!(covered[problem.primary_items()..]
+                .iter()
+                .all(|&count| count <= 1));
+
+            assert!(problem.solutions().any(|found| found == solution));
+        }
+    }
+
+    #[test]
+    fn test_unsatisfiable() {
+        let mut rng = SplitMix64::new(1);
+
+        for density in [0.1, 0.3, 0.6, 1.0] {
+            let problem = Generator::new(15, 60)
+                .secondary_items(3)
+                .density(density)
+                .unsatisfiable(&mut rng);
+
+            assert_eq!(problem.options().len(), 60);
+            for option in problem.options() {
+                let primary = option.iter().filter(|&&item| item < 15).count();
+                assert!(primary > 0 && primary % 2 == 0);
+            }
+            assert_eq!(problem.count_solutions(usize::MAX), 0);
+        }
+    }
+}
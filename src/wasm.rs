@@ -0,0 +1,59 @@
+// wasm-bindgen wrappers for the browser, enabled by the wasm feature. The crate only builds an
+// rlib by default, so the module is built as a cdylib and then run through wasm-bindgen:
+//
+//     cargo rustc --release --lib --target wasm32-unknown-unknown --no-default-features \
+//         --features wasm --crate-type cdylib
+//     wasm-bindgen --target web --out-dir pkg target/wasm32-unknown-unknown/release/lynx.wasm
+//
+// and then from JavaScript:
+//
+//     import init, { generate, solve } from "./pkg/lynx.js";
+//     await init();
+//     const puzzle = generate(BigInt(Date.now()));
+//     console.log(solve(puzzle));
+//
+// Grids go both ways as 81-character lines. There's no clock or source of entropy on
+// wasm32-unknown-unknown, so the seed has to come from JavaScript
+use crate::rng::SplitMix64;
+use crate::sudoku::Sudoku;
+
+use wasm_bindgen::prelude::*;
+
+// The solution as a line, or undefined if there is none. Throws if the puzzle can't be read
+#[wasm_bindgen]
+pub fn solve(puzzle: &str) -> Result<Option<String>, JsError> {
+    let sudoku = puzzle
+        .parse::<Sudoku>()
+        .map_err(|error| JsError::new(&error.to_string()))?;
+    Ok(sudoku.solve().map(|solved| solved.to_line()))
+}
+
+// A minimal puzzle with a unique solution, the same way lynx-sudoku generate makes them
+#[wasm_bindgen]
+pub fn generate(seed: u64) -> String {
+    let mut rng = SplitMix64::new(seed);
+    let solution = Sudoku::random_filled(&mut rng);
+    solution
+        .minimize(&mut rng)
+        .expect("filled grids have a unique solution")
+        .to_line()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sudoku::Sudoku;
+    use crate::wasm::{generate, solve};
+
+    #[test]
+    fn test_generate_and_solve() {
+        let puzzle = generate(3);
+        assert_eq!(puzzle, generate(3));
+
+        let sudoku: Sudoku = puzzle.parse().unwrap();
+        assert!(sudoku.has_unique_solution());
+
+        let solved: Sudoku = solve(&puzzle).ok().flatten().unwrap().parse().unwrap();
+        assert!(solved.is_solved());
+        assert_eq!(Some(solved), sudoku.solve());
+    }
+}
@@ -0,0 +1,174 @@
+use crate::json::solution_to_json;
+
+use std::io;
+use std::io::Write;
+
+// Sinks for solutions as they're enumerated, each written out in full before the next is found,
+// so that enumerations far too large to collect can still be saved. A solution is given by the
+// indices of its rows, as from row_indices, and is written in increasing order. Writing goes
+// straight through to the inner writer, so it's worth wrapping files and standard output in an
+// io::BufWriter
+pub trait SolutionWriter {
+    fn write_solution(&mut self, rows: &[usize]) -> io::Result<()>;
+
+    // Writes every solution, stopping at the first error, and returns how many were written
+    fn write_solutions<I>(&mut self, solutions: I) -> io::Result<usize>
+    where
+        I: IntoIterator<Item = Vec<usize>>,
+    {
+        let mut count = 0;
+        for solution in solutions {
+            self.write_solution(&solution)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
+
+// One record per solution, with a field for each of its rows: the row's label, if it has one, and
+// otherwise its index
+pub struct CsvWriter<W> {
+    inner: W,
+    labels: Vec<Option<String>>,
+    rows: Vec<usize>,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self::with_labels(inner, vec![])
+    }
+
+    // Labels are given to rows in order, as from DLXMatrix::from_json
+    pub fn with_labels(inner: W, labels: Vec<Option<String>>) -> Self {
+        Self {
+            inner,
+            labels,
+            rows: vec![],
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> SolutionWriter for CsvWriter<W> {
+    fn write_solution(&mut self, rows: &[usize]) -> io::Result<()> {
+        self.rows.clear();
+        self.rows.extend_from_slice(rows);
+        self.rows.sort_unstable();
+
+        for (i, &row) in self.rows.iter().enumerate() {
+            if i > 0 {
+                write!(self.inner, ",")?;
+            }
+            match self.labels.get(row) {
+                Some(Some(label)) => write!(self.inner, "{}", csv_field(label))?,
+                _ => write!(self.inner, "{}", row)?,
+            }
+        }
+
+        writeln!(self.inner)
+    }
+}
+
+// One JSON object per line, in the solution format of the json module
+pub struct JsonLinesWriter<W> {
+    inner: W,
+    labels: Vec<Option<String>>,
+    rows: Vec<usize>,
+}
+
+impl<W: Write> JsonLinesWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self::with_labels(inner, vec![])
+    }
+
+    pub fn with_labels(inner: W, labels: Vec<Option<String>>) -> Self {
+        Self {
+            inner,
+            labels,
+            rows: vec![],
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> SolutionWriter for JsonLinesWriter<W> {
+    fn write_solution(&mut self, rows: &[usize]) -> io::Result<()> {
+        self.rows.clear();
+        self.rows.extend_from_slice(rows);
+        self.rows.sort_unstable();
+
+        writeln!(self.inner, "{}", solution_to_json(&self.rows, &self.labels))
+    }
+}
+
+// Quotes the field only if it contains a separator, quote, or line break
+pub fn csv_field(string: &str) -> String {
+    if string.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", string.replace('"', "\"\""))
+    } else {
+        String::from(string)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::json::solution_from_json;
+    use crate::writer::{CsvWriter, JsonLinesWriter, SolutionWriter};
+    use crate::DLXMatrix;
+
+    fn matrix() -> DLXMatrix<u32> {
+        let mut matrix = DLXMatrix::with_secondary_columns(3, 1);
+        for row in [[0, 3], [1, 2], [0, 1], [2, 3]] {
+            matrix.push_row(&row);
+        }
+        matrix
+    }
+
+    #[test]
+    fn test_csv() {
+        let mut writer = CsvWriter::new(vec![]);
+        let count = writer
+            .write_solutions(matrix().solutions().row_indices())
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        let mut lines: Vec<&str> = output.lines().collect();
+        lines.sort_unstable();
+        assert_eq!(lines, vec!["0,1", "2,3"]);
+
+        let labels = vec![Some(String::from("a,b")), None, Some(String::from("\"c\""))];
+        let mut writer = CsvWriter::with_labels(vec![], labels);
+        writer.write_solution(&[2, 0, 1]).unwrap();
+        assert_eq!(writer.into_inner(), b"\"a,b\",1,\"\"\"c\"\"\"\n");
+    }
+
+    #[test]
+    fn test_json_lines() {
+        let labels = vec![None, Some(String::from("b"))];
+        let mut writer = JsonLinesWriter::with_labels(vec![], labels);
+        let count = writer
+            .write_solutions(matrix().solutions().row_indices())
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        let mut solutions: Vec<Vec<usize>> = output
+            .lines()
+            .map(|line| solution_from_json(line).unwrap())
+            .collect();
+        solutions.sort_unstable();
+        assert_eq!(solutions, vec![vec![0, 1], vec![2, 3]]);
+        assert!(output.contains(r#"{"rows":[0,1],"labels":[null,"b"]}"#));
+
+        let mut writer = JsonLinesWriter::new(vec![]);
+        writer.write_solution(&[3]).unwrap();
+        assert_eq!(writer.into_inner(), b"{\"rows\":[3]}\n");
+    }
+}